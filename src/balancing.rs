@@ -0,0 +1,119 @@
+use crate::pool::Pool;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// Strategy used to pick the socket of each request when several sockets are configured with [crate::ClientUnixBuilder::add_socket_path].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadBalancing {
+    /// Use each socket in turn.
+    #[default]
+    RoundRobin,
+    /// Use the socket with the fewest requests in flight, ties are broken in turn.
+    LeastInFlight,
+}
+
+/// Pick the [Pool] of each request according to a [LoadBalancing] strategy.
+#[derive(Debug)]
+pub(crate) struct Balancer {
+    strategy: LoadBalancing,
+    next: AtomicUsize,
+}
+
+impl Balancer {
+    pub(crate) fn new(strategy: LoadBalancing) -> Self {
+        Balancer {
+            strategy,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn select<'a>(&self, pools: &'a [Arc<Pool>]) -> &'a Arc<Pool> {
+        if pools.len() == 1 {
+            return &pools[0];
+        }
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % pools.len();
+
+        match self.strategy {
+            LoadBalancing::RoundRobin => &pools[start],
+            LoadBalancing::LeastInFlight => pools[start..]
+                .iter()
+                .chain(&pools[..start])
+                .min_by_key(|pool| pool.in_flight())
+                .unwrap_or(&pools[start]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, LoadBalancing, Method,
+        test_helpers::{raw_server::RawServer, util::make_socket_path_test},
+    };
+
+    async fn make_servers(test_function: &str) -> (RawServer, RawServer, String, String) {
+        let socket_path_a = make_socket_path_test("balancing", &format!("{}_a", test_function));
+        let socket_path_b = make_socket_path_test("balancing", &format!("{}_b", test_function));
+        let server_a = RawServer::try_new(
+            &socket_path_a,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 1\r\n\r\na",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let server_b = RawServer::try_new(
+            &socket_path_b,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 1\r\n\r\nb",
+        )
+        .await
+        .expect("RawServer::try_new");
+        (server_a, server_b, socket_path_a, socket_path_b)
+    }
+
+    #[tokio::test]
+    async fn round_robin() {
+        let (_server_a, _server_b, socket_path_a, socket_path_b) =
+            make_servers("round_robin").await;
+        let client = ClientUnix::builder(&socket_path_a)
+            .add_socket_path(&socket_path_b)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let mut responses = Vec::new();
+        for _ in 0..4 {
+            let (_, response) = client
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            responses.push(response);
+        }
+        assert_eq!(responses, vec![b"a", b"b", b"a", b"b"]);
+    }
+
+    #[tokio::test]
+    async fn least_in_flight() {
+        let (server_a, server_b, socket_path_a, socket_path_b) =
+            make_servers("least_in_flight").await;
+        let client = ClientUnix::builder(&socket_path_a)
+            .add_socket_path(&socket_path_b)
+            .load_balancing(LoadBalancing::LeastInFlight)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let responses = futures_util::future::join_all(
+            (0..4).map(|_| client.send_request("/nolanv", Method::GET, &[], None)),
+        )
+        .await;
+
+        let responses: Vec<Vec<u8>> = responses
+            .into_iter()
+            .map(|response| response.expect("client.send_request").1)
+            .collect();
+        assert_eq!(responses.iter().filter(|r| r.as_slice() == b"a").count(), 2);
+        assert_eq!(server_a.connections(), 2);
+        assert_eq!(server_b.connections(), 2);
+    }
+}
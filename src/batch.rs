@@ -0,0 +1,178 @@
+use crate::Body;
+#[cfg(feature = "json")]
+use crate::error::ErrorAndResponseJson;
+use crate::{ClientUnix, error::ErrorAndResponse};
+use futures_util::{StreamExt, stream};
+use hyper::{Method, StatusCode, http::Extensions};
+#[cfg(feature = "json")]
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Description of a request sent by [ClientUnix::send_requests] (or [ClientUnix::send_requests_json]).
+///
+/// `B` is the body type, [Body] for raw requests or the serializable type for JSON requests.
+#[derive(Debug)]
+pub struct RequestDescription<B = Body> {
+    pub endpoint: String,
+    pub method: Method,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<B>,
+    pub extensions: Extensions,
+}
+
+impl<B> RequestDescription<B> {
+    pub fn new(endpoint: impl Into<String>, method: Method) -> Self {
+        RequestDescription {
+            endpoint: endpoint.into(),
+            method,
+            headers: Vec::new(),
+            body: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: B) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Attach a typed value to the request, carried in the extensions of the sent HTTP request and echoed back in the [crate::Response] extensions by [ClientUnix::send_description].
+    pub fn extension<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    pub(crate) fn headers(&self) -> Vec<(&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+}
+
+impl ClientUnix {
+    /// Send many raw HTTP requests concurrently.
+    ///
+    /// At most `concurrency` requests are in flight at the same time, they are spread over the pooled connections (see [crate::ClientUnixBuilder::max_connections]). The results are returned in the same order as the requests.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method, RequestDescription};
+    ///
+    /// pub async fn get_many() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let results = client
+    ///         .send_requests(
+    ///             (0..10).map(|i| RequestDescription::new(format!("/nolanv{}", i), Method::GET)),
+    ///             4,
+    ///         )
+    ///         .await;
+    ///
+    ///     for result in results {
+    ///         let (_, response) = result.expect("client.send_requests");
+    ///         println!("{:?}", response);
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_requests(
+        &self,
+        requests: impl IntoIterator<Item = RequestDescription>,
+        concurrency: usize,
+    ) -> Vec<Result<(StatusCode, Vec<u8>), ErrorAndResponse>> {
+        stream::iter(requests)
+            .map(|mut request| async move {
+                let body = request.body.take();
+                self.send_request(
+                    &request.endpoint,
+                    request.method.clone(),
+                    &request.headers(),
+                    body,
+                )
+                .await
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Send many JSON HTTP requests concurrently **(feature = json)**
+    ///
+    /// Same as [ClientUnix::send_requests] using [ClientUnix::send_request_json] for each request.
+    #[cfg(feature = "json")]
+    pub async fn send_requests_json<IN: Serialize, OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        requests: impl IntoIterator<Item = RequestDescription<IN>>,
+        concurrency: usize,
+    ) -> Vec<Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>>> {
+        stream::iter(requests)
+            .map(|request| async move {
+                self.send_request_json::<IN, OUT, ERR>(
+                    &request.endpoint,
+                    request.method.clone(),
+                    &request.headers(),
+                    request.body.as_ref(),
+                )
+                .await
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::util::make_client_server;
+
+    #[tokio::test]
+    async fn send_requests() {
+        let (_, client) = make_client_server("send_requests").await;
+
+        let results = client
+            .send_requests(
+                (0..20).map(|i| RequestDescription::new(format!("/nolanv{}", i), Method::GET)),
+                4,
+            )
+            .await;
+
+        assert_eq!(results.len(), 20);
+        for (i, result) in results.into_iter().enumerate() {
+            let (status_code, response) = result.expect("client.send_requests");
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(response, format!("Hello nolanv{}", i).as_bytes());
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn send_requests_json() {
+        use serde_json::{Value, json};
+
+        let (_, client) = make_client_server("send_requests_json").await;
+
+        let results = client
+            .send_requests_json::<Value, Value, Value>(
+                ["nolanv", "nolanv2", "nolanv3"].map(|name| {
+                    RequestDescription::new("/json", Method::POST).body(json!({ "name": name }))
+                }),
+                2,
+            )
+            .await;
+
+        let hellos: Vec<Value> = results
+            .into_iter()
+            .map(|result| result.expect("client.send_requests_json").1["hello"].clone())
+            .collect();
+        assert_eq!(
+            hellos,
+            vec![json!("nolanv"), json!("nolanv2"), json!("nolanv3")]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! `uds-http`, a small curl for the HTTP servers listening on a unix socket **(feature = cli)**
+//!
+//! ```text
+//! uds-http /var/run/docker.sock /containers/json
+//! uds-http -i -H 'X-Nolan: v' /tmp/unix.socket /nolanv
+//! uds-http --json '{"name": "nolanv"}' /tmp/unix.socket /nolanv
+//! ```
+use clap::Parser;
+use http_client_unix_domain_socket::{Body, ClientUnix, Method, OriginalHeaders, Response};
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+/// Send an HTTP request to a server listening on a unix socket and print its response.
+///
+/// The exit code is 1 when the response is not successful, 2 when no response was received.
+#[derive(Debug, Parser)]
+#[command(name = "uds-http", version)]
+struct Args {
+    /// Path of the unix socket.
+    socket_path: PathBuf,
+    /// Endpoint of the request, like `/containers/json?all=true`.
+    endpoint: String,
+    /// Method of the request. Default is POST with a body, GET otherwise.
+    #[arg(short = 'X', long)]
+    method: Option<Method>,
+    /// Header of the request, as `Name: value`. Can be repeated.
+    #[arg(short = 'H', long = "header", value_parser = parse_header)]
+    headers: Vec<(String, String)>,
+    /// Raw body of the request, `@path` reads it from a file and `@-` from stdin.
+    #[arg(short, long, conflicts_with = "json")]
+    data: Option<String>,
+    /// JSON body of the request, sent with `Content-Type: application/json`. `@path` reads it from a file and `@-` from stdin.
+    #[arg(short, long)]
+    json: Option<String>,
+    /// Print the status line and the response headers before the body.
+    #[arg(short, long)]
+    include: bool,
+    /// Print the JSON responses as received instead of pretty-printing them.
+    #[arg(long)]
+    raw: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Args::parse(), &mut std::io::stdout()).await {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            eprintln!("uds-http: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+async fn run(args: Args, out: &mut impl Write) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut headers = args.headers;
+    let body = match (args.data, args.json) {
+        (Some(data), _) => Some(read_body(&data)?),
+        (None, Some(json)) => {
+            let json = read_body(&json)?;
+            serde_json::from_slice::<serde_json::Value>(&json)
+                .map_err(|e| format!("Invalid JSON body, {}", e))?;
+            if !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            {
+                headers.push(("Content-Type".into(), "application/json".into()));
+            }
+            Some(json)
+        }
+        (None, None) => None,
+    };
+    let method = args.method.unwrap_or(match body {
+        Some(_) => Method::POST,
+        None => Method::GET,
+    });
+
+    let client = ClientUnix::builder(&args.socket_path)
+        .preserve_header_case(args.include)
+        .try_build()
+        .await?;
+    let headers: Vec<(&str, &str)> = headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    let response = client
+        .send_request_unchecked(&args.endpoint, method, &headers, body.map(Body::from))
+        .await?;
+
+    if args.include {
+        write_head(out, &response)?;
+    }
+    let status_code = response.status_code();
+    let response = response.into_body();
+    match serde_json::from_slice::<serde_json::Value>(&response) {
+        Ok(json) if !args.raw => writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?,
+        _ => out.write_all(&response)?,
+    }
+    out.flush()?;
+
+    Ok(match status_code.is_success() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    })
+}
+
+/// Write the status line and the headers of the response, as written by the server when they were recorded.
+fn write_head(out: &mut impl Write, response: &Response<Vec<u8>>) -> std::io::Result<()> {
+    writeln!(out, "{:?} {}", response.version(), response.status_code())?;
+    let headers: Vec<_> = match response.extensions().get::<OriginalHeaders>() {
+        Some(headers) => headers.iter().collect(),
+        None => response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+            .collect(),
+    };
+    for (name, value) in headers {
+        writeln!(
+            out,
+            "{}: {}",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        )?;
+    }
+    writeln!(out)
+}
+
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| format!("Header {:?} must be `Name: value`", header))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
+fn read_body(argument: &str) -> std::io::Result<Vec<u8>> {
+    match argument.strip_prefix('@') {
+        Some("-") => {
+            let mut body = Vec::new();
+            std::io::stdin().read_to_end(&mut body)?;
+            Ok(body)
+        }
+        Some(path) => std::fs::read(path),
+        None => Ok(argument.as_bytes().to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_arguments() {
+        let args = Args::try_parse_from([
+            "uds-http",
+            "-X",
+            "PUT",
+            "-H",
+            "X-Nolan:  v ",
+            "--json",
+            "{}",
+            "/tmp/unix.socket",
+            "/nolanv",
+        ])
+        .expect("Args::try_parse_from");
+        assert_eq!(args.method, Some(Method::PUT));
+        assert_eq!(args.headers, vec![("X-Nolan".to_string(), "v".to_string())]);
+        assert_eq!(args.json.as_deref(), Some("{}"));
+
+        assert!(parse_header("X-Nolan").is_err());
+        assert!(Args::try_parse_from(["uds-http", "-d", "a", "-j", "{}", "/tmp/s", "/"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn include_unsuccessful_head() {
+        let socket_path =
+            std::env::temp_dir().join(format!("uds-http-{}.socket", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("UnixListener::bind");
+        tokio::task::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut stream, _) = listener.accept().await.expect("listener.accept");
+            let mut buffer = [0; 4096];
+            let _ = stream.read(&mut buffer).await;
+            stream
+                .write_all(b"HTTP/1.0 404 Not Found\r\nX-Reason: nolanv\r\ncontent-length: 7\r\n\r\nmissing")
+                .await
+                .expect("stream.write_all");
+        });
+
+        let args = Args::try_parse_from([
+            "uds-http",
+            "-i",
+            socket_path.to_str().expect("socket path"),
+            "/nolanv",
+        ])
+        .expect("Args::try_parse_from");
+        let mut out = Vec::new();
+        let exit_code = run(args, &mut out).await.expect("run");
+        let _ = std::fs::remove_file(&socket_path);
+
+        assert_eq!(exit_code, ExitCode::FAILURE);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "HTTP/1.0 404 Not Found\nX-Reason: nolanv\ncontent-length: 7\n\nmissing"
+        );
+    }
+}
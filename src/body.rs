@@ -0,0 +1,175 @@
+use crate::{Error, connection::InFlightGuard, pool::PooledConnection, priority::PriorityPermit};
+use http_body_util::{BodyExt, Empty, Full, combinators::UnsyncBoxBody};
+use hyper::{
+    HeaderMap,
+    body::{Body as HttpBody, Bytes, Frame, Incoming, SizeHint},
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Body of a request.
+///
+/// Built from bytes with [From] (`&str`, [String], [Vec<u8>], [Bytes], ...) or from any [hyper::body::Body] with [Body::new], like an `axum::body::Body` or a streaming body.
+#[derive(Debug)]
+pub struct Body(UnsyncBoxBody<Bytes, BoxError>);
+
+impl Body {
+    /// Wrap any body whose data is [Bytes].
+    pub fn new<B>(body: B) -> Self
+    where
+        B: HttpBody<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Body(body.map_err(Into::into).boxed_unsync())
+    }
+
+    pub fn empty() -> Self {
+        Body::new(Empty::new())
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::empty()
+    }
+}
+
+impl From<()> for Body {
+    fn from(_: ()) -> Self {
+        Body::empty()
+    }
+}
+
+macro_rules! body_from {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Body {
+                fn from(data: $ty) -> Self {
+                    Body::new(Full::new(Bytes::from(data)))
+                }
+            }
+        )*
+    };
+}
+
+body_from!(Bytes, Vec<u8>, String, &'static str, &'static [u8]);
+
+impl HttpBody for Body {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, BoxError>>> {
+        Pin::new(&mut self.0).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.0.size_hint()
+    }
+}
+
+/// Trailers of a collected response, stored in its extensions.
+#[derive(Debug, Clone)]
+pub(crate) struct Trailers(pub(crate) HeaderMap);
+
+/// Body of a response being read, its connection is given back to the pool once it is fully read.
+#[derive(Debug)]
+pub(crate) struct ResponseBody {
+    body: Incoming,
+    // Dropped before the connection, so the flag is reset before another request can use it.
+    _in_flight: InFlightGuard,
+    connection: PooledConnection,
+    _in_flight_permit: Option<PriorityPermit>,
+}
+
+impl ResponseBody {
+    pub(crate) fn new(
+        body: Incoming,
+        in_flight: InFlightGuard,
+        connection: PooledConnection,
+        in_flight_permit: Option<PriorityPermit>,
+    ) -> Self {
+        ResponseBody {
+            body,
+            _in_flight: in_flight,
+            connection,
+            _in_flight_permit: in_flight_permit,
+        }
+    }
+
+    /// Read the next chunk of data, [None] once the body is complete.
+    pub(crate) async fn data(&mut self) -> Option<Result<Bytes, Error>> {
+        loop {
+            match self.body.frame().await {
+                None => {
+                    self.connection.set_reusable();
+                    return None;
+                }
+                Some(Err(e)) => return Some(Err(Error::from_hyper(e, Error::ResponseCollect))),
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        return Some(Ok(data));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read the whole body.
+    pub(crate) async fn collect(self) -> Result<Bytes, Error> {
+        Ok(self.collect_with_trailers().await?.0)
+    }
+
+    /// Read the whole body and its trailers.
+    pub(crate) async fn collect_with_trailers(
+        mut self,
+    ) -> Result<(Bytes, Option<HeaderMap>), Error> {
+        let body = self
+            .connection
+            .collect_body(&mut self.body)
+            .await
+            .map_err(|e| Error::from_hyper(e, Error::ResponseCollect))?;
+        self.connection.set_reusable();
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, test_helpers::util::make_client_server};
+    use http_body_util::StreamBody;
+
+    #[tokio::test]
+    async fn wrapped_bodies() {
+        let (_server, client) = make_client_server("wrapped_bodies").await;
+
+        let chunks = [r#"{"name""#, r#": "nolanv"}"#]
+            .map(|chunk| Ok::<_, BoxError>(Frame::data(Bytes::from(chunk))));
+        for body in [
+            Body::new(axum::body::Body::from(r#"{"name": "nolanv"}"#)),
+            Body::new(StreamBody::new(futures_util::stream::iter(chunks))),
+        ] {
+            let (_, response) = client
+                .send_request(
+                    "/json",
+                    Method::POST,
+                    &[("Content-Type", "application/json")],
+                    Some(body),
+                )
+                .await
+                .expect("client.send_request");
+            assert_eq!(response, br#"{"hello": "nolanv"}"#);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Body, Error, body::BoxError};
+use http_body_util::BodyExt;
+use hyper::{Request, body::Bytes, header::CONTENT_LENGTH, http};
+use std::sync::Arc;
+
+type RequestRewriteFn =
+    dyn Fn(&http::request::Parts, Bytes) -> Result<Bytes, BoxError> + Send + Sync;
+type ResponseRewriteFn =
+    dyn Fn(&http::response::Parts, Bytes) -> Result<Bytes, BoxError> + Send + Sync;
+
+/// Hook set with [crate::ClientUnixBuilder::request_body_rewrite], transforming the body of every request before it is sent.
+#[derive(Clone)]
+pub(crate) struct RequestBodyRewrite(Arc<RequestRewriteFn>);
+
+/// Hook set with [crate::ClientUnixBuilder::response_body_rewrite], transforming the body of every buffered response.
+#[derive(Clone)]
+pub(crate) struct ResponseBodyRewrite(Arc<ResponseRewriteFn>);
+
+impl std::fmt::Debug for RequestBodyRewrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("RequestBodyRewrite").finish()
+    }
+}
+
+impl std::fmt::Debug for ResponseBodyRewrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("ResponseBodyRewrite").finish()
+    }
+}
+
+impl RequestBodyRewrite {
+    pub(crate) fn new(
+        rewrite: impl Fn(&http::request::Parts, Bytes) -> Result<Bytes, BoxError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        RequestBodyRewrite(Arc::new(rewrite))
+    }
+
+    /// Buffer the body and replace it with the rewritten one, its `Content-Length` is computed again.
+    pub(crate) async fn rewrite(&self, request: Request<Body>) -> Result<Request<Body>, Error> {
+        let (mut parts, body) = request.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(Error::RequestCollect)?
+            .to_bytes();
+
+        let body = (self.0)(&parts, body).map_err(Error::RequestEncode)?;
+        parts.headers.remove(CONTENT_LENGTH);
+        Ok(Request::from_parts(parts, Body::from(body)))
+    }
+}
+
+impl ResponseBodyRewrite {
+    pub(crate) fn new(
+        rewrite: impl Fn(&http::response::Parts, Bytes) -> Result<Bytes, BoxError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        ResponseBodyRewrite(Arc::new(rewrite))
+    }
+
+    pub(crate) fn rewrite(
+        &self,
+        parts: &http::response::Parts,
+        body: Bytes,
+    ) -> Result<Bytes, Error> {
+        (self.0)(parts, body).map_err(Error::ResponseDecode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Body, ClientUnix, ErrorAndResponse, Method,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+    use hyper::body::Bytes;
+
+    #[tokio::test]
+    async fn rewrite_envelopes() {
+        let socket_path = make_socket_path_test("body_rewrite", "rewrite_envelopes");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            let body = request
+                .split(|byte| *byte == b'\n')
+                .next_back()
+                .unwrap_or_default();
+            let response = match body {
+                b"<baguette>" => "<nolanv>".to_string(),
+                _ => "nope".to_string(),
+            };
+            format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .request_body_rewrite(|_, body| Ok(Bytes::from([b"<", body.as_ref(), b">"].concat())))
+            .response_body_rewrite(|_, body| {
+                match body.strip_prefix(b"<").and_then(|b| b.strip_suffix(b">")) {
+                    Some(body) => Ok(Bytes::copy_from_slice(body)),
+                    None => Err("missing envelope".into()),
+                }
+            })
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (_, response) = client
+            .send_request(
+                "/nolanv",
+                Method::POST,
+                &[("content-length", "8")],
+                Some(Body::from("baguette")),
+            )
+            .await
+            .expect("client.send_request");
+        assert_eq!(response, b"nolanv");
+
+        let result = client
+            .send_request("/nolanv", Method::POST, &[], Some(Body::from("nope")))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponse::InternalError(
+                crate::Error::ResponseDecode(_)
+            ))
+        ));
+    }
+}
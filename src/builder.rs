@@ -0,0 +1,672 @@
+use crate::{
+    ClientUnix, Clock, Error, FaultInjection, LoadBalancing, RetryPolicy, TokioClock,
+    body_rewrite::{RequestBodyRewrite, ResponseBodyRewrite},
+    headers::SECRET_HEADERS,
+    keep_alive::KeepAlive,
+    request_defaults::RequestDefaults,
+    request_id::RequestIdGenerator,
+    signing::RequestSigner,
+    stats::StatsRecorder,
+    throttle::Bandwidth,
+};
+use hyper::{Method, Version};
+use hyper::{
+    Request,
+    body::Bytes,
+    header::HeaderName,
+    http::{request, response},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Options applied to every connection made by a [ClientUnix].
+#[derive(Debug, Clone)]
+pub(crate) struct ClientConfig {
+    pub(crate) preserve_header_case: bool,
+    pub(crate) title_case_headers: bool,
+    pub(crate) authority: String,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) absolute_form: bool,
+    pub(crate) http_version: Version,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) canonicalize_socket_path: bool,
+    pub(crate) refuse_world_writable_parents: bool,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) write_timeout: Option<Duration>,
+    pub(crate) fail_fast: bool,
+    pub(crate) max_connections: usize,
+    pub(crate) coalesce_requests: bool,
+    pub(crate) max_in_flight_requests: Option<usize>,
+    pub(crate) max_queued_requests: Option<usize>,
+    pub(crate) queue_timeout: Option<Duration>,
+    pub(crate) pool_timeout: Option<Duration>,
+    pub(crate) min_idle_connections: usize,
+    pub(crate) max_idle_connections: Option<usize>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) rate_limit: Option<(f64, u32)>,
+    pub(crate) load_balancing: LoadBalancing,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    pub(crate) idempotency_keys: Option<RequestIdGenerator>,
+    pub(crate) response_cache: Option<usize>,
+    pub(crate) max_bandwidth: Option<u64>,
+    pub(crate) max_request_bandwidth: Option<u64>,
+    pub(crate) send_buffer_size: Option<usize>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    /// Limiter of [ClientConfig::max_bandwidth], shared by every connection of the client.
+    pub(crate) bandwidth: Option<Arc<Bandwidth>>,
+    pub(crate) request_signer: Option<RequestSigner>,
+    pub(crate) request_body_rewrite: Option<RequestBodyRewrite>,
+    pub(crate) response_body_rewrite: Option<ResponseBodyRewrite>,
+    pub(crate) request_id: Option<RequestIdGenerator>,
+    pub(crate) slow_request_threshold: Option<Duration>,
+    #[cfg(feature = "debug-wire")]
+    pub(crate) debug_wire: Option<crate::DebugWire>,
+    #[cfg(feature = "json")]
+    pub(crate) har_recorder: Option<crate::HarRecorder>,
+    #[cfg(feature = "json")]
+    pub(crate) cassette: Option<crate::Cassette>,
+    pub(crate) fault_injection: Option<FaultInjection>,
+    pub(crate) last_exchange: Option<usize>,
+    pub(crate) capture_wire: Option<usize>,
+    pub(crate) keep_alive: Option<KeepAlive>,
+    /// Replace the unix sockets by in-process pipes to a service, see [ClientUnix::builder_in_process].
+    #[cfg(feature = "server")]
+    pub(crate) in_process: Option<crate::server::InProcessConnector>,
+    pub(crate) clock: Arc<dyn Clock>,
+    /// Counters of [ClientUnix::stats], shared by every connection of the client.
+    pub(crate) stats: Arc<StatsRecorder>,
+    /// Computed from the authority and the `User-Agent` when the client is built.
+    pub(crate) request_defaults: RequestDefaults,
+    /// Headers redacted by the wire logging, HAR recorder, last exchange and cassette.
+    pub(crate) redacted_headers: Vec<HeaderName>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            preserve_header_case: false,
+            title_case_headers: false,
+            authority: "unix.socket".into(),
+            user_agent: Some(
+                concat!("http-client-unix-domain-socket/", env!("CARGO_PKG_VERSION")).into(),
+            ),
+            absolute_form: true,
+            http_version: Version::HTTP_11,
+            connect_timeout: None,
+            canonicalize_socket_path: false,
+            refuse_world_writable_parents: false,
+            read_timeout: None,
+            write_timeout: None,
+            fail_fast: false,
+            max_connections: 8,
+            coalesce_requests: false,
+            max_in_flight_requests: None,
+            max_queued_requests: None,
+            queue_timeout: None,
+            pool_timeout: None,
+            min_idle_connections: 0,
+            max_idle_connections: None,
+            idle_timeout: None,
+            rate_limit: None,
+            load_balancing: LoadBalancing::RoundRobin,
+            retry_policy: None,
+            idempotency_keys: None,
+            response_cache: None,
+            max_bandwidth: None,
+            max_request_bandwidth: None,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            bandwidth: None,
+            request_signer: None,
+            request_body_rewrite: None,
+            response_body_rewrite: None,
+            request_id: None,
+            slow_request_threshold: None,
+            #[cfg(feature = "debug-wire")]
+            debug_wire: None,
+            #[cfg(feature = "json")]
+            har_recorder: None,
+            #[cfg(feature = "json")]
+            cassette: None,
+            fault_injection: None,
+            last_exchange: None,
+            capture_wire: None,
+            keep_alive: None,
+            #[cfg(feature = "server")]
+            in_process: None,
+            clock: Arc::new(TokioClock),
+            request_defaults: RequestDefaults::default(),
+            redacted_headers: SECRET_HEADERS.to_vec(),
+            stats: Arc::default(),
+        }
+    }
+}
+
+/// Builder used to configure a [ClientUnix] before connecting it.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::ClientUnix;
+///
+/// pub async fn new_client() {
+///     ClientUnix::builder("/tmp/unix.socket")
+///         .preserve_header_case(true)
+///         .try_build()
+///         .await
+///         .expect("ClientUnixBuilder::try_build");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientUnixBuilder {
+    socket_paths: Vec<PathBuf>,
+    config: ClientConfig,
+}
+
+impl ClientUnixBuilder {
+    pub(crate) fn new(socket_path: impl AsRef<Path>) -> Self {
+        ClientUnixBuilder {
+            socket_paths: vec![socket_path.as_ref().to_path_buf()],
+            config: ClientConfig::default(),
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub(crate) fn new_in_process(connector: crate::server::InProcessConnector) -> Self {
+        let mut builder = ClientUnixBuilder::new("");
+        builder.config.in_process = Some(connector);
+        builder
+    }
+
+    /// Record the original casing and ordering of the response headers.
+    ///
+    /// Hyper normalizes header names to lowercase and groups them by name. When enabled, the raw response head is recorded as it is read from the socket so [ClientUnix::send_request_with_headers] can return the headers exactly as the server wrote them. Heads over 64 KiB are not recorded, their headers are returned lowercased. Default is false.
+    pub fn preserve_header_case(mut self, enabled: bool) -> Self {
+        self.config.preserve_header_case = enabled;
+        self
+    }
+
+    /// Write request header names as title case (`Content-Type` instead of `content-type`).
+    ///
+    /// Useful for legacy servers doing naive string matching on header names. Default is false.
+    pub fn title_case_headers(mut self, enabled: bool) -> Self {
+        self.config.title_case_headers = enabled;
+        self
+    }
+
+    /// Set the authority used in the request URI and in the `Host` header.
+    ///
+    /// The `Host` header is only added when the request doesn't already have one. Useful for servers routing on the `Host` value. An invalid authority fails [ClientUnixBuilder::try_build] with [Error::RequestBuild]. Default is `unix.socket`.
+    pub fn authority(mut self, authority: impl Into<String>) -> Self {
+        self.config.authority = authority.into();
+        self
+    }
+
+    /// Set the `User-Agent` header added to the requests not having one, so the server can tell which local client sent them. An invalid value fails [ClientUnixBuilder::try_build] with [Error::RequestBuild]. Default is `http-client-unix-domain-socket/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add a `User-Agent` header to the requests not having one, see [ClientUnixBuilder::user_agent]. Default is true.
+    pub fn send_user_agent(mut self, enabled: bool) -> Self {
+        self.config.user_agent = match enabled {
+            true => self
+                .config
+                .user_agent
+                .or(ClientConfig::default().user_agent),
+            false => None,
+        };
+        self
+    }
+
+    /// Send the full URI in the request line (`GET http://unix.socket/nolanv HTTP/1.1`), as expected by proxy-style daemons routing between their backends.
+    ///
+    /// When disabled, only the path and query are sent (`GET /nolanv HTTP/1.1`) and the authority is only carried by the `Host` header. Recorders and hooks always see the full URI. Default is true.
+    pub fn absolute_form(mut self, enabled: bool) -> Self {
+        self.config.absolute_form = enabled;
+        self
+    }
+
+    /// Set the HTTP version of the requests, only [Version::HTTP_10] and [Version::HTTP_11] are supported.
+    ///
+    /// With HTTP/1.0 the server usually closes the connection after each response, the client then transparently reconnects before sending the next request. Default is [Version::HTTP_11].
+    pub fn http_version(mut self, http_version: Version) -> Self {
+        self.config.http_version = http_version;
+        self
+    }
+
+    /// Set the maximum duration of the connection and HTTP handshake.
+    ///
+    /// Applied by [ClientUnixBuilder::try_build], [ClientUnix::try_reconnect] and every automatic reconnection, [Error::ConnectTimeout] is returned when it's exceeded. When the listen backlog of the server is full, connecting is retried until the timeout instead of failing right away. Default is no timeout.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Resolve the symlinks of the socket path before each connection, including the automatic reconnections.
+    ///
+    /// The path checks and [Error::NotASocket] then apply to the socket actually connected, and [ClientUnixBuilder::refuse_world_writable_parents] checks its real parent directories. Default is false.
+    pub fn canonicalize_socket_path(mut self, enabled: bool) -> Self {
+        self.config.canonicalize_socket_path = enabled;
+        self
+    }
+
+    /// Refuse to connect when a parent directory of the socket is writable by every user, where another user could replace the socket.
+    ///
+    /// Directories with the sticky bit, like `/tmp`, are accepted since only their owner can replace their entries. [Error::WorldWritableParent] is returned before connecting, combine it with [ClientUnixBuilder::canonicalize_socket_path] to check the directories behind the symlinks. Default is false.
+    pub fn refuse_world_writable_parents(mut self, enabled: bool) -> Self {
+        self.config.refuse_world_writable_parents = enabled;
+        self
+    }
+
+    /// Set the maximum duration without receiving any byte while waiting for a response.
+    ///
+    /// The timer restarts every time data is read, so slow but progressing responses are not interrupted. Idle connections between requests are not affected. [Error::StreamTimeout] is returned when it's exceeded. Default is no timeout.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.config.read_timeout = Some(read_timeout);
+        self
+    }
+
+    /// Set the maximum duration a request write can be blocked without sending any byte.
+    ///
+    /// [Error::StreamTimeout] is returned when it's exceeded. Default is no timeout.
+    pub fn write_timeout(mut self, write_timeout: Duration) -> Self {
+        self.config.write_timeout = Some(write_timeout);
+        self
+    }
+
+    /// Send requests without waiting for the connection to be ready.
+    ///
+    /// By default, the client waits until the connection is ready before sending a request. When enabled, the request is sent right away and [Error::ConnectionLost] is returned immediately if the connection is not ready. Idle connections already closed by the server are replaced in both cases. Default is false.
+    pub fn fail_fast(mut self, enabled: bool) -> Self {
+        self.config.fail_fast = enabled;
+        self
+    }
+
+    /// Send `method endpoint` on every connection idle for `interval`, so the server doesn't close it for inactivity between bursts of requests.
+    ///
+    /// A background task pings the idle connections until the client and all its clones are dropped, their response is discarded whatever its status. Connections failing the ping are closed and replaced on the next request. Pings are not counted in [ClientUnix::stats] requests. Default is no ping.
+    ///
+    /// # Panics
+    /// Panics if `interval` is zero.
+    pub fn keep_alive_ping(
+        mut self,
+        interval: Duration,
+        method: Method,
+        endpoint: impl Into<String>,
+    ) -> Self {
+        assert!(!interval.is_zero(), "interval must not be zero");
+        self.config.keep_alive = Some(KeepAlive {
+            interval,
+            method,
+            endpoint: endpoint.into(),
+        });
+        self
+    }
+
+    /// Set the maximum number of connections opened to the server.
+    ///
+    /// Each connection handles one request at a time, requests sent concurrently from clones of the [ClientUnix] wait for a free connection once this limit is reached. Default is 8.
+    ///
+    /// # Panics
+    /// Panics if `max_connections` is 0.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        assert!(max_connections > 0, "max_connections must be at least 1");
+        self.config.max_connections = max_connections;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept open, the connections freed above it are closed. Default is no limit, every connection freed stays idle, up to [ClientUnixBuilder::max_connections] of them.
+    pub fn max_idle_connections(mut self, max_idle_connections: usize) -> Self {
+        self.config.max_idle_connections = Some(max_idle_connections);
+        self
+    }
+
+    /// Set the number of idle connections never closed by [ClientUnixBuilder::idle_timeout]. Connections are not opened to reach it. Default is 0.
+    pub fn min_idle_connections(mut self, min_idle_connections: usize) -> Self {
+        self.config.min_idle_connections = min_idle_connections;
+        self
+    }
+
+    /// Close the connections idle for `idle_timeout`, so long quiet periods don't keep sockets open against the server.
+    ///
+    /// A background task checks the idle connections every half timeout until the client and all its clones are dropped, keeping [ClientUnixBuilder::min_idle_connections] of them. Closed connections are opened again by the next requests. Default is no timeout.
+    ///
+    /// # Panics
+    /// Panics if `idle_timeout` is zero.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        assert!(!idle_timeout.is_zero(), "idle_timeout must not be zero");
+        self.config.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Share a single request between concurrent identical GET requests.
+    ///
+    /// When a GET request is sent while an identical one (same endpoint and headers) is already in flight, it waits for the response of the first one instead of sending a new request, reducing the load on slow servers during startup stampedes. Errors are not shared, if the first request fails the waiting requests are sent on their own. Default is false.
+    pub fn coalesce_requests(mut self, enabled: bool) -> Self {
+        self.config.coalesce_requests = enabled;
+        self
+    }
+
+    /// Set the maximum number of requests sent at the same time by the client and all its clones.
+    ///
+    /// Requests over the limit are queued by [crate::Priority], then in order, until a running one completes, protecting undersized servers from bursts. Unlike [ClientUnixBuilder::max_connections], the limit covers the whole request, including connecting and reconnecting. Default is no limit.
+    ///
+    /// # Panics
+    /// Panics if `max_in_flight_requests` is 0.
+    pub fn max_in_flight_requests(mut self, max_in_flight_requests: usize) -> Self {
+        assert!(
+            max_in_flight_requests > 0,
+            "max_in_flight_requests must be at least 1"
+        );
+        self.config.max_in_flight_requests = Some(max_in_flight_requests);
+        self
+    }
+
+    /// Set the maximum number of requests of the client and all its clones waiting for a connection, or for [ClientUnixBuilder::max_in_flight_requests].
+    ///
+    /// When the queue is full, [crate::ClientUnix::try_send_request] fails right away with [Error::QueueFull] and the other requests wait for a place, up to [ClientUnixBuilder::queue_timeout]. Default is no limit.
+    ///
+    /// # Panics
+    /// Panics if `max_queued_requests` is 0.
+    pub fn max_queued_requests(mut self, max_queued_requests: usize) -> Self {
+        assert!(
+            max_queued_requests > 0,
+            "max_queued_requests must be at least 1"
+        );
+        self.config.max_queued_requests = Some(max_queued_requests);
+        self
+    }
+
+    /// Set how long a request waits for a place in a full queue before failing with [Error::QueueFull]. Only used with [ClientUnixBuilder::max_queued_requests]. Default is no timeout.
+    pub fn queue_timeout(mut self, queue_timeout: Duration) -> Self {
+        self.config.queue_timeout = Some(queue_timeout);
+        self
+    }
+
+    /// Set how long a request waits for a free connection of the pool before failing with [Error::PoolTimeout], which gives the number of requests still waiting.
+    ///
+    /// Under saturation, callers get an error instead of hanging until a connection is freed. The time spent waiting for [ClientUnixBuilder::max_in_flight_requests] is not included. Default is no timeout.
+    pub fn pool_timeout(mut self, pool_timeout: Duration) -> Self {
+        self.config.pool_timeout = Some(pool_timeout);
+        self
+    }
+
+    /// Limit the rate of the requests sent by the client and all its clones with a token bucket.
+    ///
+    /// Up to `burst` requests can be sent at once, then requests are delayed to respect `requests_per_second` on average. Default is no limit.
+    ///
+    /// # Panics
+    /// Panics if `requests_per_second` is not strictly positive or if `burst` is 0.
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be strictly positive"
+        );
+        assert!(burst > 0, "burst must be at least 1");
+        self.config.rate_limit = Some((requests_per_second, burst));
+        self
+    }
+
+    /// Add another socket of the same server, requests are distributed between all the sockets.
+    ///
+    /// Useful for servers sharding their work across worker sockets, see [ClientUnixBuilder::load_balancing]. Each socket has its own connections, [ClientUnixBuilder::max_connections] applies per socket.
+    pub fn add_socket_path(mut self, socket_path: impl AsRef<Path>) -> Self {
+        self.socket_paths.push(socket_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the strategy used to distribute the requests when several sockets are added. Default is [LoadBalancing::RoundRobin].
+    pub fn load_balancing(mut self, load_balancing: LoadBalancing) -> Self {
+        self.config.load_balancing = load_balancing;
+        self
+    }
+
+    /// Retry the requests rejected with `429 Too Many Requests` or `503 Service Unavailable`.
+    ///
+    /// The `Retry-After` header of the response is honored when scheduling the next attempt. Request bodies are buffered so they can be sent again. When the retries are exhausted, [crate::ErrorAndResponse::RetriesExhausted] is returned. See [ClientUnixBuilder::idempotency_keys] to also retry on connection errors. Default is no retry.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Send an `Idempotency-Key` header with a random UUID v4 in every POST and PATCH request, the same key being sent by all the attempts of a request.
+    ///
+    /// Requests already having the header keep their own key. With a [ClientUnixBuilder::retry_policy], the requests sent with a key are also retried on the errors for which [Error::is_retryable] is true, servers supporting the header process them only once. Default is false.
+    pub fn idempotency_keys(mut self, enabled: bool) -> Self {
+        self.config.idempotency_keys = enabled.then(RequestIdGenerator::uuid);
+        self
+    }
+
+    /// Cache up to `max_entries` GET responses in memory, shared by the client and all its clones.
+    ///
+    /// Responses are stored and reused following the `Cache-Control`, `Expires` and `Vary` headers (RFC 9111), responses without explicit freshness information are never stored. Requests with `Cache-Control: no-cache` bypass the cache. Useful when polling mostly-static endpoints. Default is no cache.
+    ///
+    /// # Panics
+    /// Panics if `max_entries` is 0.
+    pub fn response_cache(mut self, max_entries: usize) -> Self {
+        assert!(max_entries > 0, "max_entries must be at least 1");
+        self.config.response_cache = Some(max_entries);
+        self
+    }
+
+    /// Limit the throughput of the client and all its clones, in bytes per second.
+    ///
+    /// The limit is shared by every connection and applies separately to the data sent and received, so a bulk export over the socket doesn't starve the other clients of the server. Default is no limit.
+    ///
+    /// # Panics
+    /// Panics if `bytes_per_second` is 0.
+    pub fn max_bandwidth(mut self, bytes_per_second: u64) -> Self {
+        assert!(bytes_per_second > 0, "bytes_per_second must be at least 1");
+        self.config.max_bandwidth = Some(bytes_per_second);
+        self
+    }
+
+    /// Limit the throughput of each request, in bytes per second.
+    ///
+    /// Applies separately to the data sent and received, it can be combined with [ClientUnixBuilder::max_bandwidth]. Default is no limit.
+    ///
+    /// # Panics
+    /// Panics if `bytes_per_second` is 0.
+    pub fn max_request_bandwidth(mut self, bytes_per_second: u64) -> Self {
+        assert!(bytes_per_second > 0, "bytes_per_second must be at least 1");
+        self.config.max_request_bandwidth = Some(bytes_per_second);
+        self
+    }
+
+    /// Set the size of the send buffer of each socket (`SO_SNDBUF`), in bytes.
+    ///
+    /// Larger buffers help bulk uploads over the socket. The kernel may adjust the value, Linux doubles it and caps it to `net.core.wmem_max`. Default is the system default.
+    pub fn send_buffer_size(mut self, size: usize) -> Self {
+        self.config.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the receive buffer of each socket (`SO_RCVBUF`), in bytes.
+    ///
+    /// Larger buffers help bulk downloads over the socket. The kernel may adjust the value, Linux doubles it and caps it to `net.core.rmem_max`. Default is the system default.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.config.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Call `signer` on every request right before it is sent, to compute and attach signature headers.
+    ///
+    /// The request is complete: method, absolute URI, headers (including `Host`), extensions and the whole body, which is buffered to be readable. The signer is called again for every retry. Default is no signer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn new_client() {
+    ///     ClientUnix::builder("/tmp/unix.socket")
+    ///         .request_signer(|request| {
+    ///             let signature = format!("{}:{}", request.uri().path(), request.body().len());
+    ///             request
+    ///                 .headers_mut()
+    ///                 .insert("x-signature", signature.parse().expect("HeaderValue"));
+    ///         })
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    /// }
+    /// ```
+    pub fn request_signer(
+        mut self,
+        signer: impl Fn(&mut Request<Bytes>) + Send + Sync + 'static,
+    ) -> Self {
+        self.config.request_signer = Some(RequestSigner::new(signer));
+        self
+    }
+
+    /// Replace the body of every request with the one returned by `rewrite`, like wrapping it in the envelope expected by the server.
+    ///
+    /// The body is buffered and given with the head of the request, requests without body give an empty one. `Content-Length` is computed again from the new body. The rewrite runs before [ClientUnixBuilder::request_signer] and again for every retry, an error is returned as [crate::Error::RequestEncode]. Default is no rewrite.
+    ///
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    /// use hyper::body::Bytes;
+    ///
+    /// pub async fn new_client() {
+    ///     ClientUnix::builder("/tmp/unix.socket")
+    ///         .request_body_rewrite(|_, body| {
+    ///             let payload = String::from_utf8(body.to_vec())?;
+    ///             Ok(Bytes::from(format!("{{\"payload\": {}}}", payload)))
+    ///         })
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    /// }
+    /// ```
+    pub fn request_body_rewrite(
+        mut self,
+        rewrite: impl Fn(
+            &request::Parts,
+            Bytes,
+        ) -> Result<Bytes, Box<dyn std::error::Error + Send + Sync>>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.config.request_body_rewrite = Some(RequestBodyRewrite::new(rewrite));
+        self
+    }
+
+    /// Replace the body of every response with the one returned by `rewrite`, like decrypting it or removing its envelope before it is deserialized.
+    ///
+    /// Applies to the responses read whole, successful or not, the streamed ones are left untouched. The recorders, like the HAR one, see the body as received. An error is returned as [crate::Error::ResponseDecode]. Default is no rewrite.
+    pub fn response_body_rewrite(
+        mut self,
+        rewrite: impl Fn(
+            &response::Parts,
+            Bytes,
+        ) -> Result<Bytes, Box<dyn std::error::Error + Send + Sync>>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.config.response_body_rewrite = Some(ResponseBodyRewrite::new(rewrite));
+        self
+    }
+
+    /// Send an `X-Request-Id` header with a random UUID v4 in every request.
+    ///
+    /// Requests already having the header keep their own id. The id is inserted as a [crate::RequestId] in the extensions of the request, readable by [ClientUnixBuilder::request_signer], and of the [crate::Response]. The errors of the request are wrapped in [crate::Error::WithRequestId], use [crate::Error::inner] to match on their variant, and the id is added to the [ClientUnixBuilder::slow_request_threshold] warnings. Default is false.
+    pub fn request_id(mut self, enabled: bool) -> Self {
+        self.config.request_id = enabled.then(RequestIdGenerator::uuid);
+        self
+    }
+
+    /// Same as [ClientUnixBuilder::request_id] with ids built by `generator`.
+    pub fn request_id_generator(
+        mut self,
+        generator: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.config.request_id = Some(RequestIdGenerator::new(generator));
+        self
+    }
+
+    /// Log a warning for every request taking at least `threshold`, with its method, endpoint, status and duration.
+    ///
+    /// The duration covers the whole request, including queueing and retries. Warnings are emitted with the [log](https://docs.rs/log) crate, so they are visible with any logger, including `tracing` subscribers with the `log` compatibility enabled. Default is no threshold.
+    pub fn slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.config.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Log the heads of the requests and responses as they are sent and received **(feature = debug-wire)**.
+    ///
+    /// Useful to troubleshoot protocol issues with unusual servers, see [crate::DebugWire] for the body snippets and the redacted headers. Default is no logging.
+    #[cfg(feature = "debug-wire")]
+    pub fn debug_wire(mut self, debug_wire: crate::DebugWire) -> Self {
+        self.config.debug_wire = Some(debug_wire);
+        self
+    }
+
+    /// Redact the value of `name`, like a custom token header, wherever the client logs or records headers.
+    ///
+    /// Applies to the wire logging, the HAR recorder, [ClientUnixBuilder::capture_last_exchange] and cassettes, so enabling debugging doesn't leak secrets. The `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers are always redacted. Can be called several times.
+    pub fn redact_header(mut self, name: HeaderName) -> Self {
+        if !self.config.redacted_headers.contains(&name) {
+            self.config.redacted_headers.push(name);
+        }
+        self
+    }
+
+    /// Keep the most recent request and its response, with the first `max_body_size` bytes of the response body, returned by [ClientUnix::last_exchange].
+    ///
+    /// Useful to inspect what was actually exchanged when a request fails deep in application code, without logging every request. Default is no capture.
+    pub fn capture_last_exchange(mut self, max_body_size: usize) -> Self {
+        self.config.last_exchange = Some(max_body_size);
+        self
+    }
+
+    /// Capture the exact bytes received for each response, up to `max_size` bytes, returned by [crate::Response::wire_bytes].
+    ///
+    /// Useful to debug servers sending technically invalid responses that hyper still parses, like duplicated headers or an unusual chunked encoding. Streamed responses are not captured. Default is no capture.
+    pub fn capture_wire(mut self, max_size: usize) -> Self {
+        self.config.capture_wire = Some(max_size);
+        self
+    }
+
+    /// Record the requests and their responses in `har_recorder` **(feature = json)**
+    ///
+    /// Keep a clone of the [crate::HarRecorder] to enable or disable it at runtime and export the HAR document. Request bodies are buffered while it is enabled. Default is no recorder.
+    #[cfg(feature = "json")]
+    pub fn har_recorder(mut self, har_recorder: crate::HarRecorder) -> Self {
+        self.config.har_recorder = Some(har_recorder);
+        self
+    }
+
+    /// Record the exchanges to `cassette`, or replay them from it without connecting to the server **(feature = json)**
+    ///
+    /// Useful to run tests without the real server, see [crate::Cassette]. Request bodies are buffered. Default is no cassette.
+    #[cfg(feature = "json")]
+    pub fn cassette(mut self, cassette: crate::Cassette) -> Self {
+        self.config.cassette = Some(cassette);
+        self
+    }
+
+    /// Randomly delay, fail or truncate some requests sent to the server, see [FaultInjection].
+    ///
+    /// Only meant for chaos testing. Default is no fault.
+    pub fn fault_injection(mut self, fault_injection: FaultInjection) -> Self {
+        self.config.fault_injection = Some(fault_injection);
+        self
+    }
+
+    /// Set the source of time of the client, see [Clock]. Default is [TokioClock].
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.config.clock = Arc::new(clock);
+        self
+    }
+
+    /// Build the [ClientUnix] and try to connect to it.
+    pub async fn try_build(self) -> Result<ClientUnix, Error> {
+        ClientUnix::try_connect(self.socket_paths, self.config).await
+    }
+}
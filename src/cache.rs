@@ -0,0 +1,326 @@
+use crate::clock::Clock;
+use crate::coalesce::SharedResponse;
+use hyper::{
+    HeaderMap, StatusCode,
+    body::Bytes,
+    header::{AGE, CACHE_CONTROL, DATE, EXPIRES, PRAGMA, VARY},
+    http::response::Parts,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use tokio::time::Instant;
+
+/// Status codes that can be stored when the response has explicit freshness information.
+const CACHEABLE_STATUS: [StatusCode; 11] = [
+    StatusCode::OK,
+    StatusCode::NON_AUTHORITATIVE_INFORMATION,
+    StatusCode::NO_CONTENT,
+    StatusCode::MULTIPLE_CHOICES,
+    StatusCode::MOVED_PERMANENTLY,
+    StatusCode::PERMANENT_REDIRECT,
+    StatusCode::NOT_FOUND,
+    StatusCode::METHOD_NOT_ALLOWED,
+    StatusCode::GONE,
+    StatusCode::URI_TOO_LONG,
+    StatusCode::NOT_IMPLEMENTED,
+];
+
+/// Stored response, only valid for requests with the same values for the headers listed in `Vary`.
+#[derive(Debug)]
+struct CacheEntry {
+    vary: Vec<(String, Option<String>)>,
+    expires_at: Instant,
+    response: SharedResponse,
+}
+
+impl CacheEntry {
+    fn matches(&self, headers: &[(&str, &str)]) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| request_header(headers, name).as_deref() == value.as_deref())
+    }
+}
+
+/// In-memory private cache of GET responses, following the RFC 9111 freshness rules.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    max_entries: usize,
+    clock: Arc<dyn Clock>,
+    entries: Mutex<HashMap<String, Vec<CacheEntry>>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(max_entries: usize, clock: Arc<dyn Clock>) -> Self {
+        ResponseCache {
+            max_entries,
+            clock,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get a fresh response stored for this request.
+    pub(crate) fn get(&self, endpoint: &str, headers: &[(&str, &str)]) -> Option<(Parts, Bytes)> {
+        if has_directive(headers, "no-cache") || has_directive(headers, "no-store") {
+            return None;
+        }
+        let entries = self.entries.lock().ok()?;
+        let now = self.clock.now();
+        entries
+            .get(endpoint)?
+            .iter()
+            .find(|entry| entry.expires_at > now && entry.matches(headers))
+            .map(|entry| entry.response.clone().into_parts())
+    }
+
+    /// Store the response if it is cacheable.
+    pub(crate) fn store(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        parts: &Parts,
+        body: &Bytes,
+    ) {
+        if has_directive(headers, "no-store") || !CACHEABLE_STATUS.contains(&parts.status) {
+            return;
+        }
+        let Some(freshness) = freshness(&parts.headers, self.clock.system_time()) else {
+            return;
+        };
+        let Some(vary) = vary(&parts.headers, headers) else {
+            return;
+        };
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+
+        let now = self.clock.now();
+        let entry = CacheEntry {
+            vary,
+            expires_at: now + freshness,
+            response: SharedResponse::new(parts, body),
+        };
+        let variants = entries.entry(endpoint.to_string()).or_default();
+        variants.retain(|variant| variant.vary != entry.vary);
+        variants.push(entry);
+        evict(&mut entries, self.max_entries, now);
+    }
+}
+
+/// Drop the expired entries, then the ones expiring first until there are at most `max_entries`.
+fn evict(entries: &mut HashMap<String, Vec<CacheEntry>>, max_entries: usize, now: Instant) {
+    entries.retain(|_, variants| {
+        variants.retain(|variant| variant.expires_at > now);
+        !variants.is_empty()
+    });
+
+    let mut len: usize = entries.values().map(Vec::len).sum();
+    while len > max_entries {
+        let Some((endpoint, index)) = entries
+            .iter()
+            .flat_map(|(endpoint, variants)| {
+                variants
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, variant)| (endpoint, index, variant.expires_at))
+            })
+            .min_by_key(|(_, _, expires_at)| *expires_at)
+            .map(|(endpoint, index, _)| (endpoint.clone(), index))
+        else {
+            return;
+        };
+        if let Some(variants) = entries.get_mut(&endpoint) {
+            variants.remove(index);
+            if variants.is_empty() {
+                entries.remove(&endpoint);
+            }
+        }
+        len -= 1;
+    }
+}
+
+/// Remaining freshness lifetime of a response, [None] when it must not be stored.
+fn freshness(headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+    let directives = directives(headers.get_all(CACHE_CONTROL).iter());
+    let directive = |name: &str| {
+        directives
+            .iter()
+            .find(|(directive, _)| directive.eq_ignore_ascii_case(name))
+    };
+    if directive("no-store").is_some() || directive("no-cache").is_some() {
+        return None;
+    }
+
+    let lifetime = match directive("max-age") {
+        Some((_, max_age)) => Duration::from_secs(max_age.as_deref()?.parse().ok()?),
+        None => {
+            let expires = httpdate::parse_http_date(headers.get(EXPIRES)?.to_str().ok()?).ok()?;
+            let date = headers
+                .get(DATE)
+                .and_then(|date| httpdate::parse_http_date(date.to_str().ok()?).ok())
+                .unwrap_or(now);
+            expires.duration_since(date).unwrap_or(Duration::ZERO)
+        }
+    };
+    let age = headers
+        .get(AGE)
+        .and_then(|age| age.to_str().ok()?.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::ZERO);
+
+    lifetime.checked_sub(age).filter(|d| !d.is_zero())
+}
+
+/// Request header values selected by the `Vary` header, [None] for `Vary: *`.
+fn vary(
+    response_headers: &HeaderMap,
+    request_headers: &[(&str, &str)],
+) -> Option<Vec<(String, Option<String>)>> {
+    let mut vary = Vec::new();
+    for value in response_headers.get_all(VARY) {
+        for name in value.to_str().ok()?.split(',').map(str::trim) {
+            match name {
+                "" => {}
+                "*" => return None,
+                name => vary.push((
+                    name.to_ascii_lowercase(),
+                    request_header(request_headers, name),
+                )),
+            }
+        }
+    }
+    Some(vary)
+}
+
+fn request_header(headers: &[(&str, &str)], name: &str) -> Option<String> {
+    let values: Vec<&str> = headers
+        .iter()
+        .filter(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+        .collect();
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+/// Whether the request `Cache-Control` (or `Pragma`) has the directive.
+fn has_directive(headers: &[(&str, &str)], name: &str) -> bool {
+    headers
+        .iter()
+        .filter(|(header_name, _)| {
+            header_name.eq_ignore_ascii_case(CACHE_CONTROL.as_str())
+                || header_name.eq_ignore_ascii_case(PRAGMA.as_str())
+        })
+        .flat_map(|(_, value)| value.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case(name))
+}
+
+/// Parse `Cache-Control` directives as names with their optional value.
+fn directives<'a>(
+    values: impl Iterator<Item = &'a hyper::header::HeaderValue>,
+) -> Vec<(String, Option<String>)> {
+    values
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|directive| {
+            let mut directive = directive.splitn(2, '=');
+            let name = directive.next()?.trim();
+            let value = directive
+                .next()
+                .map(|value| value.trim().trim_matches('"').to_string());
+            (!name.is_empty()).then(|| (name.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TokioClock;
+    use hyper::Response;
+
+    fn parts(headers: &[(&str, &str)]) -> Parts {
+        let mut response = Response::builder();
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        response.body(()).expect("Response::builder").into_parts().0
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn max_age_and_vary() {
+        let cache = ResponseCache::new(8, Arc::new(TokioClock));
+        let body = Bytes::from_static(b"nolanv");
+        let response = parts(&[("cache-control", "max-age=10"), ("vary", "Accept")]);
+
+        cache.store("/nolanv", &[("Accept", "text/plain")], &response, &body);
+        assert!(cache.get("/nolanv", &[("accept", "text/plain")]).is_some());
+        assert!(cache.get("/nolanv", &[("accept", "text/html")]).is_none());
+        assert!(cache.get("/nolanv", &[]).is_none());
+        assert!(
+            cache
+                .get(
+                    "/nolanv",
+                    &[("accept", "text/plain"), ("cache-control", "no-cache")]
+                )
+                .is_none()
+        );
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(cache.get("/nolanv", &[("accept", "text/plain")]).is_none());
+    }
+
+    #[test]
+    fn freshness_rules() {
+        assert_eq!(
+            freshness(
+                &parts(&[("cache-control", "public, max-age=60")]).headers,
+                SystemTime::now()
+            ),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            freshness(
+                &parts(&[("cache-control", "max-age=60"), ("age", "20")]).headers,
+                SystemTime::now()
+            ),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(
+            freshness(
+                &parts(&[
+                    ("date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                    ("expires", "Wed, 21 Oct 2015 07:29:00 GMT")
+                ])
+                .headers,
+                SystemTime::now()
+            ),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            freshness(
+                &parts(&[
+                    ("cache-control", "no-store"),
+                    ("expires", "Wed, 21 Oct 2015 07:29:00 GMT")
+                ])
+                .headers,
+                SystemTime::now()
+            ),
+            None
+        );
+        assert_eq!(freshness(&parts(&[]).headers, SystemTime::now()), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn evict_first_expiring() {
+        let cache = ResponseCache::new(2, Arc::new(TokioClock));
+        let body = Bytes::new();
+        cache.store("/a", &[], &parts(&[("cache-control", "max-age=30")]), &body);
+        cache.store("/b", &[], &parts(&[("cache-control", "max-age=10")]), &body);
+        cache.store("/c", &[], &parts(&[("cache-control", "max-age=20")]), &body);
+
+        assert!(cache.get("/a", &[]).is_some());
+        assert!(cache.get("/b", &[]).is_none());
+        assert!(cache.get("/c", &[]).is_some());
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{
+    Error,
+    headers::{REDACTED, SECRET_HEADERS, merge_redacted},
+};
+use hyper::{Method, Response, body::Bytes, header::HeaderName, http::response::Parts};
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Record and replay file of the requests sent by a client, set with [crate::ClientUnixBuilder::cassette] **(feature = json)**
+///
+/// When the file doesn't exist, requests are sent to the server and every exchange is recorded to the file. When it exists, responses are replayed from it without connecting to the socket, so tests can run without the server. Requests are matched on their method, endpoint and body, each recorded exchange is replayed once in order, then the last one is reused. Only requests whose response body is read by the client are recorded, streamed responses are not. The values of the `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers, and of those given to [crate::ClientUnixBuilder::redact_header], are redacted.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{Cassette, ClientUnix, Method};
+///
+/// pub async fn get_with_cassette() {
+///     let cassette = Cassette::try_new("tests/cassettes/get_hello.json").expect("Cassette::try_new");
+///     let client = ClientUnix::builder("/tmp/unix.socket")
+///         .cassette(cassette)
+///         .try_build()
+///         .await
+///         .expect("ClientUnixBuilder::try_build");
+///
+///     client
+///         .send_request("/nolanv", Method::GET, &[], None)
+///         .await
+///         .expect("client.send_request");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    match_body: bool,
+    replaying: bool,
+    redacted_headers: Vec<HeaderName>,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+/// Body stored as text when it is valid UTF-8, as an array of bytes otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordedBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Bytes(bytes.to_vec()),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            RecordedBody::Text(text) => text.as_bytes(),
+            RecordedBody::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl Cassette {
+    /// Load the cassette at `path` to replay it, or start recording when the file doesn't exist.
+    pub fn try_new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let (replaying, interactions) = match std::fs::read(&path) {
+            Ok(content) => {
+                let file: CassetteFile = serde_json::from_slice(&content)
+                    .map_err(|e| Error::Cassette(io::Error::from(e)))?;
+                (true, file.interactions)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (false, Vec::new()),
+            Err(e) => return Err(Error::Cassette(e)),
+        };
+
+        Ok(Cassette {
+            path,
+            match_body: true,
+            replaying,
+            redacted_headers: SECRET_HEADERS.to_vec(),
+            state: Arc::new(Mutex::new(CassetteState {
+                played: vec![false; interactions.len()],
+                interactions,
+            })),
+        })
+    }
+
+    /// Compare the request bodies when matching the recorded exchanges. Default is true.
+    pub fn match_body(mut self, enabled: bool) -> Self {
+        self.match_body = enabled;
+        self
+    }
+
+    /// Whether the responses are replayed from the file instead of being requested to the server.
+    pub fn is_replaying(&self) -> bool {
+        self.replaying
+    }
+
+    /// Find the recorded response of the request.
+    pub(crate) fn replay(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        body: &[u8],
+    ) -> Result<(Parts, Bytes), Error> {
+        let mut state = self.state.lock().expect("Cassette lock");
+        let state = &mut *state;
+        let matching: Vec<usize> = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| {
+                let request = &interaction.request;
+                request.method == method.as_str()
+                    && request.endpoint == endpoint
+                    && (!self.match_body || request.body.as_bytes() == body)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let index = matching
+            .iter()
+            .find(|index| !state.played[**index])
+            .or(matching.last())
+            .copied()
+            .ok_or_else(|| Error::CassetteMiss(format!("{} {}", method, endpoint)))?;
+        state.played[index] = true;
+
+        let recorded = &state.interactions[index].response;
+        let mut response = Response::builder().status(recorded.status);
+        for (name, value) in &recorded.headers {
+            response = response.header(name, value);
+        }
+        let (parts, ()) = response.body(()).map_err(Error::RequestBuild)?.into_parts();
+        Ok((parts, Bytes::copy_from_slice(recorded.body.as_bytes())))
+    }
+
+    /// Also redact the headers given to [crate::ClientUnixBuilder::redact_header].
+    pub(crate) fn redact_headers(&mut self, redacted: &[HeaderName]) {
+        merge_redacted(&mut self.redacted_headers, redacted);
+    }
+
+    /// Append the exchange to the cassette and write it to the file.
+    pub(crate) fn record(
+        &self,
+        method: &Method,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+        parts: &Parts,
+        body_response: &[u8],
+    ) -> Result<(), Error> {
+        let redacted = |name: &str| {
+            self.redacted_headers
+                .iter()
+                .any(|redacted| redacted.as_str().eq_ignore_ascii_case(name))
+        };
+        let mut state = self.state.lock().expect("Cassette lock");
+        state.interactions.push(Interaction {
+            request: RecordedRequest {
+                method: method.to_string(),
+                endpoint: endpoint.to_string(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| match redacted(name) {
+                        true => (name.to_string(), REDACTED.to_string()),
+                        false => (name.to_string(), value.to_string()),
+                    })
+                    .collect(),
+                body: RecordedBody::new(body),
+            },
+            response: RecordedResponse {
+                status: parts.status.as_u16(),
+                headers: parts
+                    .headers
+                    .iter()
+                    .map(|(name, value)| match redacted(name.as_str()) {
+                        true => (name.to_string(), REDACTED.to_string()),
+                        false => {
+                            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                            (name.to_string(), value)
+                        }
+                    })
+                    .collect(),
+                body: RecordedBody::new(body_response),
+            },
+        });
+        state.played.push(true);
+
+        let content = serde_json::to_vec_pretty(&CassetteFile {
+            interactions: state.interactions.clone(),
+        })
+        .map_err(|e| Error::Cassette(io::Error::from(e)))?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(Error::Cassette)?;
+        }
+        std::fs::write(&self.path, content).map_err(Error::Cassette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Body;
+    use crate::{
+        ClientUnix, ErrorAndResponse,
+        test_helpers::{server::Server, util::*},
+    };
+    use hyper::StatusCode;
+
+    #[tokio::test]
+    async fn record_then_replay() {
+        let socket_path = make_socket_path_test("cassette", "record_then_replay");
+        let cassette_path = format!("{}.cassette.json", socket_path);
+        let _ = std::fs::remove_file(&cassette_path);
+        let server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+
+        let cassette = Cassette::try_new(&cassette_path).expect("Cassette::try_new");
+        assert!(!cassette.is_replaying());
+        let client = ClientUnix::builder(&socket_path)
+            .cassette(cassette)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let (status_code, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, b"Hello nolanv");
+        server.abort().await;
+
+        let cassette = Cassette::try_new(&cassette_path).expect("Cassette::try_new");
+        assert!(cassette.is_replaying());
+        let client = ClientUnix::builder(make_socket_path_test("cassette", "no_server"))
+            .cassette(cassette)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let (status_code, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, b"Hello nolanv");
+
+        let result = client
+            .send_request("/nolanv", Method::POST, &[], Some(Body::from("baguette")))
+            .await;
+        assert!(matches!(
+            result.err(),
+            Some(ErrorAndResponse::InternalError(Error::CassetteMiss(request)))
+                if request == "POST /nolanv"
+        ));
+    }
+
+    #[tokio::test]
+    async fn record_redacted_headers() {
+        let socket_path = make_socket_path_test("cassette", "record_redacted_headers");
+        let cassette_path = format!("{}.cassette.json", socket_path);
+        let _ = std::fs::remove_file(&cassette_path);
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+
+        let client = ClientUnix::builder(&socket_path)
+            .cassette(Cassette::try_new(&cassette_path).expect("Cassette::try_new"))
+            .redact_header(HeaderName::from_static("x-token"))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        client
+            .send_request(
+                "/nolanv",
+                Method::GET,
+                &[("Authorization", "Bearer secret"), ("X-Token", "secret")],
+                None,
+            )
+            .await
+            .expect("client.send_request");
+
+        let cassette: CassetteFile =
+            serde_json::from_slice(&std::fs::read(&cassette_path).expect("std::fs::read"))
+                .expect("serde_json::from_slice");
+        assert_eq!(
+            cassette.interactions[0].request.headers,
+            [
+                ("Authorization".to_string(), REDACTED.to_string()),
+                ("X-Token".to_string(), REDACTED.to_string()),
+            ]
+        );
+    }
+}
@@ -1,24 +1,75 @@
+use crate::Body;
+#[cfg(feature = "opentelemetry")]
+use crate::trace_context;
 #[cfg(feature = "json")]
-use crate::error::ErrorAndResponseJson;
-use crate::{Error, error::ErrorAndResponse};
-use axum_core::body::Body;
+use crate::{BorrowedJson, error::ErrorAndResponseJson, har::HarRequest, json, text};
+use crate::{
+    ClientStats, ClientUnixBuilder, Error, FaultInjection, OriginalHeaders, PoolStats, RequestId,
+    balancing::Balancer,
+    body::{ResponseBody, Trailers},
+    builder::ClientConfig,
+    cache::ResponseCache,
+    coalesce::{Coalescer, Role, SharedResponse, wait_leader},
+    conditional::ValidatorStore,
+    error::ErrorAndResponse,
+    headers::keeps_alive,
+    idempotency::{IDEMPOTENCY_KEY_HEADER, idempotency_key},
+    idle_reaper, keep_alive,
+    last_exchange::{ExchangeId, LastExchange, LastExchangeRecorder},
+    pool::{ConnectionLease, Pool, PooledConnection},
+    priority::{Priority, PrioritySemaphore},
+    queue::RequestQueue,
+    rate_limit::RateLimiter,
+    request_defaults::RequestDefaults,
+    request_id::REQUEST_ID_HEADER,
+    retry::{is_retryable, parse_retry_after},
+    throttle::Bandwidth,
+    timing::Timing,
+    wire::WireCapture,
+};
+use bytes::BytesMut;
 use http_body_util::BodyExt;
 use hyper::{
-    Method, Request, StatusCode,
-    client::conn::http1::{self, SendRequest},
+    Method, Request, StatusCode, Version,
+    body::{Body as _, Bytes},
+    header::{HOST, HeaderValue, RETRY_AFTER, USER_AGENT},
+    http::{Extensions, response::Parts},
 };
-use hyper_util::rt::TokioIo;
 #[cfg(feature = "json")]
 use serde::{Serialize, de::DeserializeOwned};
-use std::path::{PathBuf, Path};
-use tokio::{net::UnixStream, task::JoinHandle};
+#[cfg(feature = "json")]
+use serde_json::value::RawValue;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// A simple HTTP (json) client using UNIX domain socket in Rust
-#[derive(Debug)]
+///
+/// Connections are pooled, so a [ClientUnix] can be cloned cheaply and used concurrently from many tasks, all the clones share the same connections.
+#[derive(Debug, Clone)]
 pub struct ClientUnix {
-    socket_path: PathBuf,
-    sender: SendRequest<Body>,
-    join_handle: JoinHandle<Error>,
+    inner: Arc<ClientInner>,
+    pub(crate) priority: Priority,
+    pub(crate) wait_for_queue: bool,
+    /// Connection leased by [ClientUnix::with_connection], used by all the requests of the clone.
+    pub(crate) lease: Option<Arc<ConnectionLease>>,
+}
+
+/// State shared by all the clones of a [ClientUnix].
+#[derive(Debug)]
+struct ClientInner {
+    config: ClientConfig,
+    pools: Vec<Arc<Pool>>,
+    balancer: Balancer,
+    coalescer: Option<Arc<Coalescer>>,
+    cache: Option<ResponseCache>,
+    in_flight_limit: Option<Arc<PrioritySemaphore>>,
+    queue: Option<RequestQueue>,
+    rate_limiter: Option<RateLimiter>,
+    validators: ValidatorStore,
+    last_exchange: Option<LastExchangeRecorder>,
 }
 
 impl ClientUnix {
@@ -33,14 +84,57 @@ impl ClientUnix {
     /// }
     /// ```
     pub async fn try_new(socket_path: impl AsRef<Path>) -> Result<Self, Error> {
-        let socket_path = PathBuf::from(socket_path.as_ref().to_path_buf());
-        ClientUnix::try_connect(socket_path).await
+        ClientUnix::builder(socket_path).try_build().await
+    }
+
+    /// Create a [ClientUnixBuilder] to configure the HTTP client before connecting it.
+    pub fn builder(socket_path: impl AsRef<Path>) -> ClientUnixBuilder {
+        ClientUnixBuilder::new(socket_path)
+    }
+
+    /// Create a [ClientUnixBuilder] connecting to `service` through in-process pipes instead of a socket **(feature = server)**
+    ///
+    /// Every connection serves `service`, like an `axum::Router`, in a [tokio::task] over an in-memory duplex pipe, so handlers can be tested through the real client code path without creating any socket file.
+    ///
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    /// use axum::{Router, routing::get};
+    ///
+    /// pub async fn get_hello_in_process() {
+    ///     let client = ClientUnix::builder_in_process(Router::new().route("/hello", get(|| async { "Hello" })))
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    ///
+    ///     let (_, response) = client
+    ///         .send_request("/hello", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request");
+    ///     assert_eq!(response, b"Hello");
+    /// }
+    /// ```
+    #[cfg(feature = "server")]
+    pub fn builder_in_process<S, B>(service: S) -> ClientUnixBuilder
+    where
+        S: tower_service::Service<Request<hyper::body::Incoming>, Response = hyper::Response<B>>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        B: hyper::body::Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        ClientUnixBuilder::new_in_process(crate::server::InProcessConnector::new(service))
     }
 
     /// Reconnect to an existing [ClientUnix].
     ///
-    /// Sometimes the server to which the client is connected may reboot, causing the client to disconnect. For simplicity, no automatic reconnection is implemented - it must be manually performed by calling this function.
-    /// The error will be probably trigger during the [ClientUnix::send_request](or [ClientUnix::send_request_json]) with this error [Error::RequestSend].
+    /// Sometimes the server to which the client is connected may reboot, causing the client to disconnect. The pool replaces the idle connections already closed by the server before using them, and doesn't reuse a connection lost during a request, so the next requests open new connections on their own.
+    /// A request sent on a connection whose closing the client didn't notice yet, like right after the reboot, still fails with [Error::ConnectionLost] (or [Error::RequestSend]) during the [ClientUnix::send_request](or [ClientUnix::send_request_json]). Calling this function then replaces every idle connection at once and checks that the server is reachable again, instead of letting the next requests fail on the other stale connections.
     /// # Example
     /// ```rust
     /// use http_client_unix_domain_socket::{ClientUnix, Method, Error, ErrorAndResponse};
@@ -51,48 +145,721 @@ impl ClientUnix {
     ///
     ///     if(matches!(
     ///         response_result.err(),
-    ///         Some(ErrorAndResponse::InternalError(Error::RequestSend(e)))
-    ///             if e.is_canceled()
+    ///         Some(ErrorAndResponse::InternalError(Error::ConnectionLost(_)))
     ///     )){
     ///         client = client.try_reconnect().await.expect("client.try_reconnect");
     ///     }
     /// }
     /// ```
     pub async fn try_reconnect(self) -> Result<Self, Error> {
-        let socket_path = self.socket_path.clone();
-        self.abort().await;
-        ClientUnix::try_connect(socket_path).await
+        for pool in &self.inner.pools {
+            pool.try_reconnect().await?;
+        }
+        Ok(self)
     }
 
-    /// Abort the [ClientUnix] connection [JoinHandle].
-    ///
-    /// Used for stopping the connection [JoinHandle]([tokio::task]), it's also used for [ClientUnix::try_reconnect]. The returned [Error] can be used to know if it was stopped without any error.
-    pub async fn abort(self) -> Option<Error> {
-        self.join_handle.abort();
-        self.join_handle.await.ok()
+    /// Snapshot of the cumulative counters of the client and all its clones, to build a health dashboard.
+    pub fn stats(&self) -> ClientStats {
+        let in_flight = self.inner.pools.iter().map(|pool| pool.in_flight()).sum();
+        let pool = self.inner.pools.iter().map(|pool| pool.stats()).fold(
+            PoolStats::default(),
+            |total, pool| PoolStats {
+                total: total.total + pool.total,
+                idle: total.idle + pool.idle,
+                in_use: total.in_use + pool.in_use,
+                waiting: total.waiting + pool.waiting,
+            },
+        );
+        self.config().stats.snapshot(in_flight, pool)
     }
 
-    async fn try_connect(socket_path: PathBuf) -> Result<Self, Error> {
-        let stream = TokioIo::new(
-            UnixStream::connect(socket_path.clone())
-                .await
-                .map_err(Error::SocketConnectionInitiation)?,
-        );
+    /// Most recent request sent by the client or one of its clones, with its response, enabled with [ClientUnixBuilder::capture_last_exchange].
+    ///
+    /// Useful to inspect a failure deep in application code without re-running it with wire logging. [None] when the capture is disabled or no request was sent.
+    pub fn last_exchange(&self) -> Option<LastExchange> {
+        self.inner.last_exchange.as_ref()?.last()
+    }
 
-        let (sender, connection) = http1::handshake(stream).await.map_err(Error::Handhsake)?;
+    /// Abort the [ClientUnix] idle connections [JoinHandle](tokio::task::JoinHandle).
+    ///
+    /// Used for stopping the connections [JoinHandle](tokio::task::JoinHandle)([tokio::task]), it's also used for [ClientUnix::try_reconnect]. The returned [Error] can be used to know if they were stopped without any error.
+    pub async fn abort(self) -> Option<Error> {
+        let mut error = None;
+        for pool in &self.inner.pools {
+            let pool_error = pool.close_idle().await;
+            error = error.or(pool_error);
+        }
+        error
+    }
 
-        let join_handle =
-            tokio::task::spawn(
-                async move { Error::SocketConnectionClosed(connection.await.err()) },
+    pub(crate) async fn try_connect(
+        socket_paths: Vec<PathBuf>,
+        mut config: ClientConfig,
+    ) -> Result<Self, Error> {
+        config.bandwidth = config
+            .max_bandwidth
+            .map(|bytes_per_second| Arc::new(Bandwidth::new(bytes_per_second)));
+        config.stats = Arc::default();
+        config.request_defaults = RequestDefaults::try_new(&config)?;
+        #[cfg(feature = "debug-wire")]
+        if let Some(debug_wire) = &mut config.debug_wire {
+            debug_wire.redact_headers(&config.redacted_headers);
+        }
+        #[cfg(feature = "json")]
+        if let Some(har_recorder) = &mut config.har_recorder {
+            har_recorder.redact_headers(&config.redacted_headers);
+        }
+        #[cfg(feature = "json")]
+        if let Some(cassette) = &mut config.cassette {
+            cassette.redact_headers(&config.redacted_headers);
+        }
+        let mut pools = Vec::with_capacity(socket_paths.len());
+        for socket_path in socket_paths {
+            pools.push(Arc::new(Pool::try_new(socket_path, config.clone()).await?));
+        }
+        if let Some(idle_timeout) = config.idle_timeout {
+            idle_reaper::spawn(
+                idle_timeout,
+                config.clock.clone(),
+                pools.iter().map(Arc::downgrade).collect(),
+            );
+        }
+        if let Some(keep_alive) = &config.keep_alive {
+            keep_alive::spawn(
+                keep_alive.clone(),
+                config.clock.clone(),
+                pools.iter().map(Arc::downgrade).collect(),
             );
+        }
 
         Ok(ClientUnix {
-            socket_path,
-            sender,
-            join_handle,
+            inner: Arc::new(ClientInner {
+                balancer: Balancer::new(config.load_balancing),
+                coalescer: config
+                    .coalesce_requests
+                    .then(|| Arc::new(Coalescer::default())),
+                cache: config
+                    .response_cache
+                    .map(|max_entries| ResponseCache::new(max_entries, config.clock.clone())),
+                in_flight_limit: config.max_in_flight_requests.map(|max_in_flight_requests| {
+                    Arc::new(PrioritySemaphore::new(max_in_flight_requests))
+                }),
+                queue: config.max_queued_requests.map(|max_queued_requests| {
+                    RequestQueue::new(max_queued_requests, config.queue_timeout)
+                }),
+                rate_limiter: config.rate_limit.map(|(requests_per_second, burst)| {
+                    RateLimiter::new(requests_per_second, burst, config.clock.clone())
+                }),
+                validators: ValidatorStore::default(),
+                last_exchange: config.last_exchange.map(|max_body_size| {
+                    LastExchangeRecorder::new(max_body_size, config.redacted_headers.clone())
+                }),
+                pools,
+                config,
+            }),
+            priority: Priority::default(),
+            wait_for_queue: true,
+            lease: None,
         })
     }
 
+    pub(crate) fn config(&self) -> &ClientConfig {
+        &self.inner.config
+    }
+
+    /// Pool of the socket the next request is sent to.
+    pub(crate) fn select_pool(&self) -> &Arc<Pool> {
+        self.inner.balancer.select(&self.inner.pools)
+    }
+
+    pub(crate) fn validators(&self) -> &ValidatorStore {
+        &self.inner.validators
+    }
+
+    /// Wait until the connection can send a new request.
+    async fn ready(&self, connection: &mut PooledConnection) -> Result<(), Error> {
+        let sender = &mut connection.sender;
+        let closed = match self.config().fail_fast {
+            true if !sender.is_closed() => return Ok(()),
+            true => None,
+            false => match sender.ready().await {
+                Ok(()) => return Ok(()),
+                Err(e) => Some(e),
+            },
+        };
+
+        // Without keep-alive, HTTP/1.0 servers close the connection after each response.
+        if self.config().http_version != Version::HTTP_10 {
+            return Err(Error::ConnectionLost(closed));
+        }
+        connection.reconnect().await
+    }
+
+    pub(crate) async fn send(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<(Parts, Bytes), Error> {
+        self.send_with_extensions(endpoint, method, headers, body_request, &Extensions::new())
+            .await
+    }
+
+    /// Send the request, `extensions` are inserted in the extensions of the sent [Request].
+    pub(crate) async fn send_with_extensions(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        let Some(threshold) = self.config().slow_request_threshold else {
+            return self
+                .send_cached(endpoint, method, headers, body_request, extensions)
+                .await;
+        };
+        let started = self.config().clock.now();
+        let result = self
+            .send_cached(endpoint, method.clone(), headers, body_request, extensions)
+            .await;
+
+        let elapsed = self.config().clock.now().duration_since(started);
+        if elapsed >= threshold {
+            let (outcome, request_id) = match &result {
+                Ok((parts, _)) => (
+                    parts.status.to_string(),
+                    parts.extensions.get::<RequestId>(),
+                ),
+                Err(e) => (format!("error: {}", e.inner()), e.request_id()),
+            };
+            match request_id {
+                Some(request_id) => log::warn!(
+                    "slow request: {} {} -> {} in {:?} (request id {})",
+                    method,
+                    endpoint,
+                    outcome,
+                    elapsed,
+                    request_id
+                ),
+                None => log::warn!(
+                    "slow request: {} {} -> {} in {:?}",
+                    method,
+                    endpoint,
+                    outcome,
+                    elapsed
+                ),
+            }
+        }
+        result
+    }
+
+    async fn send_cached(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        let cache = match &self.inner.cache {
+            // Requests of a lease have to reach its connection.
+            Some(cache)
+                if self.lease.is_none()
+                    && method == Method::GET
+                    && body_request.as_ref().is_none_or(|b| b.is_end_stream()) =>
+            {
+                cache
+            }
+            _ => {
+                return self
+                    .coalesce(endpoint, method, headers, body_request, extensions)
+                    .await;
+            }
+        };
+        if let Some(cached) = cache.get(endpoint, headers) {
+            return Ok(cached);
+        }
+
+        let (parts, body_response) = self
+            .coalesce(endpoint, method, headers, body_request, extensions)
+            .await?;
+        cache.store(endpoint, headers, &parts, &body_response);
+        Ok((parts, body_response))
+    }
+
+    async fn coalesce(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        let coalescer = match &self.inner.coalescer {
+            Some(coalescer)
+                if self.lease.is_none()
+                    && method == Method::GET
+                    && body_request.as_ref().is_none_or(|b| b.is_end_stream()) =>
+            {
+                coalescer
+            }
+            _ => {
+                return self
+                    .execute(endpoint, method, headers, body_request, extensions)
+                    .await;
+            }
+        };
+
+        let key = format!("{}\n{:?}", endpoint, headers);
+        let leader = match coalescer.join(key) {
+            Role::Leader(leader) => leader,
+            Role::Follower(receiver) => match wait_leader(receiver).await {
+                Some(shared_response) => return Ok(shared_response.into_parts()),
+                // The leader failed, errors are not shared so send the request again.
+                None => {
+                    return self
+                        .execute(endpoint, method, headers, body_request, extensions)
+                        .await;
+                }
+            },
+        };
+
+        let (parts, body_response) = self
+            .execute(endpoint, method, headers, body_request, extensions)
+            .await?;
+        leader.complete(SharedResponse::new(&parts, &body_response));
+        Ok((parts, body_response))
+    }
+
+    /// Send the request, retrying it according to the [crate::RetryPolicy].
+    async fn execute(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        let idempotency_key = self
+            .config()
+            .idempotency_keys
+            .as_ref()
+            .and_then(|generator| idempotency_key(generator, &method, headers));
+        let headers_with_key;
+        let headers = match &idempotency_key {
+            Some(key) => {
+                headers_with_key = [headers, &[(IDEMPOTENCY_KEY_HEADER, key.as_str())]].concat();
+                &headers_with_key
+            }
+            None => headers,
+        };
+
+        let Some(retry_policy) = &self.config().retry_policy else {
+            return self
+                .execute_once(endpoint, method, headers, body_request, extensions)
+                .await;
+        };
+
+        // The body is consumed by each attempt, buffer it to be able to send it again.
+        let body_request = match body_request {
+            Some(body_request) => Some(
+                body_request
+                    .collect()
+                    .await
+                    .map_err(Error::RequestCollect)?
+                    .to_bytes(),
+            ),
+            None => None,
+        };
+
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .execute_once(
+                    endpoint,
+                    method.clone(),
+                    headers,
+                    body_request.clone().map(Body::from),
+                    extensions,
+                )
+                .await;
+            let (parts, body_response) = match result {
+                Ok(response) => response,
+                // Only the requests with a key can be processed twice safely.
+                Err(e) if idempotency_key.is_some() && e.is_retryable() => {
+                    match retry_policy.delay(attempt, None) {
+                        Some(delay) => self.config().clock.sleep(delay).await,
+                        None => return Err(e),
+                    }
+                    attempt += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if !is_retryable(parts.status) {
+                return Ok((parts, body_response));
+            }
+
+            let retry_after = parts
+                .headers
+                .get(RETRY_AFTER)
+                .and_then(|value| parse_retry_after(value, self.config().clock.system_time()));
+            match retry_policy.delay(attempt, retry_after) {
+                Some(delay) => self.config().clock.sleep(delay).await,
+                None => return Ok((parts, body_response)),
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn execute_once(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        #[cfg(feature = "json")]
+        if let Some(cassette) = &self.config().cassette {
+            let body_request = match body_request {
+                Some(body_request) => body_request
+                    .collect()
+                    .await
+                    .map_err(Error::RequestCollect)?
+                    .to_bytes(),
+                None => Bytes::new(),
+            };
+            if cassette.is_replaying() {
+                return cassette.replay(&method, endpoint, &body_request);
+            }
+            let (parts, body_response) = self
+                .exchange(
+                    endpoint,
+                    method.clone(),
+                    headers,
+                    Some(Body::from(body_request.clone())),
+                    extensions,
+                )
+                .await?;
+            cassette.record(
+                &method,
+                endpoint,
+                headers,
+                &body_request,
+                &parts,
+                &body_response,
+            )?;
+            return Ok((parts, body_response));
+        }
+        self.exchange(endpoint, method, headers, body_request, extensions)
+            .await
+    }
+
+    /// Send the request and read the whole response body.
+    async fn exchange(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, Bytes), Error> {
+        let request = self.build_request(endpoint, method, headers, body_request, extensions)?;
+        self.exchange_request(request).await
+    }
+
+    /// Send a built request and read the whole response body.
+    pub(crate) async fn exchange_request(
+        &self,
+        request: Request<Body>,
+    ) -> Result<(Parts, Bytes), Error> {
+        let fault = self
+            .config()
+            .fault_injection
+            .as_ref()
+            .map(FaultInjection::draw)
+            .unwrap_or_default();
+        if let Some(delay) = fault.delay {
+            self.config().clock.sleep(delay).await;
+        }
+        if fault.error {
+            return Err(Error::ConnectionLost(None));
+        }
+
+        let (mut parts, body_response) = self.start_request(request).await?;
+        let body_started = Instant::now();
+        let mut collected = body_response.collect_with_trailers().await;
+        if let Some(request_id) = parts.extensions.get::<RequestId>() {
+            collected = collected.map_err(|e| request_id.wrap(e));
+        }
+        if let (Some(last_exchange), Some(exchange_id)) = (
+            &self.inner.last_exchange,
+            parts.extensions.remove::<ExchangeId>(),
+        ) {
+            match &collected {
+                Ok((body_response, _)) => last_exchange.body(exchange_id, body_response),
+                Err(e) => last_exchange.error(exchange_id, e),
+            }
+        }
+        let (mut body_response, trailers) = collected?;
+        #[cfg(feature = "debug-wire")]
+        if let Some(debug_wire) = &self.config().debug_wire {
+            debug_wire.log_response_body(&body_response);
+        }
+        if let Some(timing) = parts.extensions.get_mut::<Timing>() {
+            timing.body = body_started.elapsed();
+            self.config().stats.latency(timing.total());
+        }
+        if let Some(trailers) = trailers {
+            parts.extensions.insert(Trailers(trailers));
+        }
+        if let Some(wire_capture) = parts.extensions.remove::<WireCapture>() {
+            parts.extensions.insert(wire_capture.take());
+        }
+        #[cfg(feature = "json")]
+        if let (Some(har_recorder), Some(har_request)) = (
+            &self.config().har_recorder,
+            parts.extensions.remove::<HarRequest>(),
+        ) {
+            har_recorder.record(har_request, &parts, &body_response);
+        }
+        if let Some(truncated_len) = fault.truncated_len(body_response.len()) {
+            body_response.truncate(truncated_len);
+        }
+        if let Some(response_body_rewrite) = &self.config().response_body_rewrite {
+            body_response = response_body_rewrite.rewrite(&parts, body_response)?;
+        }
+        Ok((parts, body_response))
+    }
+
+    /// Send the request and wait for the response head, the body is left to be read.
+    pub(crate) async fn start(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<(Parts, ResponseBody), Error> {
+        let request = self.build_request(endpoint, method, headers, body_request, extensions)?;
+        self.start_request(request).await
+    }
+
+    pub(crate) async fn start_request(
+        &self,
+        request: Request<Body>,
+    ) -> Result<(Parts, ResponseBody), Error> {
+        let stats = &self.config().stats;
+        stats.request(request.method());
+        let result = self.send_head(request).await;
+        stats.response(result.as_ref().ok().map(|(parts, _)| parts.status));
+        result
+    }
+
+    pub(crate) fn build_request(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        extensions: &Extensions,
+    ) -> Result<Request<Body>, Error> {
+        let request_defaults = &self.config().request_defaults;
+        let mut request_builder = Request::builder();
+        for header in headers {
+            request_builder = request_builder.header(header.0, header.1);
+        }
+        if let Some(host) = request_defaults.host()
+            && !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("host"))
+        {
+            request_builder = request_builder.header(HOST, host.clone());
+        }
+        if let Some(user_agent) = request_defaults.user_agent()
+            && !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("user-agent"))
+        {
+            request_builder = request_builder.header(USER_AGENT, user_agent.clone());
+        }
+        let mut request = request_builder
+            .method(method)
+            .version(self.config().http_version)
+            .uri(request_defaults.uri(endpoint))
+            .body(body_request.unwrap_or_default())
+            .map_err(Error::RequestBuild)?;
+        request.extensions_mut().extend(extensions.clone());
+        Ok(request)
+    }
+
+    /// Send a built request through the hooks and recorders, then wait for the response head.
+    async fn send_head(&self, mut request: Request<Body>) -> Result<(Parts, ResponseBody), Error> {
+        let request_id = self
+            .config()
+            .request_id
+            .as_ref()
+            .map(|generator| generator.request_id(request.headers()));
+        if let Some(request_id) = &request_id {
+            if !request.headers().contains_key(REQUEST_ID_HEADER) {
+                request.headers_mut().insert(
+                    REQUEST_ID_HEADER,
+                    HeaderValue::from_str(&request_id.0)
+                        .map_err(|e| Error::RequestBuild(e.into()))?,
+                );
+            }
+            request.extensions_mut().insert(request_id.clone());
+        }
+        #[cfg(feature = "opentelemetry")]
+        trace_context::inject(request.headers_mut());
+        if let Some(request_body_rewrite) = &self.config().request_body_rewrite {
+            request = request_body_rewrite.rewrite(request).await?;
+        }
+        if let Some(request_signer) = &self.config().request_signer {
+            request = request_signer.sign(request).await?;
+        }
+        #[cfg(feature = "debug-wire")]
+        if let Some(debug_wire) = &self.config().debug_wire {
+            request = debug_wire.log_request(request).await?;
+        }
+        #[cfg(feature = "json")]
+        let har_request = match &self.config().har_recorder {
+            Some(har_recorder) => {
+                let har_request;
+                (request, har_request) = har_recorder.request(request).await?;
+                har_request
+            }
+            None => None,
+        };
+
+        let exchange_id = self
+            .inner
+            .last_exchange
+            .as_ref()
+            .map(|last_exchange| last_exchange.request(&request));
+        let mut result = self.dispatch(request).await;
+        if let Some(request_id) = &request_id {
+            result = result.map_err(|e| request_id.wrap(e));
+        }
+        if let (Some(last_exchange), Some(exchange_id)) = (&self.inner.last_exchange, exchange_id) {
+            match &result {
+                Ok((parts, _)) => last_exchange.response(exchange_id, parts),
+                Err(e) => last_exchange.error(exchange_id, e),
+            }
+        }
+        let (mut parts, body_response) = result?;
+        if let Some(exchange_id) = exchange_id {
+            parts.extensions.insert(exchange_id);
+        }
+        if let Some(request_id) = request_id {
+            parts.extensions.insert(request_id);
+        }
+        #[cfg(feature = "json")]
+        if let Some(har_request) = har_request {
+            parts.extensions.insert(har_request);
+        }
+        Ok((parts, body_response))
+    }
+
+    /// Wait for a connection, then send the request and wait for the response head.
+    async fn dispatch(&self, mut request: Request<Body>) -> Result<(Parts, ResponseBody), Error> {
+        if !self.config().absolute_form
+            && let Some(path_and_query) = request.uri().path_and_query()
+        {
+            *request.uri_mut() = path_and_query.clone().into();
+        }
+        let queued = Instant::now();
+        let place = match &self.inner.queue {
+            Some(queue) => Some(queue.enter(self.wait_for_queue).await?),
+            None => None,
+        };
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let in_flight_permit = match &self.inner.in_flight_limit {
+            Some(in_flight_limit) => Some(in_flight_limit.acquire(self.priority).await),
+            None => None,
+        };
+        let mut connection = match &self.lease {
+            Some(lease) => lease.acquire().await?,
+            None => self.select_pool().acquire(self.priority).await?,
+        };
+        drop(place);
+        self.ready(&mut connection).await?;
+
+        if let Some(Ok(mut head_recorder)) = connection.head_recorder.as_ref().map(|r| r.lock()) {
+            head_recorder.reset();
+        }
+        let wire_capture = connection
+            .wire_recorder
+            .as_ref()
+            .and_then(|wire_recorder| Some(wire_recorder.lock().ok()?.start()));
+
+        let in_flight = connection.start_request();
+        let dispatched = Instant::now();
+        let response = connection.sender.send_request(request).await.map_err(|e| {
+            match e.is_canceled() || e.is_closed() {
+                true => Error::ConnectionLost(Some(e)),
+                false => Error::from_hyper(e, Error::RequestSend),
+            }
+        })?;
+
+        let head_received = Instant::now();
+        let written = connection
+            .last_write()
+            .filter(|written| *written >= dispatched)
+            .unwrap_or(dispatched);
+
+        let (mut parts, body_response) = response.into_parts();
+        if !keeps_alive(&parts) {
+            connection.close_after_response();
+        }
+        #[cfg(feature = "debug-wire")]
+        if let Some(debug_wire) = &self.config().debug_wire {
+            debug_wire.log_response(&parts);
+        }
+        parts.extensions.insert(Timing {
+            queue: dispatched - queued,
+            write: written - dispatched,
+            time_to_first_byte: head_received - dispatched,
+            body: Duration::ZERO,
+        });
+        let original_headers = connection
+            .head_recorder
+            .as_ref()
+            .and_then(|head_recorder| head_recorder.lock().ok()?.take())
+            .and_then(|head| OriginalHeaders::parse(&head));
+        if let Some(original_headers) = original_headers {
+            parts.extensions.insert(original_headers);
+        }
+        if let Some(wire_capture) = wire_capture {
+            parts.extensions.insert(wire_capture);
+        }
+
+        Ok((
+            parts,
+            ResponseBody::new(body_response, in_flight, connection, in_flight_permit),
+        ))
+    }
+
+    /// Map an unsuccessful response to [ErrorAndResponse].
+    pub(crate) fn unsuccessful(&self, parts: &Parts, body_response: Bytes) -> ErrorAndResponse {
+        match self.config().retry_policy.is_some() && is_retryable(parts.status) {
+            true => ErrorAndResponse::RetriesExhausted(
+                parts.status,
+                parts
+                    .headers
+                    .get(RETRY_AFTER)
+                    .and_then(|value| parse_retry_after(value, self.config().clock.system_time())),
+                body_response.into(),
+            ),
+            false => ErrorAndResponse::ResponseUnsuccessful(parts.status, body_response.into()),
+        }
+    }
+
     /// Send a raw HTTP request.
     ///
     /// The [ClientUnix::send_request] method allows sending an HTTP request without serializing it. This method can be useful when communicating using a format other than JSON, or for endpoints that don’t return responses adhering to the JSON format. [Error] are wrapped in an Enum [ErrorAndResponse] that includes both [ErrorAndResponse::InternalError] and HTTP response [ErrorAndResponse::ResponseUnsuccessful].
@@ -102,7 +869,7 @@ impl ClientUnix {
     /// use http_client_unix_domain_socket::{ClientUnix, Method, StatusCode, ErrorAndResponse};
     ///
     /// pub async fn get_hello_world() {
-    ///     let mut client = ClientUnix::try_new("/tmp/unix.socket")
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
     ///         .await
     ///         .expect("ClientUnix::try_new");
     ///
@@ -129,7 +896,7 @@ impl ClientUnix {
     /// use http_client_unix_domain_socket::{ClientUnix, Method, StatusCode, Body};
     ///
     /// pub async fn post_hello_world() {
-    ///     let mut client = ClientUnix::try_new("/tmp/unix.socket")
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
     ///         .await
     ///         .expect("ClientUnix::try_new");
     ///
@@ -143,47 +910,122 @@ impl ClientUnix {
     /// }
     /// ```
     pub async fn send_request(
-        &mut self,
+        &self,
         endpoint: &str,
         method: Method,
         headers: &[(&str, &str)],
         body_request: Option<Body>,
     ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
-        let mut request_builder = Request::builder();
-        for header in headers {
-            request_builder = request_builder.header(header.0, header.1);
+        let (parts, body_response) = self
+            .send(endpoint, method, headers, body_request)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
         }
-        let request = request_builder
-            .method(method)
-            .uri(format!("http://unix.socket{}", endpoint))
-            .body(body_request.unwrap_or(Body::empty()))
-            .map_err(|e| ErrorAndResponse::InternalError(Error::RequestBuild(e)))?;
+        Ok((parts.status, body_response.into()))
+    }
 
-        let response = self
-            .sender
-            .send_request(request)
+    /// Send a raw HTTP request and return the response headers.
+    ///
+    /// Same as [ClientUnix::send_request] but the response headers are also returned as [OriginalHeaders]. Enable [ClientUnixBuilder::preserve_header_case] to keep the casing and ordering used by the server, for example when proxying the response to case-sensitive clients.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_headers() {
+    ///     let client = ClientUnix::builder("/tmp/unix.socket")
+    ///         .preserve_header_case(true)
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    ///
+    ///     let (_, headers, _) = client
+    ///         .send_request_with_headers("/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_with_headers");
+    ///
+    ///     for (name, value) in headers.iter() {
+    ///         println!("{}: {:?}", name, value);
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_with_headers(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<(StatusCode, OriginalHeaders, Vec<u8>), ErrorAndResponse> {
+        let (mut parts, body_response) = self
+            .send(endpoint, method, headers, body_request)
             .await
-            .map_err(|e| ErrorAndResponse::InternalError(Error::RequestSend(e)))?;
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
 
-        let status_code = response.status();
-        let body_response = response
-            .collect()
+        let headers = parts
+            .extensions
+            .remove::<OriginalHeaders>()
+            .unwrap_or_else(|| OriginalHeaders::from_header_map(&parts.headers));
+
+        Ok((parts.status, headers, body_response.into()))
+    }
+
+    /// Send a raw HTTP request, writing the response body into a buffer provided by the caller.
+    ///
+    /// Same as [ClientUnix::send_request] but the buffer is cleared and filled with the response body instead of allocating a new one, so a buffer reused in a hot loop doesn't allocate once its capacity fits the responses.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{BytesMut, ClientUnix, Method};
+    ///
+    /// pub async fn poll_status() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let mut buffer = BytesMut::with_capacity(1024);
+    ///     for _ in 0..1000 {
+    ///         client
+    ///             .send_request_into("/status", Method::GET, &[], None, &mut buffer)
+    ///             .await
+    ///             .expect("client.send_request_into");
+    ///         println!("{}", String::from_utf8_lossy(&buffer));
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_into(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+        buffer: &mut BytesMut,
+    ) -> Result<StatusCode, ErrorAndResponse> {
+        let (parts, body_response) = self
+            .send(endpoint, method, headers, body_request)
             .await
-            .map_err(|e| ErrorAndResponse::InternalError(Error::ResponseCollect(e)))?
-            .to_bytes();
+            .map_err(ErrorAndResponse::InternalError)?;
 
-        if !status_code.is_success() {
-            return Err(ErrorAndResponse::ResponseUnsuccessful(
-                status_code,
-                body_response.to_vec(),
-            ));
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
         }
-        Ok((status_code, body_response.to_vec()))
+
+        buffer.clear();
+        buffer.extend_from_slice(&body_response);
+        Ok(parts.status)
     }
 
     /// Send JSON HTTP request **(feature = json)**
     ///
-    /// Use [ClientUnix::send_request], adding automatically the "Content-Type" header and handling JSON (de)serialization for both the request body and response. This method does not use the same [Error] Enum, enabling typed error responses instead via [ErrorAndResponseJson].
+    /// Use [ClientUnix::send_request], adding automatically the "Content-Type" header and handling JSON (de)serialization for both the request body and response. This method does not use the same [Error] Enum, enabling typed error responses instead via [ErrorAndResponseJson]. Responses with a `charset` other than UTF-8 in their `Content-Type` header are transcoded before being parsed.
+    ///
+    /// The request is sent with `Content-Type: application/json`, unless another media type is given in `headers`, like `application/merge-patch+json`, the body still being serialized as JSON.
+    ///
+    /// Empty response bodies, like `204 No Content`, are deserialized as `null`: use `Option<OUT>` for endpoints that may answer without a body. With `OUT = ()` the response body is discarded without being parsed.
     /// # Examples
     /// ## HTTP POST JSON **(feature = json)**
     /// ```rust
@@ -206,7 +1048,7 @@ impl ClientUnix {
     /// }
     ///
     /// pub async fn post_hello_world() {
-    ///     let mut client = ClientUnix::try_new("/tmp/unix.socket")
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
     ///         .await
     ///         .expect("ClientUnix::try_new");
     ///
@@ -234,54 +1076,210 @@ impl ClientUnix {
     /// ```
     #[cfg(feature = "json")]
     pub async fn send_request_json<IN: Serialize, OUT: DeserializeOwned, ERR: DeserializeOwned>(
-        &mut self,
+        &self,
         endpoint: &str,
         method: Method,
         headers: &[(&str, &str)],
         body_request: Option<&IN>,
     ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
-        let mut headers = headers.to_vec();
-        headers.push(("Content-Type", "application/json"));
+        let (parts, body_response) = self
+            .send_json(endpoint, method, headers, body_request)
+            .await?;
+        Ok((
+            parts.status,
+            json::from_bytes(body_response).map_err(ErrorAndResponseJson::InternalError)?,
+        ))
+    }
 
-        let body_request = match body_request {
-            Some(body_request) => Body::from(
-                serde_json::to_vec(body_request)
-                    .map_err(|e| ErrorAndResponseJson::InternalError(Error::RequestParsing(e)))?,
-            ),
-            None => Body::empty(),
-        };
+    /// Send JSON HTTP request and keep the response as a [RawValue] **(feature = json)**
+    ///
+    /// Same as [ClientUnix::send_request_json] but the response is only validated, not parsed, so huge documents can be forwarded as is or parsed later. Nested [RawValue] fields can also be used in the `OUT` type of [ClientUnix::send_request_json], except with the `simd-json` feature.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    /// use serde_json::Value;
+    ///
+    /// pub async fn forward_inventory() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, inventory) = client
+    ///         .send_request_json_raw::<(), Value>("/inventory", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_json_raw");
+    ///     println!("{}", inventory.get());
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub async fn send_request_json_raw<IN: Serialize, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<(StatusCode, Box<RawValue>), ErrorAndResponseJson<ERR>> {
+        let (parts, body_response) = self
+            .send_json(endpoint, method, headers, body_request)
+            .await?;
+        Ok((
+            parts.status,
+            serde_json::from_slice(&body_response)
+                .map_err(|e| ErrorAndResponseJson::InternalError(Error::ResponseParsing(e)))?,
+        ))
+    }
+
+    /// Send JSON HTTP request and keep the response body alive for zero-copy deserialization **(feature = json)**
+    ///
+    /// Same as [ClientUnix::send_request_json] but the response is returned as a [BorrowedJson], deserialized with [BorrowedJson::parse] into types implementing `Deserialize<'de>` instead of `DeserializeOwned`. Fields like `&str` or `&[u8]` then point into the response body, avoiding allocations for very large responses.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    /// use serde::Deserialize;
+    /// use serde_json::Value;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct HelloJson<'a> {
+    ///     hello: &'a str,
+    /// }
+    ///
+    /// pub async fn get_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let response = client
+    ///         .send_request_json_borrowed::<(), Value>("/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_json_borrowed");
+    ///     let hello: HelloJson = response.parse().expect("response.parse");
+    ///     println!("{}", hello.hello);
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub async fn send_request_json_borrowed<IN: Serialize, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<BorrowedJson, ErrorAndResponseJson<ERR>> {
+        let (parts, body_response) = self
+            .send_json(endpoint, method, headers, body_request)
+            .await?;
+        Ok(BorrowedJson::new(parts.status, body_response))
+    }
 
-        match self
-            .send_request(endpoint, method, &headers, Some(body_request))
+    /// Send a JSON GET request, a `404 Not Found` response is returned as [None] **(feature = json)**
+    ///
+    /// Convenience over [ClientUnix::send_request_json] for lookups of resources that may be absent, other unsuccessful responses are still returned as errors.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    /// use serde_json::Value;
+    ///
+    /// pub async fn get_key() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     match client
+    ///         .get_json_opt::<Value, Value>("/keys/nolanv", &[])
+    ///         .await
+    ///         .expect("client.get_json_opt")
+    ///     {
+    ///         Some(value) => println!("{}", value),
+    ///         None => println!("absent"),
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub async fn get_json_opt<OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<Option<OUT>, ErrorAndResponseJson<ERR>> {
+        let (parts, body_response) = self
+            .send(endpoint, Method::GET, headers, None)
+            .await
+            .map_err(ErrorAndResponseJson::InternalError)?;
+        let body_response = text::to_utf8(&parts.headers, body_response);
+
+        if parts.status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !parts.status.is_success() {
+            return Err(ErrorAndResponseJson::from_error_and_response(
+                self.unsuccessful(&parts, body_response),
+            ));
+        }
+        json::from_bytes(body_response)
+            .map(Some)
+            .map_err(ErrorAndResponseJson::InternalError)
+    }
+
+    /// Serialize the request and send it, returning the body of a successful response.
+    #[cfg(feature = "json")]
+    pub(crate) async fn send_json<IN: Serialize, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<(Parts, Bytes), ErrorAndResponseJson<ERR>> {
+        self.send_json_unparsed(endpoint, method, headers, body_request)
             .await
+            .map_err(ErrorAndResponseJson::from_error_and_response)
+    }
+
+    /// Same as [ClientUnix::send_json] but the body of the unsuccessful responses is not parsed.
+    #[cfg(feature = "json")]
+    pub(crate) async fn send_json_unparsed<IN: Serialize>(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<(Parts, Bytes), ErrorAndResponse> {
+        let mut headers = headers.to_vec();
+        if !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
         {
-            Ok((status_code, response)) => Ok((
-                status_code,
-                serde_json::from_slice(&response)
-                    .map_err(|e| ErrorAndResponseJson::InternalError(Error::ResponseParsing(e)))?,
-            )),
-            Err(ErrorAndResponse::InternalError(e)) => Err(ErrorAndResponseJson::InternalError(e)),
-            Err(ErrorAndResponse::ResponseUnsuccessful(status_code, response)) => {
-                Err(ErrorAndResponseJson::ResponseUnsuccessful(
-                    status_code,
-                    serde_json::from_slice(&response).map_err(|e| {
-                        ErrorAndResponseJson::InternalError(Error::ResponseParsing(e))
-                    })?,
-                ))
-            }
+            headers.push(("Content-Type", "application/json"));
+        }
+        let body_request = json::to_body(body_request).map_err(ErrorAndResponse::InternalError)?;
+
+        let (parts, body_response) = self
+            .send(endpoint, method, &headers, Some(body_request))
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+        // JSON parsers only accept UTF-8, bodies in another charset are transcoded first.
+        let body_response = text::to_utf8(&parts.headers, body_response);
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
         }
+        Ok((parts, body_response))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helpers::{server::Server, util::*};
+    use crate::{
+        ErrorKind, RetryPolicy,
+        test_helpers::{raw_server::RawServer, server::Server, util::*},
+    };
     use hyper::Method;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
 
     #[tokio::test]
     async fn simple_request() {
-        let (_, mut client) = make_client_server("simple_request").await;
+        let (_, client) = make_client_server("simple_request").await;
 
         let (status_code, response) = client
             .send_request("/nolanv", Method::GET, &[], None)
@@ -294,7 +1292,7 @@ mod tests {
 
     #[tokio::test]
     async fn simple_404_request() {
-        let (_, mut client) = make_client_server("simple_404_request").await;
+        let (_, client) = make_client_server("simple_404_request").await;
 
         let result = client
             .send_request("/nolanv/nope", Method::GET, &[], None)
@@ -309,7 +1307,7 @@ mod tests {
 
     #[tokio::test]
     async fn multiple_request() {
-        let (_, mut client) = make_client_server("multiple_request").await;
+        let (_, client) = make_client_server("multiple_request").await;
 
         for i in 0..20 {
             let (status_code, response) = client
@@ -323,6 +1321,140 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn coalesce_requests() {
+        let socket_path = make_socket_path_test("client", "coalesce_requests");
+        let hits = Arc::new(AtomicUsize::new(0));
+        let server_hits = hits.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |_| {
+            let hit = server_hits.fetch_add(1, Ordering::SeqCst) + 1;
+            format!("HTTP/1.1 200 OK\r\ncontent-length: 1\r\n\r\n{}", hit).into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .coalesce_requests(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let responses = futures_util::future::join_all(
+            (0..5).map(|_| client.send_request("/nolanv", Method::GET, &[], None)),
+        )
+        .await;
+        for response in responses {
+            assert_eq!(response.expect("client.send_request").1, b"1");
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(response, b"2");
+    }
+
+    #[tokio::test]
+    async fn response_cache() {
+        let socket_path = make_socket_path_test("client", "response_cache");
+        let hits = Arc::new(AtomicUsize::new(0));
+        let server_hits = hits.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |_| {
+            let hit = server_hits.fetch_add(1, Ordering::SeqCst) + 1;
+            format!(
+                "HTTP/1.1 200 OK\r\ncache-control: max-age=60\r\ncontent-length: 1\r\n\r\n{}",
+                hit
+            )
+            .into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .response_cache(16)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        for _ in 0..3 {
+            let (_, response) = client
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            assert_eq!(response, b"1");
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (_, response) = client
+            .send_request(
+                "/nolanv",
+                Method::GET,
+                &[("Cache-Control", "no-cache")],
+                None,
+            )
+            .await
+            .expect("client.send_request");
+        assert_eq!(response, b"2");
+        let (_, response) = client
+            .send_request("/nolanv", Method::POST, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(response, b"3");
+    }
+
+    #[tokio::test]
+    async fn max_request_bandwidth() {
+        let socket_path = make_socket_path_test("client", "max_request_bandwidth");
+        let _server = RawServer::try_new_with(&socket_path, false, |_| {
+            [
+                b"HTTP/1.1 200 OK\r\ncontent-length: 10000\r\n\r\n".as_slice(),
+                &[b'n'; 10000],
+            ]
+            .concat()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .max_request_bandwidth(50_000)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let start = tokio::time::Instant::now();
+        for _ in 0..2 {
+            let (_, response) = client
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            assert_eq!(response.len(), 10000);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn max_in_flight_requests() {
+        let socket_path = make_socket_path_test("client", "max_in_flight_requests");
+        let server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .max_in_flight_requests(2)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let responses = futures_util::future::join_all(
+            (0..10).map(|_| client.send_request("/nolanv", Method::GET, &[], None)),
+        )
+        .await;
+        for response in responses {
+            assert_eq!(response.expect("client.send_request").1, b"nolanv");
+        }
+        assert_eq!(server.connections(), 2);
+    }
+
     #[tokio::test]
     async fn server_not_started() {
         let socket_path = make_socket_path_test("client", "server_not_started");
@@ -334,22 +1466,108 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn connect_timeout() {
+        let socket_path = make_socket_path_test("client", "connect_timeout");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+
+        let client = ClientUnix::builder(&socket_path)
+            .connect_timeout(Duration::from_secs(1))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let (status_code, _) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(status_code, StatusCode::OK);
+
+        let client = client.try_reconnect().await;
+        assert!(client.is_ok());
+
+        // A server which never accepts, with its listen backlog full.
+        let stalled_path = make_socket_path_test("client", "connect_timeout_stalled");
+        let _ = std::fs::remove_file(&stalled_path);
+        let listener = socket2::Socket::new(socket2::Domain::UNIX, socket2::Type::STREAM, None)
+            .expect("Socket::new");
+        listener
+            .bind(&socket2::SockAddr::unix(&stalled_path).expect("SockAddr::unix"))
+            .expect("Socket::bind");
+        listener.listen(0).expect("Socket::listen");
+        let mut queued = Vec::new();
+        while let Ok(stream) = tokio::net::UnixStream::connect(&stalled_path).await {
+            queued.push(stream);
+        }
+
+        let result = ClientUnix::builder(&stalled_path)
+            .connect_timeout(Duration::from_millis(50))
+            .try_build()
+            .await;
+        let Err(error) = result else {
+            panic!("ClientUnixBuilder::try_build connected");
+        };
+        assert!(matches!(error, Error::ConnectTimeout(_)), "{:?}", error);
+        assert_eq!(error.kind(), ErrorKind::Timeout);
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        let socket_path = make_socket_path_test("client", "read_timeout");
+        let _server = RawServer::try_new(&socket_path, b"")
+            .await
+            .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .read_timeout(Duration::from_millis(50))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let response_result = client.send_request("/nolanv", Method::GET, &[], None).await;
+        assert!(matches!(
+            response_result.err(),
+            Some(ErrorAndResponse::InternalError(Error::StreamTimeout(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_timeout_idle_connection() {
+        let socket_path = make_socket_path_test("client", "read_timeout_idle_connection");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .read_timeout(Duration::from_millis(20))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        for _ in 0..2 {
+            let (status_code, _) = client
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            assert_eq!(status_code, StatusCode::OK);
+            tokio::time::sleep(Duration::from_millis(60)).await;
+        }
+    }
+
     #[tokio::test]
     async fn server_stopped() {
-        let (server, mut client) = make_client_server("server_stopped").await;
+        let (server, client) = make_client_server("server_stopped").await;
         server.abort().await;
 
         let response_result = client.send_request("/nolanv", Method::GET, &[], None).await;
         assert!(matches!(
             response_result.err(),
-                         Some(ErrorAndResponse::InternalError(Error::RequestSend(e)))
-                         if e.is_canceled()
+            Some(ErrorAndResponse::InternalError(Error::ConnectionLost(_)))
         ));
 
         let _ = Server::try_new(&make_socket_path_test("client", "server_stopped"))
             .await
             .expect("Server::try_new");
-        let mut http_client = client.try_reconnect().await.expect("client.try_reconnect");
+        let http_client = client.try_reconnect().await.expect("client.try_reconnect");
 
         let (status_code, response) = http_client
             .send_request("/nolanv", Method::GET, &[], None)
@@ -361,21 +1579,97 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn server_rebooted() {
-        let (server, mut client) = make_client_server("server_rebooted").await;
-        server.abort().await;
+    async fn connection_close() {
+        let socket_path = make_socket_path_test("client", "connection_close");
+        let server = RawServer::try_new_with(&socket_path, false, |request| {
+            match request.starts_with(b"GET http://unix.socket/http10") {
+                true => b"HTTP/1.0 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec(),
+                false => b"HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 6\r\n\r\nnolanv"
+                    .to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
 
-        let _ = Server::try_new(&make_socket_path_test("client", "server_rebooted"))
+        for endpoint in ["/close", "/close", "/http10", "/http10"] {
+            let (status_code, _) = client
+                .send_request(endpoint, Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            assert_eq!(status_code, StatusCode::OK);
+            // Not given back to the pool, even before the server closes it.
+            assert_eq!(client.stats().pool.idle, 0);
+        }
+        assert_eq!(server.connections(), 4);
+
+        client
+            .with_connection(|client| async move {
+                for _ in 0..2 {
+                    client
+                        .send_request("/close", Method::GET, &[], None)
+                        .await
+                        .expect("client.send_request");
+                }
+            })
+            .await
+            .expect("client.with_connection");
+        assert_eq!(server.connections(), 6);
+    }
+
+    #[tokio::test]
+    async fn fail_fast() {
+        let socket_path = make_socket_path_test("client", "fail_fast");
+        let server = Server::try_new(&socket_path)
             .await
             .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .fail_fast(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
 
+        let (status_code, _) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(status_code, StatusCode::OK);
+
+        server.abort().await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // The closed connection is dropped, and the server is no longer there to open a new one.
         let response_result = client.send_request("/nolanv", Method::GET, &[], None).await;
         assert!(matches!(
             response_result.err(),
-                         Some(ErrorAndResponse::InternalError(Error::RequestSend(e)))
-                         if e.is_canceled()
+            Some(ErrorAndResponse::InternalError(
+                Error::SocketConnectionInitiation(_)
+            ))
         ));
-        let mut http_client = client.try_reconnect().await.expect("client.try_reconnect");
+    }
+
+    #[tokio::test]
+    async fn server_rebooted() {
+        let (server, client) = make_client_server("server_rebooted").await;
+        server.abort().await;
+
+        let _ = Server::try_new(&make_socket_path_test("client", "server_rebooted"))
+            .await
+            .expect("Server::try_new");
+
+        // Unless the client already noticed the closed connection and replaced it.
+        let response_result = client.send_request("/nolanv", Method::GET, &[], None).await;
+        assert!(
+            matches!(
+                response_result,
+                Ok(_) | Err(ErrorAndResponse::InternalError(Error::ConnectionLost(_)))
+            ),
+            "{:?}",
+            response_result
+        );
+        let http_client = client.try_reconnect().await.expect("client.try_reconnect");
 
         let (status_code, response) = http_client
             .send_request("/nolanv", Method::GET, &[], None)
@@ -385,6 +1679,362 @@ mod tests {
         assert_eq!(status_code, StatusCode::OK);
         assert_eq!(response, "Hello nolanv".as_bytes())
     }
+
+    #[tokio::test]
+    async fn send_request_into() {
+        let socket_path = make_socket_path_test("client", "send_request_into");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n5\r\nHello\r\n7\r\n nolanv\r\n0\r\n\r\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let mut buffer = BytesMut::with_capacity(64);
+        let allocation = buffer.as_ptr();
+        for _ in 0..3 {
+            let status_code = client
+                .send_request_into("/nolanv", Method::GET, &[], None, &mut buffer)
+                .await
+                .expect("client.send_request_into");
+
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(&buffer[..], b"Hello nolanv");
+            assert_eq!(buffer.as_ptr(), allocation);
+        }
+    }
+
+    #[tokio::test]
+    async fn response_headers() {
+        let (_, client) = make_client_server("response_headers").await;
+
+        let (status_code, headers, response) = client
+            .send_request_with_headers("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_with_headers");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, "Hello nolanv".as_bytes());
+        assert_eq!(
+            headers.get("Content-Type").map(|v| v.as_bytes()),
+            Some("text/plain; charset=utf-8".as_bytes())
+        );
+        assert!(headers.iter().all(|(name, _)| name == name.to_lowercase()));
+    }
+
+    #[tokio::test]
+    async fn response_headers_preserve_case() {
+        let socket_path = make_socket_path_test("client", "response_headers_preserve_case");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\nX-Nolan: v\r\ncontent-length: 5\r\nx-NOLAN: w\r\n\r\nHello",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .preserve_header_case(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        for _ in 0..2 {
+            let (status_code, headers, response) = client
+                .send_request_with_headers("/", Method::GET, &[], None)
+                .await
+                .expect("client.send_request_with_headers");
+
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(response, "Hello".as_bytes());
+            let names: Vec<&str> = headers.iter().map(|(name, _)| name).collect();
+            assert_eq!(names, vec!["X-Nolan", "content-length", "x-NOLAN"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn title_case_headers() {
+        let socket_path = make_socket_path_test("client", "title_case_headers");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .title_case_headers(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[("x-nolan-name", "nolanv")], None)
+            .await
+            .expect("client.send_request");
+
+        let request = String::from_utf8(response).expect("String::from_utf8");
+        assert!(request.contains("X-Nolan-Name: nolanv\r\n"));
+    }
+
+    #[tokio::test]
+    async fn authority() {
+        let socket_path = make_socket_path_test("client", "authority");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .authority("nolanv.daemon")
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        let request = String::from_utf8(response).expect("String::from_utf8");
+        assert!(request.starts_with("GET http://nolanv.daemon/nolanv HTTP/1.1\r\n"));
+        assert!(request.contains("host: nolanv.daemon\r\n"));
+
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[("Host", "localhost")], None)
+            .await
+            .expect("client.send_request");
+        let request = String::from_utf8(response).expect("String::from_utf8");
+        assert!(request.contains("host: localhost\r\n"));
+        assert!(!request.contains("host: nolanv.daemon"));
+    }
+
+    #[tokio::test]
+    async fn user_agent() {
+        let socket_path = make_socket_path_test("client", "user_agent");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let user_agent = async |builder: ClientUnixBuilder, headers: &[(&str, &str)]| {
+            let client = builder
+                .try_build()
+                .await
+                .expect("ClientUnixBuilder::try_build");
+            let (_, response) = client
+                .send_request("/nolanv", Method::GET, headers, None)
+                .await
+                .expect("client.send_request");
+            String::from_utf8(response)
+                .expect("String::from_utf8")
+                .lines()
+                .find_map(|line| line.strip_prefix("user-agent: ").map(str::to_string))
+        };
+
+        assert_eq!(
+            user_agent(ClientUnix::builder(&socket_path), &[]).await,
+            Some(format!(
+                "http-client-unix-domain-socket/{}",
+                env!("CARGO_PKG_VERSION")
+            ))
+        );
+        assert_eq!(
+            user_agent(
+                ClientUnix::builder(&socket_path).user_agent("nolanv/1.0"),
+                &[]
+            )
+            .await,
+            Some("nolanv/1.0".to_string())
+        );
+        assert_eq!(
+            user_agent(
+                ClientUnix::builder(&socket_path),
+                &[("User-Agent", "caller/2.0")]
+            )
+            .await,
+            Some("caller/2.0".to_string())
+        );
+        assert_eq!(
+            user_agent(
+                ClientUnix::builder(&socket_path).send_user_agent(false),
+                &[]
+            )
+            .await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn origin_form() {
+        let socket_path = make_socket_path_test("client", "origin_form");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .authority("nolanv.daemon")
+            .absolute_form(false)
+            .capture_last_exchange(0)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (_, response) = client
+            .send_request("/nolanv?name=v", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        let request = String::from_utf8(response).expect("String::from_utf8");
+        assert!(request.starts_with("GET /nolanv?name=v HTTP/1.1\r\n"));
+        assert!(request.contains("host: nolanv.daemon\r\n"));
+        let exchange = client.last_exchange().expect("client.last_exchange");
+        assert_eq!(exchange.uri, "http://nolanv.daemon/nolanv?name=v");
+    }
+
+    #[tokio::test]
+    async fn http_version_1_0() {
+        let socket_path = make_socket_path_test("client", "http_version_1_0");
+        let _server = RawServer::try_new_with(&socket_path, true, |request| {
+            let mut response = format!(
+                "HTTP/1.0 200 OK\r\ncontent-length: {}\r\n\r\n",
+                request.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(request);
+            response
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .http_version(Version::HTTP_10)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        for _ in 0..3 {
+            let (_, response) = client
+                .send_request("/nolanv", Method::POST, &[], Some(Body::from("nolanv")))
+                .await
+                .expect("client.send_request");
+
+            let request = String::from_utf8(response).expect("String::from_utf8");
+            assert!(request.starts_with("POST http://unix.socket/nolanv HTTP/1.0\r\n"));
+            assert!(request.contains("content-length: 6\r\n"));
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after() {
+        let socket_path = make_socket_path_test("client", "retry_after");
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let server_attempts = attempts.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            assert!(request.ends_with(b"nolanv"));
+            match server_attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => {
+                    b"HTTP/1.1 429 Too Many Requests\r\nretry-after: 0\r\ncontent-length: 0\r\n\r\n"
+                        .to_vec()
+                }
+                1 => b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n".to_vec(),
+                _ => b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .retry_policy(RetryPolicy::new(2).base_delay(Duration::from_millis(1)))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (status_code, response) = client
+            .send_request("/nolanv", Method::POST, &[], Some(Body::from("nolanv")))
+            .await
+            .expect("client.send_request");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, b"ok");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_exhausted() {
+        let socket_path = make_socket_path_test("client", "retries_exhausted");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 429 Too Many Requests\r\nretry-after: 120\r\ncontent-length: 4\r\n\r\nslow",
+        )
+        .await
+        .expect("RawServer::try_new");
+        // The 120s asked by the server are capped to the max delay.
+        let client = ClientUnix::builder(&socket_path)
+            .retry_policy(RetryPolicy::new(3).max_delay(Duration::from_millis(1)))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let result = client.send_request("/nolanv", Method::GET, &[], None).await;
+
+        assert!(matches!(
+            result.err(),
+            Some(ErrorAndResponse::RetriesExhausted(status_code, Some(retry_after), body))
+                if status_code == StatusCode::TOO_MANY_REQUESTS
+                    && retry_after == Duration::from_secs(120)
+                    && body == b"slow"
+        ));
+    }
+
+    static SLOW_REQUESTS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    struct SlowRequestLogger;
+
+    impl log::Log for SlowRequestLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            SLOW_REQUESTS
+                .lock()
+                .expect("SLOW_REQUESTS.lock")
+                .push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[tokio::test]
+    async fn slow_request_threshold() {
+        let _ = log::set_logger(&SlowRequestLogger);
+        log::set_max_level(log::LevelFilter::Warn);
+        let socket_path = make_socket_path_test("client", "slow_request_threshold");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .slow_request_threshold(Duration::ZERO)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        client
+            .send_request("/slow_nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+
+        let client = ClientUnix::builder(&socket_path)
+            .slow_request_threshold(Duration::ZERO)
+            .request_id_generator(|| "nolanv".into())
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        client
+            .send_request("/slow_request_id", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+
+        let slow_requests = SLOW_REQUESTS.lock().expect("SLOW_REQUESTS.lock");
+        assert!(
+            slow_requests
+                .iter()
+                .any(|line| line.starts_with("slow request: GET /slow_nolanv -> 200 OK in "))
+        );
+        assert!(slow_requests.iter().any(|line| {
+            line.starts_with("slow request: GET /slow_request_id -> ")
+                && line.ends_with(" (request id nolanv)")
+        }));
+    }
 }
 
 #[cfg(feature = "json")]
@@ -394,7 +2044,11 @@ mod json_tests {
     use serde::{Deserialize, Serialize};
     use serde_json::{Value, json};
 
-    use crate::{error::ErrorAndResponseJson, test_helpers::util::make_client_server};
+    use crate::{
+        ClientUnix,
+        error::ErrorAndResponseJson,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
 
     #[derive(Deserialize, Debug)]
     struct ErrorJson {
@@ -403,7 +2057,7 @@ mod json_tests {
 
     #[tokio::test]
     async fn simple_get_request() {
-        let (_, mut client) = make_client_server("simple_get_request").await;
+        let (_, client) = make_client_server("simple_get_request").await;
 
         let (status_code, response) = client
             .send_request_json::<(), Value, Value>("/json/nolanv", Method::GET, &[], None)
@@ -414,9 +2068,107 @@ mod json_tests {
         assert_eq!(response.get("hello"), Some(&json!("nolanv")))
     }
 
+    #[tokio::test]
+    async fn raw_value_request() {
+        let (_, client) = make_client_server("raw_value_request").await;
+
+        let (status_code, response) = client
+            .send_request_json_raw::<(), Value>("/json/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_json_raw");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(
+            serde_json::from_str::<Value>(response.get()).expect("serde_json::from_str"),
+            json!({"hello": "nolanv"})
+        );
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    #[tokio::test]
+    async fn nested_raw_value_request() {
+        let (_, client) = make_client_server("nested_raw_value_request").await;
+
+        #[derive(Deserialize)]
+        struct HelloJson {
+            hello: Box<serde_json::value::RawValue>,
+        }
+
+        let (_, response) = client
+            .send_request_json::<(), HelloJson, Value>("/json/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_json");
+
+        assert_eq!(response.hello.get(), "\"nolanv\"");
+    }
+
+    #[tokio::test]
+    async fn borrowed_request() {
+        let (_, client) = make_client_server("borrowed_request").await;
+
+        #[derive(Deserialize)]
+        struct HelloJson<'a> {
+            hello: &'a str,
+        }
+
+        let response = client
+            .send_request_json_borrowed::<(), Value>("/json/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_json_borrowed");
+        let hello: HelloJson = response.parse().expect("response.parse");
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(hello.hello, "nolanv");
+        assert!(
+            response
+                .as_bytes()
+                .as_ptr_range()
+                .contains(&hello.hello.as_ptr())
+        );
+    }
+
+    #[tokio::test]
+    async fn no_content_request() {
+        let socket_path = make_socket_path_test("client", "no_content_request");
+        let _server = RawServer::try_new(&socket_path, b"HTTP/1.1 204 No Content\r\n\r\n")
+            .await
+            .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, response) = client
+            .send_request_json::<(), Option<Value>, Value>("/nolanv", Method::DELETE, &[], None)
+            .await
+            .expect("client.send_request_json");
+
+        assert_eq!(status_code, StatusCode::NO_CONTENT);
+        assert_eq!(response, None);
+    }
+
+    #[tokio::test]
+    async fn get_json_opt() {
+        let (_, client) = make_client_server("get_json_opt").await;
+
+        let response = client
+            .get_json_opt::<Value, ErrorJson>("/json/nolanv", &[])
+            .await
+            .expect("client.get_json_opt");
+        assert_eq!(
+            response.and_then(|response| response.get("hello").cloned()),
+            Some(json!("nolanv"))
+        );
+
+        let response = client
+            .get_json_opt::<Value, ErrorJson>("/json/nolanv/nop", &[])
+            .await
+            .expect("client.get_json_opt");
+        assert_eq!(response, None);
+    }
+
     #[tokio::test]
     async fn simple_get_404_request() {
-        let (_, mut client) = make_client_server("simple_get_404_request").await;
+        let (_, client) = make_client_server("simple_get_404_request").await;
 
         let result = client
             .send_request_json::<(), Value, ErrorJson>("/json/nolanv/nop", Method::GET, &[], None)
@@ -432,7 +2184,7 @@ mod json_tests {
 
     #[tokio::test]
     async fn simple_post_request() {
-        let (_, mut client) = make_client_server("simple_post_request").await;
+        let (_, client) = make_client_server("simple_post_request").await;
 
         #[derive(Serialize)]
         struct NameJson {
@@ -460,9 +2212,47 @@ mod json_tests {
         assert_eq!(response.hello, "nolanv")
     }
 
+    #[tokio::test]
+    async fn content_type_override() {
+        let socket_path = make_socket_path_test("client", "content_type_override");
+        let content_types = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received = content_types.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            let request = String::from_utf8_lossy(request);
+            received.lock().unwrap().extend(
+                request
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("content-type: "))
+                    .map(str::to_string),
+            );
+            b"HTTP/1.1 204 No Content\r\n\r\n".to_vec()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        for headers in [&[][..], &[("Content-Type", "application/merge-patch+json")]] {
+            client
+                .send_request_json::<Value, (), Value>(
+                    "/nolanv",
+                    Method::PATCH,
+                    headers,
+                    Some(&json!({"name": null})),
+                )
+                .await
+                .expect("client.send_request_json");
+        }
+        assert_eq!(
+            *content_types.lock().unwrap(),
+            ["application/json", "application/merge-patch+json"]
+        );
+    }
+
     #[tokio::test]
     async fn simple_post_bad_request() {
-        let (_, mut client) = make_client_server("simple_post_bad_request").await;
+        let (_, client) = make_client_server("simple_post_bad_request").await;
 
         #[derive(Serialize)]
         struct NameBadJson {
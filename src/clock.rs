@@ -0,0 +1,104 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    time::{Duration, SystemTime},
+};
+use tokio::time::Instant;
+
+/// Source of time of a [crate::ClientUnix], set with [crate::ClientUnixBuilder::clock].
+///
+/// It drives the retry delays, the `Retry-After` dates, the rate limiter, the response cache freshness, the injected fault delays, the slow request threshold and the idle time of the pooled connections compared to [crate::ClientUnixBuilder::idle_timeout] and the keep-alive interval. The connect, read and write timeouts and the bandwidth limits always use the tokio timer.
+///
+/// Every method has a default based on the tokio timer, the one of [TokioClock], so tests using `tokio::time::pause` already drive the client deterministically. Override them to control time from a test, like fixing the wall-clock time used to read HTTP dates.
+pub trait Clock: Debug + Send + Sync {
+    /// Monotonic time. Default is [Instant::now].
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// Wall-clock time, used to read the HTTP dates of the responses. Default is [SystemTime::now].
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Wait for `duration`. Default is [tokio::time::sleep].
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// [Clock] based on the tokio timer, used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, Method, RetryPolicy, StatusCode,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Clock stuck at a wall-clock time, recording the sleeps instead of waiting.
+    #[derive(Debug)]
+    struct FixedClock {
+        system_time: SystemTime,
+        sleeps: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl Clock for FixedClock {
+        fn system_time(&self) -> SystemTime {
+            self.system_time
+        }
+
+        fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+            self.sleeps.lock().expect("sleeps lock").push(duration);
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after_date_with_clock() {
+        let socket_path = make_socket_path_test("clock", "retry_after_date_with_clock");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server_requests = requests.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |_| {
+            match server_requests.fetch_add(1, Ordering::SeqCst) {
+                0 => b"HTTP/1.1 503 Service Unavailable\r\nretry-after: Wed, 21 Oct 2015 07:28:05 GMT\r\ncontent-length: 0\r\n\r\n".to_vec(),
+                _ => b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let sleeps = Arc::new(Mutex::new(Vec::new()));
+        let client = ClientUnix::builder(&socket_path)
+            .retry_policy(RetryPolicy::new(1))
+            .clock(FixedClock {
+                system_time: httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT")
+                    .expect("parse_http_date"),
+                sleeps: sleeps.clone(),
+            })
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (status_code, _) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *sleeps.lock().expect("sleeps lock"),
+            vec![Duration::from_secs(5)]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::OriginalHeaders;
+use hyper::{HeaderMap, Response, StatusCode, Version, body::Bytes, http::response::Parts};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+
+/// Response shared between the callers of a coalesced request.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    original_headers: Option<OriginalHeaders>,
+    body: Bytes,
+}
+
+impl SharedResponse {
+    pub(crate) fn new(parts: &Parts, body: &Bytes) -> Self {
+        SharedResponse {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers.clone(),
+            original_headers: parts.extensions.get::<OriginalHeaders>().cloned(),
+            body: body.clone(),
+        }
+    }
+
+    pub(crate) fn into_parts(self) -> (Parts, Bytes) {
+        let mut response = Response::new(());
+        *response.status_mut() = self.status;
+        *response.version_mut() = self.version;
+        *response.headers_mut() = self.headers;
+        if let Some(original_headers) = self.original_headers {
+            response.extensions_mut().insert(original_headers);
+        }
+        (response.into_parts().0, self.body)
+    }
+}
+
+type InFlight = watch::Receiver<Option<SharedResponse>>;
+
+/// Identical GET requests in flight, shared between concurrent callers.
+#[derive(Debug, Default)]
+pub(crate) struct Coalescer {
+    in_flight: Mutex<HashMap<String, InFlight>>,
+}
+
+/// Role of a caller of [Coalescer::join].
+pub(crate) enum Role {
+    /// First caller, it must send the request and complete the [LeaderGuard].
+    Leader(LeaderGuard),
+    /// An identical request is already in flight, wait for its response.
+    Follower(InFlight),
+}
+
+impl Coalescer {
+    pub(crate) fn join(self: &Arc<Self>, key: String) -> Role {
+        let Ok(mut in_flight) = self.in_flight.lock() else {
+            return Role::Leader(LeaderGuard {
+                coalescer: None,
+                key,
+                sender: watch::channel(None).0,
+            });
+        };
+        if let Some(receiver) = in_flight.get(&key) {
+            return Role::Follower(receiver.clone());
+        }
+
+        let (sender, receiver) = watch::channel(None);
+        in_flight.insert(key.clone(), receiver);
+        Role::Leader(LeaderGuard {
+            coalescer: Some(self.clone()),
+            key,
+            sender,
+        })
+    }
+}
+
+/// Wait for the response of the leader, [None] when it failed or was cancelled.
+pub(crate) async fn wait_leader(mut receiver: InFlight) -> Option<SharedResponse> {
+    receiver
+        .wait_for(|response| response.is_some())
+        .await
+        .ok()
+        .and_then(|response| response.clone())
+}
+
+/// Held by the caller sending the request, followers are released when it's dropped.
+pub(crate) struct LeaderGuard {
+    coalescer: Option<Arc<Coalescer>>,
+    key: String,
+    sender: watch::Sender<Option<SharedResponse>>,
+}
+
+impl LeaderGuard {
+    pub(crate) fn complete(self, response: SharedResponse) {
+        self.sender.send_replace(Some(response));
+    }
+}
+
+impl Drop for LeaderGuard {
+    fn drop(&mut self) {
+        if let Some(Ok(mut in_flight)) = self.coalescer.as_ref().map(|c| c.in_flight.lock()) {
+            in_flight.remove(&self.key);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::Body;
+use crate::{
+    ClientUnix, Error,
+    error::{ErrorAndResponse, ErrorAndResponseTyped},
+};
+use hyper::{
+    Method, StatusCode,
+    header::{ACCEPT, CONTENT_TYPE},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Serialization format used by [ClientUnix::send_request_with_codec] **(feature = codec)**.
+///
+/// Implement it to plug a custom format (bincode, flexbuffers, a proprietary format, ...) on top of the client.
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::Codec;
+/// use serde::{Serialize, de::DeserializeOwned};
+///
+/// struct PrettyJson;
+///
+/// impl Codec for PrettyJson {
+///     type Error = serde_json::Error;
+///
+///     fn content_type(&self) -> &str {
+///         "application/json"
+///     }
+///
+///     fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+///         serde_json::to_vec_pretty(value)
+///     }
+///
+///     fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+///         serde_json::from_slice(bytes)
+///     }
+/// }
+/// ```
+pub trait Codec {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Value of the `Content-Type` and `Accept` headers.
+    fn content_type(&self) -> &str;
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// [Codec] of the JSON format, the one of [ClientUnix::send_request_json] **(feature = json)**.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl Codec for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        serde_json::to_vec(value)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+impl ClientUnix {
+    /// Send HTTP request serialized with a [Codec] **(feature = codec)**
+    ///
+    /// Like [ClientUnix::send_request_json] with the format of the [Codec]: the request body is encoded and the response body, successful or not, is decoded with it. The `Accept` header, and the `Content-Type` header when there is a body, are set to [Codec::content_type] unless given in `headers`.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, JsonCodec, Method};
+    /// use serde_json::Value;
+    ///
+    /// pub async fn get_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, hello) = client
+    ///         .send_request_with_codec::<_, (), Value, Value>(&JsonCodec, "/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_with_codec");
+    /// }
+    /// ```
+    pub async fn send_request_with_codec<
+        C: Codec,
+        IN: Serialize,
+        OUT: DeserializeOwned,
+        ERR: DeserializeOwned,
+    >(
+        &self,
+        codec: &C,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseTyped<ERR>> {
+        let has_header = |name: &str| {
+            headers
+                .iter()
+                .any(|(header, _)| header.eq_ignore_ascii_case(name))
+        };
+        let mut codec_headers = headers.to_vec();
+        if body_request.is_some() && !has_header(CONTENT_TYPE.as_str()) {
+            codec_headers.push((CONTENT_TYPE.as_str(), codec.content_type()));
+        }
+        if !has_header(ACCEPT.as_str()) {
+            codec_headers.push((ACCEPT.as_str(), codec.content_type()));
+        }
+
+        let body_request = match body_request {
+            Some(body_request) => Some(Body::from(codec.encode(body_request).map_err(|e| {
+                ErrorAndResponseTyped::InternalError(Error::RequestEncode(Box::new(e)))
+            })?)),
+            None => None,
+        };
+
+        let (parts, body_response) = self
+            .send(endpoint, method, &codec_headers, body_request)
+            .await
+            .map_err(ErrorAndResponseTyped::InternalError)?;
+
+        if parts.status.is_success() {
+            return Ok((parts.status, decode(codec, &body_response)?));
+        }
+        Err(match self.unsuccessful(&parts, body_response) {
+            ErrorAndResponse::InternalError(e) => ErrorAndResponseTyped::InternalError(e),
+            ErrorAndResponse::ResponseUnsuccessful(status_code, response) => {
+                ErrorAndResponseTyped::ResponseUnsuccessful(status_code, decode(codec, &response)?)
+            }
+            ErrorAndResponse::RetriesExhausted(status_code, retry_after, response) => {
+                ErrorAndResponseTyped::RetriesExhausted(
+                    status_code,
+                    retry_after,
+                    decode(codec, &response)?,
+                )
+            }
+        })
+    }
+}
+
+fn decode<C: Codec, T: DeserializeOwned, ERR: DeserializeOwned>(
+    codec: &C,
+    response: &[u8],
+) -> Result<T, ErrorAndResponseTyped<ERR>> {
+    codec
+        .decode(response)
+        .map_err(|e| ErrorAndResponseTyped::InternalError(Error::ResponseDecode(Box::new(e))))
+}
+
+// The test codec is built on `serde_json`.
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use std::fmt;
+
+    /// `key=value` lines.
+    struct LinesCodec;
+
+    #[derive(Debug)]
+    struct LinesError;
+
+    impl fmt::Display for LinesError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "invalid lines")
+        }
+    }
+    impl std::error::Error for LinesError {}
+
+    impl Codec for LinesCodec {
+        type Error = LinesError;
+
+        fn content_type(&self) -> &str {
+            "text/x-lines"
+        }
+
+        fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+            let value = serde_json::to_value(value).map_err(|_| LinesError)?;
+            let object = value.as_object().ok_or(LinesError)?;
+            Ok(object
+                .iter()
+                .map(|(key, value)| format!("{}={}\n", key, value.as_str().unwrap_or_default()))
+                .collect::<String>()
+                .into_bytes())
+        }
+
+        fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+            let object: serde_json::Map<String, serde_json::Value> = String::from_utf8_lossy(bytes)
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.into()))
+                .collect();
+            serde_json::from_value(object.into()).map_err(|_| LinesError)
+        }
+    }
+
+    #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Name {
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn custom_codec() {
+        let socket_path = make_socket_path_test("codec", "custom_codec");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            let request = String::from_utf8_lossy(request);
+            assert!(request.contains("content-type: text/x-lines\r\n"));
+            assert!(request.contains("accept: text/x-lines\r\n"));
+            assert!(request.ends_with("\r\n\r\nname=nolanv\n"));
+            b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\nname=hello".to_vec()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, response) = client
+            .send_request_with_codec::<_, Name, Name, Name>(
+                &LinesCodec,
+                "/nolanv",
+                Method::POST,
+                &[],
+                Some(&Name {
+                    name: "nolanv".into(),
+                }),
+            )
+            .await
+            .expect("client.send_request_with_codec");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(
+            response,
+            Name {
+                name: "hello".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn keep_caller_headers() {
+        let socket_path = make_socket_path_test("codec", "keep_caller_headers");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            let request = String::from_utf8_lossy(request).to_lowercase();
+            let count = |header: &str| request.matches(header).count();
+            match request.starts_with("post") {
+                true => {
+                    assert!(request.contains("content-type: text/x-lines; charset=utf-8\r\n"));
+                    assert_eq!(count("content-type:"), 1);
+                }
+                false => assert_eq!(count("content-type:"), 0),
+            }
+            assert!(request.contains("accept: text/x-lines\r\n"));
+            assert_eq!(count("accept:"), 1);
+            b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\nname=hello".to_vec()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let name = Name {
+            name: "nolanv".into(),
+        };
+        let caller_headers = [
+            ("Content-Type", "text/x-lines; charset=utf-8"),
+            ("ACCEPT", "text/x-lines"),
+        ];
+        for (method, headers, body_request) in [
+            (Method::POST, &caller_headers[..], Some(&name)),
+            (Method::GET, &[], None),
+        ] {
+            let (status_code, _) = client
+                .send_request_with_codec::<_, Name, Name, Name>(
+                    &LinesCodec,
+                    "/nolanv",
+                    method,
+                    headers,
+                    body_request,
+                )
+                .await
+                .expect("client.send_request_with_codec");
+            assert_eq!(status_code, StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{ClientUnix, error::ErrorAndResponse};
+use hyper::{
+    Method, StatusCode,
+    header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use std::{collections::HashMap, sync::Mutex};
+
+/// Outcome of [ClientUnix::send_request_conditional].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conditional {
+    /// The resource changed (or was never fetched), with the status code and the new body.
+    Modified(StatusCode, Vec<u8>),
+    /// The server answered `304 Not Modified`, the previously received body is still valid.
+    NotModified,
+}
+
+/// `ETag` and `Last-Modified` values of the last successful response of an endpoint.
+#[derive(Debug, Clone, Default)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| Some(headers.get(name)?.to_str().ok()?.to_string());
+        let validators = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        (validators.etag.is_some() || validators.last_modified.is_some()).then_some(validators)
+    }
+}
+
+/// Validators remembered per endpoint by a [ClientUnix] and all its clones.
+#[derive(Debug, Default)]
+pub(crate) struct ValidatorStore(Mutex<HashMap<String, Validators>>);
+
+impl ValidatorStore {
+    fn get(&self, endpoint: &str) -> Option<Validators> {
+        self.0.lock().ok()?.get(endpoint).cloned()
+    }
+
+    fn set(&self, endpoint: &str, validators: Option<Validators>) {
+        let Ok(mut store) = self.0.lock() else {
+            return;
+        };
+        match validators {
+            Some(validators) => store.insert(endpoint.to_string(), validators),
+            None => store.remove(endpoint),
+        };
+    }
+}
+
+impl ClientUnix {
+    /// Send a conditional HTTP GET request.
+    ///
+    /// The `ETag` and `Last-Modified` headers of the last successful response of the endpoint are remembered and sent back as `If-None-Match` and `If-Modified-Since`. A `304 Not Modified` response is returned as [Conditional::NotModified] instead of an error, so a cached body can be revalidated cheaply.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Conditional};
+    ///
+    /// pub async fn poll_config(cached: &mut Vec<u8>) {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     match client
+    ///         .send_request_conditional("/config", &[])
+    ///         .await
+    ///         .expect("client.send_request_conditional")
+    ///     {
+    ///         Conditional::Modified(_, response) => *cached = response,
+    ///         Conditional::NotModified => {}
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_conditional(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<Conditional, ErrorAndResponse> {
+        let validators = self.validators().get(endpoint).unwrap_or_default();
+        let mut headers = headers.to_vec();
+        if let Some(etag) = &validators.etag {
+            headers.push((IF_NONE_MATCH.as_str(), etag));
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            headers.push((IF_MODIFIED_SINCE.as_str(), last_modified));
+        }
+
+        let (parts, body_response) = self
+            .send(endpoint, Method::GET, &headers, None)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if parts.status == StatusCode::NOT_MODIFIED {
+            return Ok(Conditional::NotModified);
+        }
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+
+        self.validators()
+            .set(endpoint, Validators::from_headers(&parts.headers));
+        Ok(Conditional::Modified(parts.status, body_response.into()))
+    }
+
+    /// Forget the `ETag` and `Last-Modified` remembered for an endpoint, the next [ClientUnix::send_request_conditional] fetches it unconditionally.
+    pub fn forget_validators(&self, endpoint: &str) {
+        self.validators().set(endpoint, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Conditional,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+
+    #[tokio::test]
+    async fn not_modified() {
+        let socket_path = make_socket_path_test("conditional", "not_modified");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            let request = String::from_utf8_lossy(request).to_lowercase();
+            match request.contains("if-none-match: \"v1\"\r\n")
+                && request.contains("if-modified-since: wed, 21 oct 2015 07:28:00 gmt\r\n")
+            {
+                true => b"HTTP/1.1 304 Not Modified\r\n\r\n".to_vec(),
+                false => b"HTTP/1.1 200 OK\r\netag: \"v1\"\r\nlast-modified: Wed, 21 Oct 2015 07:28:00 GMT\r\ncontent-length: 6\r\n\r\nnolanv".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let first = client
+            .send_request_conditional("/nolanv", &[])
+            .await
+            .expect("client.send_request_conditional");
+        assert!(matches!(first, Conditional::Modified(_, response) if response == b"nolanv"));
+
+        let second = client
+            .send_request_conditional("/nolanv", &[])
+            .await
+            .expect("client.send_request_conditional");
+        assert_eq!(second, Conditional::NotModified);
+
+        client.forget_validators("/nolanv");
+        let third = client
+            .send_request_conditional("/nolanv", &[])
+            .await
+            .expect("client.send_request_conditional");
+        assert!(matches!(third, Conditional::Modified(_, _)));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::Body;
+use crate::{
+    Error,
+    builder::ClientConfig,
+    socket_path,
+    stream::{ClientStream, HeadRecorder, Socket},
+    throttle::Bandwidth,
+    wire::WireRecorder,
+};
+use bytes::{Bytes, BytesMut};
+use http_body_util::BodyExt;
+use hyper::{
+    HeaderMap, Request,
+    body::{Body as _, Incoming},
+    client::conn::http1::{self, SendRequest},
+};
+use hyper_util::rt::TokioIo;
+use std::{
+    io,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::{net::UnixStream, task::JoinHandle};
+
+/// A single HTTP/1 connection to the server, driven by its own [tokio::task].
+#[derive(Debug)]
+pub(crate) struct Connection {
+    pub(crate) sender: SendRequest<Body>,
+    join_handle: JoinHandle<Error>,
+    pub(crate) head_recorder: Option<Arc<Mutex<HeadRecorder>>>,
+    pub(crate) wire_recorder: Option<Arc<Mutex<WireRecorder>>>,
+    in_flight: Arc<AtomicBool>,
+    body_buffer: BytesMut,
+    last_write: Arc<Mutex<Option<Instant>>>,
+    /// Time of the [ClientConfig::clock] at which the connection was opened or last given back to the pool.
+    idle_since: tokio::time::Instant,
+}
+
+/// Pause before connecting again to a server whose listen backlog is full.
+const BACKLOG_FULL_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Open the socket of a new connection, or a pipe to the in-process service.
+pub(crate) async fn open_socket(
+    socket_path: &Path,
+    config: &ClientConfig,
+) -> Result<Socket, Error> {
+    #[cfg(feature = "server")]
+    if let Some(connector) = &config.in_process {
+        return Ok(Socket::InProcess(connector.connect()));
+    }
+    let socket_path = socket_path::validate(socket_path, config).await?;
+    let stream = loop {
+        match UnixStream::connect(&socket_path).await {
+            // The listen backlog of the server is full, wait for it to accept connections within the connect timeout.
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock && config.connect_timeout.is_some() => {
+                tokio::time::sleep(BACKLOG_FULL_RETRY_DELAY).await;
+            }
+            result => break result.map_err(|e| socket_path::connect_error(&socket_path, e))?,
+        }
+    };
+    set_buffer_sizes(&stream, config).map_err(Error::SocketOption)?;
+    Ok(Socket::Unix(stream))
+}
+
+fn set_buffer_sizes(stream: &UnixStream, config: &ClientConfig) -> std::io::Result<()> {
+    let socket = socket2::SockRef::from(stream);
+    if let Some(size) = config.send_buffer_size {
+        socket.set_send_buffer_size(size)?;
+    }
+    if let Some(size) = config.recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    Ok(())
+}
+
+/// Limiters of the throughput of a new connection: the one shared by the client and its own.
+pub(crate) fn bandwidths(config: &ClientConfig) -> Vec<Arc<Bandwidth>> {
+    config
+        .bandwidth
+        .iter()
+        .cloned()
+        .chain(
+            config
+                .max_request_bandwidth
+                .map(|bytes_per_second| Arc::new(Bandwidth::new(bytes_per_second))),
+        )
+        .collect()
+}
+
+/// Mark a request as in flight on a [Connection] until dropped.
+#[derive(Debug)]
+pub(crate) struct InFlightGuard(Arc<AtomicBool>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl Connection {
+    pub(crate) async fn try_connect(
+        socket_path: &Path,
+        config: &ClientConfig,
+    ) -> Result<Self, Error> {
+        match config.connect_timeout {
+            Some(connect_timeout) => {
+                tokio::time::timeout(connect_timeout, Connection::connect(socket_path, config))
+                    .await
+                    .map_err(|_| Error::ConnectTimeout(connect_timeout))?
+            }
+            None => Connection::connect(socket_path, config).await,
+        }
+    }
+
+    async fn connect(socket_path: &Path, config: &ClientConfig) -> Result<Self, Error> {
+        let head_recorder = config
+            .preserve_header_case
+            .then(|| Arc::new(Mutex::new(HeadRecorder::default())));
+        let wire_recorder = config
+            .capture_wire
+            .map(|max_size| Arc::new(Mutex::new(WireRecorder::new(max_size))));
+        let in_flight = Arc::new(AtomicBool::new(false));
+        let last_write = Arc::new(Mutex::new(None));
+        let stream = TokioIo::new(ClientStream::new(
+            open_socket(socket_path, config).await?,
+            head_recorder.clone(),
+            wire_recorder.clone(),
+            in_flight.clone(),
+            config,
+            bandwidths(config),
+            last_write.clone(),
+        ));
+
+        let (sender, connection) = http1::Builder::new()
+            .title_case_headers(config.title_case_headers)
+            .handshake(stream)
+            .await
+            .map_err(Error::Handhsake)?;
+        config.stats.connection();
+
+        let join_handle = tokio::task::spawn(async move {
+            Error::SocketConnectionClosed(connection.with_upgrades().await.err())
+        });
+
+        Ok(Connection {
+            sender,
+            join_handle,
+            head_recorder,
+            wire_recorder,
+            in_flight,
+            body_buffer: BytesMut::new(),
+            last_write,
+            idle_since: config.clock.now(),
+        })
+    }
+
+    /// Start a request, the read timeout is only applied while the returned guard is alive.
+    pub(crate) fn start_request(&self) -> InFlightGuard {
+        self.in_flight.store(true, Ordering::Release);
+        InFlightGuard(self.in_flight.clone())
+    }
+
+    /// Last time bytes were written on the socket.
+    pub(crate) fn last_write(&self) -> Option<Instant> {
+        self.last_write
+            .lock()
+            .ok()
+            .and_then(|last_write| *last_write)
+    }
+
+    /// Time of the [ClientConfig::clock] since which the connection is unused.
+    pub(crate) fn idle_since(&self) -> tokio::time::Instant {
+        self.idle_since
+    }
+
+    /// Record that the connection is unused from `now`, read from the [ClientConfig::clock].
+    pub(crate) fn set_idle(&mut self, now: tokio::time::Instant) {
+        self.idle_since = now;
+    }
+
+    /// Send a keep-alive request and discard its response, whatever its status.
+    pub(crate) async fn ping(&mut self, request: Request<Body>) -> Result<(), Error> {
+        let _in_flight = self.start_request();
+        self.sender
+            .ready()
+            .await
+            .map_err(|e| Error::ConnectionLost(Some(e)))?;
+        let mut response = self
+            .sender
+            .send_request(request)
+            .await
+            .map_err(|e| Error::from_hyper(e, Error::RequestSend))?;
+        self.collect_body(response.body_mut())
+            .await
+            .map_err(|e| Error::from_hyper(e, Error::ResponseCollect))?;
+        Ok(())
+    }
+
+    /// Collect a response body and its trailers.
+    ///
+    /// Single frame bodies are returned without copy. Bodies split in several frames are gathered in a buffer owned by the connection, its allocation is reclaimed by the next requests once the returned [Bytes] are dropped.
+    pub(crate) async fn collect_body(
+        &mut self,
+        body: &mut Incoming,
+    ) -> Result<(Bytes, Option<HeaderMap>), hyper::Error> {
+        let mut single_frame: Option<Bytes> = None;
+        let mut trailers = None;
+        while let Some(frame) = body.frame().await {
+            let data = match frame?.into_data() {
+                Ok(data) => data,
+                Err(frame) => {
+                    trailers = frame.into_trailers().ok();
+                    continue;
+                }
+            };
+            if single_frame.is_none() && self.body_buffer.is_empty() {
+                single_frame = Some(data);
+                continue;
+            }
+            if let Some(first_frame) = single_frame.take() {
+                let remaining = usize::try_from(body.size_hint().lower()).unwrap_or(0);
+                self.body_buffer
+                    .reserve(first_frame.len() + data.len() + remaining);
+                self.body_buffer.extend_from_slice(&first_frame);
+            }
+            self.body_buffer.extend_from_slice(&data);
+        }
+
+        let data = match single_frame {
+            Some(data) => data,
+            None => self.body_buffer.split().freeze(),
+        };
+        Ok((data, trailers))
+    }
+
+    /// Stop the connection without waiting for its [tokio::task].
+    pub(crate) fn close(self) {
+        self.join_handle.abort();
+    }
+
+    pub(crate) async fn abort(self) -> Option<Error> {
+        self.join_handle.abort();
+        self.join_handle.await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn buffer_sizes() {
+        let (stream, _) = UnixStream::pair().expect("UnixStream::pair");
+        let config = ClientConfig {
+            send_buffer_size: Some(256 * 1024),
+            recv_buffer_size: Some(128 * 1024),
+            ..Default::default()
+        };
+        set_buffer_sizes(&stream, &config).expect("set_buffer_sizes");
+
+        let socket = socket2::SockRef::from(&stream);
+        assert!(socket.send_buffer_size().expect("send_buffer_size") >= 128 * 1024);
+        assert!(socket.recv_buffer_size().expect("recv_buffer_size") >= 64 * 1024);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::RequestDescription;
+use hyper::Method;
+#[cfg(feature = "json")]
+use serde::Serialize;
+use std::path::Path;
+
+impl<B: AsRef<[u8]>> RequestDescription<B> {
+    /// Render the request as an equivalent `curl --unix-socket` command line, to reproduce it outside of Rust.
+    ///
+    /// The body must be made of bytes (`Vec<u8>`, `String`, `&str`, ...) to be rendered, raw [crate::Body] streams can't be read without being sent. The URL uses the default `unix.socket` authority.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{Method, RequestDescription};
+    ///
+    /// let curl = RequestDescription::new("/nolanv", Method::POST)
+    ///     .header("X-Bread", "baguette")
+    ///     .body("hello")
+    ///     .to_curl("/tmp/unix.socket");
+    /// assert_eq!(
+    ///     curl,
+    ///     "curl --unix-socket '/tmp/unix.socket' -X POST -H 'X-Bread: baguette' --data-binary 'hello' 'http://unix.socket/nolanv'"
+    /// );
+    /// ```
+    pub fn to_curl(&self, socket_path: impl AsRef<Path>) -> String {
+        curl_command(
+            socket_path.as_ref(),
+            &self.endpoint,
+            &self.method,
+            &self.headers(),
+            self.body.as_ref().map(AsRef::as_ref),
+        )
+    }
+}
+
+#[cfg(feature = "json")]
+impl<B: Serialize> RequestDescription<B> {
+    /// Same as [RequestDescription::to_curl] for a request sent with [crate::ClientUnix::send_requests_json] **(feature = json)**
+    ///
+    /// The body is serialized and the `Content-Type: application/json` header is added, like when the request is sent.
+    pub fn to_curl_json(&self, socket_path: impl AsRef<Path>) -> Result<String, crate::Error> {
+        let body = self
+            .body
+            .as_ref()
+            .map(serde_json::to_vec)
+            .transpose()
+            .map_err(crate::Error::RequestParsing)?;
+        let mut headers = self.headers();
+        headers.push(("Content-Type", "application/json"));
+        Ok(curl_command(
+            socket_path.as_ref(),
+            &self.endpoint,
+            &self.method,
+            &headers,
+            body.as_deref(),
+        ))
+    }
+}
+
+fn curl_command(
+    socket_path: &Path,
+    endpoint: &str,
+    method: &Method,
+    headers: &[(&str, &str)],
+    body: Option<&[u8]>,
+) -> String {
+    let mut command = format!(
+        "curl --unix-socket {}",
+        quote(socket_path.as_os_str().as_encoded_bytes())
+    );
+    match *method {
+        Method::GET => {}
+        // `-X HEAD` would wait for a body that never comes.
+        Method::HEAD => command.push_str(" --head"),
+        _ => command.push_str(&format!(" -X {}", method)),
+    }
+    for (name, value) in headers {
+        command.push_str(&format!(
+            " -H {}",
+            quote(format!("{}: {}", name, value).as_bytes())
+        ));
+    }
+    if let Some(body) = body {
+        command.push_str(&format!(" --data-binary {}", quote(body)));
+    }
+    command.push_str(&format!(
+        " {}",
+        quote(format!("http://unix.socket{}", endpoint).as_bytes())
+    ));
+    command
+}
+
+/// Quote a shell argument, bytes that can't be written as is use the `$'...'` form.
+fn quote(argument: &[u8]) -> String {
+    match argument
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        true => format!(
+            "'{}'",
+            String::from_utf8_lossy(argument).replace('\'', r"'\''")
+        ),
+        false => format!("$'{}'", argument.escape_ascii()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_special_bytes() {
+        assert_eq!(quote(b"it's"), r"'it'\''s'");
+        assert_eq!(quote(b"line\nbreak\xff"), r"$'line\nbreak\xff'");
+    }
+
+    #[test]
+    fn head_request() {
+        let curl = RequestDescription::<&str>::new("/nolanv?page=2", Method::HEAD)
+            .to_curl("/tmp/unix.socket");
+        assert_eq!(
+            curl,
+            "curl --unix-socket '/tmp/unix.socket' --head 'http://unix.socket/nolanv?page=2'"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::Body;
+use crate::{
+    Error,
+    headers::{REDACTED, SECRET_HEADERS, merge_redacted},
+};
+use http_body_util::BodyExt;
+use hyper::{HeaderMap, Request, body::Bytes, header::HeaderName, http::response::Parts};
+use std::fmt::Write;
+
+const LOG_TARGET: &str = "http_client_unix_domain_socket::wire";
+
+/// Options of the wire logging set with [crate::ClientUnixBuilder::debug_wire] **(feature = debug-wire)**.
+///
+/// The request and response heads are logged at the debug level with the `http_client_unix_domain_socket::wire` target of the [log](https://docs.rs/log) crate. The values of the `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers, and of those given to [crate::ClientUnixBuilder::redact_header], are redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugWire {
+    body_snippet: usize,
+    redacted_headers: Vec<HeaderName>,
+}
+
+impl Default for DebugWire {
+    fn default() -> Self {
+        DebugWire {
+            body_snippet: 0,
+            redacted_headers: SECRET_HEADERS.to_vec(),
+        }
+    }
+}
+
+impl DebugWire {
+    pub fn new() -> Self {
+        DebugWire::default()
+    }
+
+    /// Also log up to `max_len` bytes of the request and response bodies, request bodies are then buffered. Default is 0.
+    pub fn body_snippet(mut self, max_len: usize) -> Self {
+        self.body_snippet = max_len;
+        self
+    }
+
+    /// Redact the value of another header, like a custom token header.
+    pub fn redact_header(mut self, name: HeaderName) -> Self {
+        self.redacted_headers.push(name);
+        self
+    }
+
+    /// Also redact the headers given to [crate::ClientUnixBuilder::redact_header].
+    pub(crate) fn redact_headers(&mut self, redacted: &[HeaderName]) {
+        merge_redacted(&mut self.redacted_headers, redacted);
+    }
+
+    /// Log the request head, and its body when [DebugWire::body_snippet] is set.
+    pub(crate) async fn log_request(&self, request: Request<Body>) -> Result<Request<Body>, Error> {
+        if !log::log_enabled!(target: LOG_TARGET, log::Level::Debug) {
+            return Ok(request);
+        }
+        let mut head = format!(
+            "> {} {} {:?}",
+            request.method(),
+            request.uri(),
+            request.version()
+        );
+        self.write_headers(&mut head, '>', request.headers());
+        if self.body_snippet == 0 {
+            log::debug!(target: LOG_TARGET, "{}", head);
+            return Ok(request);
+        }
+
+        let (parts, body) = request.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(Error::RequestCollect)?
+            .to_bytes();
+        self.write_body(&mut head, '>', &body);
+        log::debug!(target: LOG_TARGET, "{}", head);
+        Ok(Request::from_parts(parts, Body::from(body)))
+    }
+
+    pub(crate) fn log_response(&self, parts: &Parts) {
+        if !log::log_enabled!(target: LOG_TARGET, log::Level::Debug) {
+            return;
+        }
+        log::debug!(target: LOG_TARGET, "{}", self.format_response(parts));
+    }
+
+    pub(crate) fn log_response_body(&self, body: &Bytes) {
+        if self.body_snippet == 0 || !log::log_enabled!(target: LOG_TARGET, log::Level::Debug) {
+            return;
+        }
+        let mut snippet = String::new();
+        self.write_body(&mut snippet, '<', body);
+        log::debug!(target: LOG_TARGET, "{}", snippet.trim_start());
+    }
+
+    fn format_response(&self, parts: &Parts) -> String {
+        let mut head = format!("< {:?} {}", parts.version, parts.status);
+        self.write_headers(&mut head, '<', &parts.headers);
+        head
+    }
+
+    fn write_headers(&self, output: &mut String, direction: char, headers: &HeaderMap) {
+        for (name, value) in headers {
+            let _ = match self.redacted_headers.contains(name) {
+                true => write!(output, "\n{} {}: {}", direction, name, REDACTED),
+                false => write!(
+                    output,
+                    "\n{} {}: {}",
+                    direction,
+                    name,
+                    value.as_bytes().escape_ascii()
+                ),
+            };
+        }
+    }
+
+    fn write_body(&self, output: &mut String, direction: char, body: &[u8]) {
+        let snippet = &body[..body.len().min(self.body_snippet)];
+        let _ = write!(output, "\n{} {}", direction, snippet.escape_ascii());
+        if snippet.len() < body.len() {
+            let _ = write!(output, "... ({} bytes)", body.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix,
+        test_helpers::{server::Server, util::make_socket_path_test},
+    };
+    use hyper::{Response, header::HeaderValue};
+
+    #[test]
+    fn redact_and_truncate() {
+        let debug_wire = DebugWire::new()
+            .body_snippet(4)
+            .redact_header(HeaderName::from_static("x-token"));
+        let (parts, _) = Response::builder()
+            .header("content-type", "text/plain")
+            .header("set-cookie", "session=secret")
+            .header("x-token", HeaderValue::from_static("secret"))
+            .body(())
+            .expect("Response::builder")
+            .into_parts();
+
+        let mut output = debug_wire.format_response(&parts);
+        debug_wire.write_body(&mut output, '<', b"baguette\n");
+
+        assert_eq!(
+            output,
+            "< HTTP/1.1 200 OK\n< content-type: text/plain\n< set-cookie: <redacted>\n< x-token: <redacted>\n< bagu... (9 bytes)"
+        );
+    }
+
+    #[tokio::test]
+    async fn client_redacted_headers() {
+        let socket_path = make_socket_path_test("debug_wire", "client_redacted_headers");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .debug_wire(DebugWire::new())
+            .redact_header(HeaderName::from_static("x-token"))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let (parts, _) = Response::builder()
+            .header("authorization", "Bearer secret")
+            .header("x-token", "secret")
+            .body(())
+            .expect("Response::builder")
+            .into_parts();
+
+        let debug_wire = client.config().debug_wire.as_ref().expect("debug_wire");
+        assert_eq!(
+            debug_wire.format_response(&parts),
+            "< HTTP/1.1 200 OK\n< authorization: <redacted>\n< x-token: <redacted>"
+        );
+    }
+}
@@ -0,0 +1,714 @@
+use crate::{ClientUnix, Error, error::ErrorAndResponse};
+use hyper::{
+    HeaderMap, Method, StatusCode,
+    header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    http::Extensions,
+};
+use std::{
+    ffi::OsString,
+    io::SeekFrom,
+    ops::{Bound, RangeBounds},
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncSeekExt, AsyncWriteExt},
+};
+
+/// Format a `Range` header value, [None] when the range is empty.
+fn range_header(range: impl RangeBounds<u64>) -> Option<String> {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => Some(*end),
+        Bound::Excluded(end) => Some(end.checked_sub(1)?),
+        Bound::Unbounded => None,
+    };
+    match end {
+        Some(end) if end < start => None,
+        Some(end) => Some(format!("bytes={}-{}", start, end)),
+        None => Some(format!("bytes={}-", start)),
+    }
+}
+
+/// First and last bytes of the range served, from a `Content-Range: bytes start-end/length` header.
+fn content_range(headers: &HeaderMap) -> Option<(u64, u64)> {
+    let content_range = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (range, _) = content_range.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, end) = range.trim().split_once('-')?;
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
+
+/// Complete length of the resource from a `Content-Range: bytes start-end/length` header.
+fn content_range_length(headers: &HeaderMap) -> Option<u64> {
+    let content_range = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    content_range.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Value for the `If-Range` header of the next requests: the strong `ETag` of the response, or its `Last-Modified` date.
+fn range_validator(headers: &HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        // Weak validators can't be used with If-Range.
+        .filter(|etag| !etag.starts_with("W/"));
+    etag.or_else(|| headers.get(LAST_MODIFIED)?.to_str().ok())
+        .map(str::to_string)
+}
+
+impl ClientUnix {
+    /// Send an HTTP GET request for a byte range of the resource.
+    ///
+    /// The server answers [StatusCode::PARTIAL_CONTENT] with the requested bytes, or [StatusCode::OK] with the whole resource when it doesn't support range requests. An empty range is sent as a plain GET request.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn get_header_bytes() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, response) = client
+    ///         .send_request_range("/archive.tar", &[], 0..512)
+    ///         .await
+    ///         .expect("client.send_request_range");
+    /// }
+    /// ```
+    pub async fn send_request_range(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        range: impl RangeBounds<u64>,
+    ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        let range = range_header(range);
+        let mut headers = headers.to_vec();
+        if let Some(range) = &range {
+            headers.push((RANGE.as_str(), range));
+        }
+        self.send_request(endpoint, Method::GET, &headers, None)
+            .await
+    }
+
+    /// Download a resource into a file, resuming from the current size of the file.
+    ///
+    /// The resource is fetched with range requests of `chunk_size` bytes, each chunk being appended to the file as soon as it is received. If the download is interrupted, calling this method again resumes it from the end of the file. The chunks after the first one are sent with an `If-Range` header holding its `ETag` or `Last-Modified`, so a resource changed during the download is served whole and the file rewritten instead of mixing both versions. This validator is kept in `<path>.validator` until the download completes, so a resumed download is validated too; a partial file without it (not started by this method, or from a server without validators) is resumed without `If-Range`. [Error::DownloadRange] is returned when a chunk doesn't start at the end of the file. When the server doesn't support range requests (no `Accept-Ranges: bytes`, it answers [StatusCode::OK]), the whole file is rewritten. Returns the size of the downloaded file.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn download_export() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let size = client
+    ///         .download_resumable("/export", &[], "/tmp/export.tar", 1024 * 1024)
+    ///         .await
+    ///         .expect("client.download_resumable");
+    /// }
+    /// ```
+    pub async fn download_resumable(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        path: impl AsRef<Path>,
+        chunk_size: u64,
+    ) -> Result<u64, ErrorAndResponse> {
+        assert!(chunk_size > 0, "chunk_size must be at least 1");
+        let path = path.as_ref();
+        let file_error = |e| ErrorAndResponse::InternalError(Error::DownloadFile(e));
+        let range_error = |e| ErrorAndResponse::InternalError(Error::DownloadRange(e));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(file_error)?;
+        let mut offset = file.metadata().await.map_err(file_error)?.len();
+        // Validator of the first chunk, kept next to the file so the chunks of the next calls are
+        // also only partial if the resource didn't change.
+        let mut validator_path = OsString::from(path);
+        validator_path.push(".validator");
+        let validator_path = PathBuf::from(validator_path);
+        let mut if_range = match offset {
+            0 => None,
+            _ => tokio::fs::read_to_string(&validator_path).await.ok(),
+        };
+        let done = |size| {
+            let validator_path = validator_path.as_path();
+            async move {
+                match tokio::fs::remove_file(validator_path).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(file_error(e)),
+                    _ => Ok(size),
+                }
+            }
+        };
+
+        loop {
+            let range = format!("bytes={}-{}", offset, offset.saturating_add(chunk_size - 1));
+            let mut chunk_headers = headers.to_vec();
+            chunk_headers.push((RANGE.as_str(), &range));
+            if let Some(if_range) = &if_range {
+                chunk_headers.push((IF_RANGE.as_str(), if_range));
+            }
+
+            let (parts, body_response) = self
+                .send(endpoint, Method::GET, &chunk_headers, None)
+                .await
+                .map_err(ErrorAndResponse::InternalError)?;
+
+            match parts.status {
+                // The file is already complete.
+                StatusCode::RANGE_NOT_SATISFIABLE
+                    if content_range_length(&parts.headers) == Some(offset) =>
+                {
+                    return done(offset).await;
+                }
+                StatusCode::PARTIAL_CONTENT => {
+                    if content_range(&parts.headers).map(|(start, _)| start) != Some(offset) {
+                        return Err(range_error("unexpected content-range"));
+                    }
+                    if if_range.is_none() {
+                        if_range = range_validator(&parts.headers);
+                        if let Some(if_range) = &if_range {
+                            tokio::fs::write(&validator_path, if_range)
+                                .await
+                                .map_err(file_error)?;
+                        }
+                    }
+                    file.write_all(&body_response).await.map_err(file_error)?;
+                    file.flush().await.map_err(file_error)?;
+                    offset += body_response.len() as u64;
+
+                    let complete =
+                        content_range_length(&parts.headers).is_none_or(|length| offset >= length);
+                    if complete || body_response.is_empty() {
+                        return done(offset).await;
+                    }
+                }
+                status if status.is_success() => {
+                    file.set_len(0).await.map_err(file_error)?;
+                    file.write_all(&body_response).await.map_err(file_error)?;
+                    file.flush().await.map_err(file_error)?;
+                    return done(body_response.len() as u64).await;
+                }
+                _ => return Err(self.unsuccessful(&parts, body_response)),
+            }
+        }
+    }
+
+    /// Download a resource into a file, fetching `ranges` byte ranges of it concurrently.
+    ///
+    /// A first request for the first byte reads the length of the resource, the rest is split in `ranges` parts of the same size, each fetched with its own range request over the connection pool and streamed to its offset in the file, so the resource is never held in memory. Speeds up large downloads from a local daemon, the parallelism is bounded by [crate::ClientUnixBuilder::max_connections]. The ranges are sent with an `If-Range` header holding the `ETag` or `Last-Modified` of the first response. When the server doesn't support range requests, it answers [StatusCode::OK] to the first request and its whole body is written. [Error::DownloadRange] is returned when a range is not served as requested, like when the resource changed during the download.
+    ///
+    /// The ranges are written to `<path>.part`, renamed to `path` once they are all downloaded, and removed on error so `path` is never left incomplete. The file is overwritten, returns its size.
+    ///
+    /// # Panics
+    /// Panics if `ranges` is 0.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn pull_artifact() {
+    ///     let client = ClientUnix::builder("/tmp/unix.socket")
+    ///         .max_connections(8)
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    ///
+    ///     let size = client
+    ///         .download_parallel("/artifacts/image.tar", &[], "/tmp/image.tar", 8)
+    ///         .await
+    ///         .expect("client.download_parallel");
+    /// }
+    /// ```
+    pub async fn download_parallel(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        path: impl AsRef<Path>,
+        ranges: usize,
+    ) -> Result<u64, ErrorAndResponse> {
+        assert!(ranges > 0, "ranges must be at least 1");
+        let path = path.as_ref();
+        let file_error = |e| ErrorAndResponse::InternalError(Error::DownloadFile(e));
+        let range_error = |e| ErrorAndResponse::InternalError(Error::DownloadRange(e));
+
+        let mut first_headers = headers.to_vec();
+        first_headers.push((RANGE.as_str(), "bytes=0-0"));
+        let (parts, first_byte) = self
+            .send(endpoint, Method::GET, &first_headers, None)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+        let length = match parts.status {
+            StatusCode::RANGE_NOT_SATISFIABLE
+                if content_range_length(&parts.headers) == Some(0) =>
+            {
+                File::create(path).await.map_err(file_error)?;
+                return Ok(0);
+            }
+            StatusCode::PARTIAL_CONTENT if content_range(&parts.headers) != Some((0, 0)) => {
+                return Err(range_error("unexpected content-range"));
+            }
+            StatusCode::PARTIAL_CONTENT => content_range_length(&parts.headers)
+                .ok_or_else(|| range_error("missing content-range length"))?,
+            status if status.is_success() => {
+                tokio::fs::write(path, &first_byte)
+                    .await
+                    .map_err(file_error)?;
+                return Ok(first_byte.len() as u64);
+            }
+            _ => return Err(self.unsuccessful(&parts, first_byte)),
+        };
+        if first_byte.len() != 1 {
+            return Err(range_error("unexpected range length"));
+        }
+
+        // The ranges are only partial if the resource didn't change since the first byte.
+        let if_range = range_validator(&parts.headers);
+        let mut range_headers = headers.to_vec();
+        if let Some(if_range) = &if_range {
+            range_headers.push((IF_RANGE.as_str(), if_range));
+        }
+
+        // Downloaded next to the file, which is only replaced once every range is written.
+        let mut part_path = OsString::from(path);
+        part_path.push(".part");
+        let part_path = PathBuf::from(part_path);
+        let download = async {
+            let mut file = File::create(&part_path).await.map_err(file_error)?;
+            file.set_len(length).await.map_err(file_error)?;
+            file.write_all(&first_byte).await.map_err(file_error)?;
+            file.flush().await.map_err(file_error)?;
+
+            let range_len = (length - 1).div_ceil(ranges as u64).max(1);
+            futures_util::future::try_join_all((1..length).step_by(range_len as usize).map(
+                |start| {
+                    self.download_range(
+                        endpoint,
+                        &range_headers,
+                        &part_path,
+                        start,
+                        (start + range_len).min(length),
+                    )
+                },
+            ))
+            .await?;
+            tokio::fs::rename(&part_path, path)
+                .await
+                .map_err(file_error)
+        };
+        if let Err(e) = download.await {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+        Ok(length)
+    }
+
+    /// Fetch the bytes from `start` to `end` (excluded) and write them at their offset in the file as they are received.
+    async fn download_range(
+        &self,
+        endpoint: &str,
+        headers: &[(&str, &str)],
+        path: &Path,
+        start: u64,
+        end: u64,
+    ) -> Result<(), ErrorAndResponse> {
+        let file_error = |e| ErrorAndResponse::InternalError(Error::DownloadFile(e));
+        let range_error = |e| ErrorAndResponse::InternalError(Error::DownloadRange(e));
+        let range = format!("bytes={}-{}", start, end - 1);
+        let mut range_headers = headers.to_vec();
+        range_headers.push((RANGE.as_str(), &range));
+
+        let (parts, mut body_response) = self
+            .start(
+                endpoint,
+                Method::GET,
+                &range_headers,
+                None,
+                &Extensions::new(),
+            )
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+        match parts.status {
+            StatusCode::PARTIAL_CONTENT
+                if content_range(&parts.headers) == Some((start, end - 1)) => {}
+            // Also a resource changed since the first byte, served whole.
+            status if status.is_success() => {
+                return Err(range_error("unexpected content-range"));
+            }
+            _ => {
+                let body_response = body_response
+                    .collect()
+                    .await
+                    .map_err(ErrorAndResponse::InternalError)?;
+                return Err(self.unsuccessful(&parts, body_response));
+            }
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .map_err(file_error)?;
+        file.seek(SeekFrom::Start(start))
+            .await
+            .map_err(file_error)?;
+        let mut remaining = end - start;
+        while let Some(data) = body_response.data().await {
+            let data = data.map_err(ErrorAndResponse::InternalError)?;
+            remaining = remaining
+                .checked_sub(data.len() as u64)
+                .ok_or_else(|| range_error("unexpected range length"))?;
+            file.write_all(&data).await.map_err(file_error)?;
+        }
+        if remaining != 0 {
+            return Err(range_error("unexpected range length"));
+        }
+        file.flush().await.map_err(file_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    const CONTENT: &[u8] = b"0123456789abcdefghij";
+
+    /// Minimal range server over [CONTENT].
+    fn serve_range(request: &[u8]) -> Vec<u8> {
+        let request = String::from_utf8_lossy(request).to_lowercase();
+        let Some(range) = request
+            .lines()
+            .find_map(|line| line.strip_prefix("range: bytes="))
+        else {
+            return [
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n",
+                    CONTENT.len()
+                )
+                .as_bytes(),
+                CONTENT,
+            ]
+            .concat();
+        };
+        let (start, end) = range.trim().split_once('-').expect("range");
+        let start: usize = start.parse().expect("start");
+        if start >= CONTENT.len() {
+            return format!(
+                "HTTP/1.1 416 Range Not Satisfiable\r\ncontent-range: bytes */{}\r\ncontent-length: 0\r\n\r\n",
+                CONTENT.len()
+            )
+            .into_bytes();
+        }
+        let end = end
+            .parse::<usize>()
+            .map_or(CONTENT.len() - 1, |end| end.min(CONTENT.len() - 1));
+        [
+            format!(
+                "HTTP/1.1 206 Partial Content\r\ncontent-range: bytes {}-{}/{}\r\ncontent-length: {}\r\n\r\n",
+                start,
+                end,
+                CONTENT.len(),
+                end + 1 - start
+            )
+            .as_bytes(),
+            &CONTENT[start..=end],
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn range_header_bounds() {
+        assert_eq!(range_header(0..512), Some("bytes=0-511".into()));
+        assert_eq!(range_header(100..=199), Some("bytes=100-199".into()));
+        assert_eq!(range_header(1024..), Some("bytes=1024-".into()));
+        assert_eq!(range_header(10..10), None);
+    }
+
+    #[tokio::test]
+    async fn range_request() {
+        let socket_path = make_socket_path_test("download", "range_request");
+        let _server = RawServer::try_new_with(&socket_path, false, serve_range)
+            .await
+            .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, response) = client
+            .send_request_range("/file", &[], 5..10)
+            .await
+            .expect("client.send_request_range");
+
+        assert_eq!(status_code, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response, b"56789");
+    }
+
+    #[tokio::test]
+    async fn download_resume() {
+        let socket_path = make_socket_path_test("download", "download_resume");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server_requests = requests.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            server_requests.fetch_add(1, Ordering::SeqCst);
+            serve_range(request)
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let file_path = format!("{}.download", socket_path);
+        tokio::fs::write(&file_path, &CONTENT[..7])
+            .await
+            .expect("tokio::fs::write");
+
+        let size = client
+            .download_resumable("/file", &[], &file_path, 8)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, CONTENT.len() as u64);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            CONTENT
+        );
+
+        let size = client
+            .download_resumable("/file", &[], &file_path, 8)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, CONTENT.len() as u64);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+
+    #[tokio::test]
+    async fn download_changed_resource() {
+        let socket_path = make_socket_path_test("download", "download_changed_resource");
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            if String::from_utf8_lossy(request).contains("if-range: \"v1\"") {
+                return b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec();
+            }
+            String::from_utf8(serve_range(request))
+                .expect("String::from_utf8")
+                .replacen("\r\n", "\r\netag: \"v1\"\r\n", 1)
+                .into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+        let file_path = format!("{}.download", socket_path);
+
+        // The resource changed after the first chunk.
+        let size = client
+            .download_resumable("/file", &[], &file_path, 8)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, 6);
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            b"nolanv"
+        );
+        let validator_path = format!("{}.validator", file_path);
+        assert!(!Path::new(&validator_path).exists());
+
+        // The resource changed while the download was interrupted.
+        tokio::fs::write(&file_path, &CONTENT[..8])
+            .await
+            .expect("tokio::fs::write");
+        tokio::fs::write(&validator_path, "\"v1\"")
+            .await
+            .expect("tokio::fs::write");
+        let size = client
+            .download_resumable("/file", &[], &file_path, 8)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, 6);
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            b"nolanv"
+        );
+        assert!(!Path::new(&validator_path).exists());
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+        let size = client
+            .download_resumable("/file", &[], &file_path, u64::MAX)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, CONTENT.len() as u64);
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+
+    #[tokio::test]
+    async fn download_unexpected_range() {
+        let socket_path = make_socket_path_test("download", "download_unexpected_range");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 206 Partial Content\r\ncontent-range: bytes 0-5/20\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let file_path = format!("{}.download", socket_path);
+        tokio::fs::write(&file_path, &CONTENT[..7])
+            .await
+            .expect("tokio::fs::write");
+
+        let result = client.download_resumable("/file", &[], &file_path, 8).await;
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponse::InternalError(Error::DownloadRange(_)))
+        ));
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            &CONTENT[..7]
+        );
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+
+    #[tokio::test]
+    async fn download_parallel_ranges() {
+        let socket_path = make_socket_path_test("download", "download_parallel_ranges");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server_requests = requests.clone();
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            server_requests.fetch_add(1, Ordering::SeqCst);
+            serve_range(request)
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(3)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let file_path = format!("{}.download", socket_path);
+        tokio::fs::write(&file_path, b"stale content, longer than the resource")
+            .await
+            .expect("tokio::fs::write");
+
+        let size = client
+            .download_parallel("/file", &[], &file_path, 3)
+            .await
+            .expect("client.download_parallel");
+        assert_eq!(size, CONTENT.len() as u64);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            CONTENT
+        );
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+
+    #[tokio::test]
+    async fn download_parallel_changed_resource() {
+        let socket_path = make_socket_path_test("download", "download_parallel_changed_resource");
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            let request = String::from_utf8_lossy(request);
+            if request.contains("range: bytes=0-0") {
+                return String::from_utf8(serve_range(request.as_bytes()))
+                    .expect("String::from_utf8")
+                    .replacen("\r\n", "\r\netag: \"v1\"\r\n", 1)
+                    .into_bytes();
+            }
+            // The resource changed after the first byte.
+            match request.contains("if-range: \"v1\"") {
+                true => b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec(),
+                false => serve_range(request.as_bytes()),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let file_path = format!("{}.download", socket_path);
+        tokio::fs::write(&file_path, b"stale content")
+            .await
+            .expect("tokio::fs::write");
+
+        let result = client.download_parallel("/file", &[], &file_path, 3).await;
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponse::InternalError(Error::DownloadRange(_)))
+        ));
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            b"stale content"
+        );
+        assert!(!Path::new(&format!("{}.part", file_path)).exists());
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+
+    #[tokio::test]
+    async fn download_without_range_support() {
+        let socket_path = make_socket_path_test("download", "download_without_range_support");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let file_path = format!("{}.download", socket_path);
+        tokio::fs::write(&file_path, b"stale content")
+            .await
+            .expect("tokio::fs::write");
+
+        let size = client
+            .download_resumable("/file", &[], &file_path, 4)
+            .await
+            .expect("client.download_resumable");
+        assert_eq!(size, 6);
+        assert_eq!(
+            tokio::fs::read(&file_path).await.expect("tokio::fs::read"),
+            b"nolanv"
+        );
+
+        tokio::fs::remove_file(&file_path)
+            .await
+            .expect("tokio::fs::remove_file");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Error;
+
+/// Join a caller-provided `path` to a `base` path, like the prefix of a versioned API.
+///
+/// Duplicate slashes are collapsed and the query string of `path` is kept as is. [Error::InvalidEndpoint] is returned when a segment of `path` is `.` or `..`, even percent-encoded, so it can't escape `base`.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{join_path, path_segment};
+///
+/// let name = path_segment("my container").expect("path_segment");
+/// let endpoint = join_path("/v1.47/", &format!("/containers/{}/json?size=1", name))
+///     .expect("join_path");
+/// assert_eq!(endpoint, "/v1.47/containers/my%20container/json?size=1");
+/// assert!(join_path("/v1.47", "/../secrets").is_err());
+/// ```
+pub fn join_path(base: &str, path: &str) -> Result<String, Error> {
+    let (path, query) = match path.find(['?', '#']) {
+        Some(index) => path.split_at(index),
+        None => (path, ""),
+    };
+    let mut endpoint = String::with_capacity(base.len() + path.len() + query.len() + 1);
+    for segment in base.split('/').filter(|segment| !segment.is_empty()) {
+        endpoint.push('/');
+        endpoint.push_str(segment);
+    }
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if is_dot_segment(segment) {
+            return Err(Error::InvalidEndpoint(format!(
+                "{:?} is not allowed in {:?}",
+                segment, path
+            )));
+        }
+        endpoint.push('/');
+        endpoint.push_str(segment);
+    }
+    if endpoint.is_empty() || path.ends_with('/') {
+        endpoint.push('/');
+    }
+    endpoint.push_str(query);
+    Ok(endpoint)
+}
+
+/// Percent-encode an untrusted string as a single path segment, `/`, `?` and `#` can't change the endpoint.
+///
+/// [Error::InvalidEndpoint] is returned for an empty string, `.` and `..` which have a meaning as a segment.
+pub fn path_segment(value: &str) -> Result<String, Error> {
+    if value.is_empty() || value == "." || value == ".." {
+        return Err(Error::InvalidEndpoint(format!(
+            "{:?} is not a valid path segment",
+            value
+        )));
+    }
+    Ok(percent_encode(value))
+}
+
+/// Percent-encode every byte outside of the RFC 3986 unreserved characters.
+pub(crate) fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// `.` or `..`, with their dots possibly percent-encoded.
+fn is_dot_segment(segment: &str) -> bool {
+    let decoded = segment.to_ascii_lowercase().replace("%2e", ".");
+    decoded == "." || decoded == ".."
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_paths() {
+        assert_eq!(join_path("", "/nolanv").expect("join_path"), "/nolanv");
+        assert_eq!(join_path("/api//v1/", "").expect("join_path"), "/api/v1");
+        assert_eq!(join_path("", "").expect("join_path"), "/");
+        assert_eq!(
+            join_path("/api", "//nolanv//names/?a=//b").expect("join_path"),
+            "/api/nolanv/names/?a=//b"
+        );
+        for path in ["/..", "/a/./b", "/a/%2E%2e/b", "/a/%2e"] {
+            assert!(matches!(
+                join_path("/api", path),
+                Err(Error::InvalidEndpoint(_))
+            ));
+        }
+        assert_eq!(join_path("/api", "/a..b").expect("join_path"), "/api/a..b");
+    }
+
+    #[test]
+    fn path_segments() {
+        assert_eq!(path_segment("nolanv").expect("path_segment"), "nolanv");
+        assert_eq!(
+            path_segment("../a b/?#").expect("path_segment"),
+            "..%2Fa%20b%2F%3F%23"
+        );
+        for value in ["", ".", ".."] {
+            assert!(path_segment(value).is_err());
+        }
+    }
+}
@@ -1,20 +1,66 @@
+use crate::retry::is_retryable;
 use hyper::StatusCode;
 #[cfg(feature = "json")]
-use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer, de::DeserializeOwned, ser::SerializeStruct};
+use std::time::Duration;
 
 /// Internal Error, wrapping other source of error.
 #[derive(Debug)]
 pub enum Error {
     SocketConnectionInitiation(std::io::Error),
+    /// The socket path doesn't fit in `sockaddr_un`, 107 bytes on Linux and 103 on macOS.
+    PathTooLong(std::path::PathBuf),
+    /// The socket path exists and is not a unix socket.
+    NotASocket(std::path::PathBuf),
+    /// The socket or one of its parent directories is not accessible by the process.
+    PermissionDenied(std::path::PathBuf),
+    /// A parent directory of the socket is writable by every user, see [crate::ClientUnixBuilder::refuse_world_writable_parents].
+    WorldWritableParent(std::path::PathBuf),
+    ConnectTimeout(Duration),
+    SocketOption(std::io::Error),
     SocketConnectionClosed(Option<hyper::Error>),
     Handhsake(hyper::Error),
     RequestBuild(hyper::http::Error),
+    /// An endpoint or a path segment would escape its base path, see [crate::join_path].
+    InvalidEndpoint(String),
+    /// The given number of requests are already waiting for a connection, see [crate::ClientUnixBuilder::max_queued_requests].
+    QueueFull(usize),
+    /// No connection of the pool was free after the given duration, the number of requests still waiting for one is given, see [crate::ClientUnixBuilder::pool_timeout].
+    PoolTimeout(Duration, usize),
     RequestSend(hyper::Error),
+    RequestCollect(Box<dyn std::error::Error + Send + Sync>),
+    ConnectionLost(Option<hyper::Error>),
     #[cfg(feature = "json")]
     RequestParsing(serde_json::Error),
     ResponseCollect(hyper::Error),
+    StreamTimeout(hyper::Error),
     #[cfg(feature = "json")]
     ResponseParsing(serde_json::Error),
+    #[cfg(feature = "simd-json")]
+    ResponseParsingSimd(simd_json::Error),
+    DownloadFile(std::io::Error),
+    /// A range of a parallel download was not served as requested, see [crate::ClientUnix::download_parallel].
+    DownloadRange(&'static str),
+    RequestEncode(Box<dyn std::error::Error + Send + Sync>),
+    ResponseDecode(Box<dyn std::error::Error + Send + Sync>),
+    MultipartParsing(&'static str),
+    StdcopyParsing(&'static str),
+    /// Writing or reading a raw exchange failed, see [crate::ClientUnix::send_raw_bytes].
+    RawExchange(std::io::Error),
+    RawResponseParsing(&'static str),
+    Upgrade(hyper::Error),
+    #[cfg(feature = "prometheus")]
+    MetricsParsing(crate::MetricsParseError),
+    #[cfg(feature = "json")]
+    Cassette(std::io::Error),
+    #[cfg(feature = "json")]
+    CassetteMiss(String),
+    #[cfg(feature = "jsonschema")]
+    InvalidSchema(String),
+    #[cfg(feature = "jsonschema")]
+    ResponseValidation(Vec<crate::SchemaViolation>),
+    /// Error of a request sent with a [crate::RequestId], see [crate::ClientUnixBuilder::request_id] and [Error::inner].
+    WithRequestId(crate::RequestId, Box<Error>),
 }
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -22,6 +68,40 @@ impl std::fmt::Display for Error {
             Error::SocketConnectionInitiation(e) => {
                 write!(f, "Failed to connect to unix stream, {}", e)
             }
+            Error::PathTooLong(path) => {
+                write!(
+                    f,
+                    "Failed to connect to unix stream, path is too long: {}",
+                    path.display()
+                )
+            }
+            Error::NotASocket(path) => {
+                write!(
+                    f,
+                    "Failed to connect to unix stream, {} is not a unix socket",
+                    path.display()
+                )
+            }
+            Error::PermissionDenied(path) => {
+                write!(
+                    f,
+                    "Failed to connect to unix stream, permission denied on {}",
+                    path.display()
+                )
+            }
+            Error::WorldWritableParent(path) => {
+                write!(
+                    f,
+                    "Failed to connect to unix stream, {} is writable by every user",
+                    path.display()
+                )
+            }
+            Error::SocketOption(e) => {
+                write!(f, "Failed to set unix stream option, {}", e)
+            }
+            Error::ConnectTimeout(timeout) => {
+                write!(f, "Failed to connect to unix stream within {:?}", timeout)
+            }
             Error::SocketConnectionClosed(None) => {
                 write!(f, "Unix stream was closed without any error.")
             }
@@ -34,9 +114,42 @@ impl std::fmt::Display for Error {
             Error::RequestBuild(e) => {
                 write!(f, "Failed to build http request, {}", e)
             }
+            Error::InvalidEndpoint(e) => {
+                write!(f, "Failed to build http request endpoint, {}", e)
+            }
+            Error::QueueFull(max_queued_requests) => {
+                write!(
+                    f,
+                    "Failed to queue http request, {} requests are already waiting",
+                    max_queued_requests
+                )
+            }
+            Error::PoolTimeout(pool_timeout, waiting) => {
+                write!(
+                    f,
+                    "Failed to acquire a pooled connection after {:?}, {} requests are still waiting",
+                    pool_timeout, waiting
+                )
+            }
             Error::RequestSend(e) => {
                 write!(f, "Failed to send http request, {}", e)
             }
+            Error::RequestCollect(e) => {
+                write!(f, "Failed to collect http request body, {}", e)
+            }
+            Error::ConnectionLost(None) => {
+                write!(
+                    f,
+                    "Connection to the server was lost, it must be reconnected."
+                )
+            }
+            Error::ConnectionLost(Some(e)) => {
+                write!(
+                    f,
+                    "Connection to the server was lost, it must be reconnected, {}",
+                    e
+                )
+            }
             #[cfg(feature = "json")]
             Error::RequestParsing(e) => {
                 write!(f, "Failed to parse http json request, {}", e)
@@ -44,10 +157,71 @@ impl std::fmt::Display for Error {
             Error::ResponseCollect(e) => {
                 write!(f, "Failed to collect http request, {}", e)
             }
+            Error::StreamTimeout(e) => {
+                write!(f, "Unix stream timed out, {}", e)
+            }
             #[cfg(feature = "json")]
             Error::ResponseParsing(e) => {
                 write!(f, "Failed to parse http json response, {}", e)
             }
+            #[cfg(feature = "simd-json")]
+            Error::ResponseParsingSimd(e) => {
+                write!(f, "Failed to parse http json response, {}", e)
+            }
+            Error::DownloadFile(e) => {
+                write!(f, "Failed to write downloaded file, {}", e)
+            }
+            Error::DownloadRange(e) => {
+                write!(f, "Failed to download byte range, {}", e)
+            }
+            Error::RequestEncode(e) => {
+                write!(f, "Failed to encode http request, {}", e)
+            }
+            Error::ResponseDecode(e) => {
+                write!(f, "Failed to decode http response, {}", e)
+            }
+            Error::MultipartParsing(e) => {
+                write!(f, "Failed to parse multipart response, {}", e)
+            }
+            Error::StdcopyParsing(e) => {
+                write!(f, "Failed to demultiplex docker stream, {}", e)
+            }
+            Error::RawExchange(e) => {
+                write!(f, "Failed to exchange raw http request, {}", e)
+            }
+            Error::RawResponseParsing(e) => {
+                write!(f, "Failed to parse raw http response, {}", e)
+            }
+            Error::Upgrade(e) => {
+                write!(f, "Failed to upgrade http connection, {}", e)
+            }
+            #[cfg(feature = "prometheus")]
+            Error::MetricsParsing(e) => {
+                write!(f, "Failed to parse prometheus metrics, {}", e)
+            }
+            #[cfg(feature = "json")]
+            Error::Cassette(e) => {
+                write!(f, "Failed to read or write cassette, {}", e)
+            }
+            #[cfg(feature = "json")]
+            Error::CassetteMiss(request) => {
+                write!(f, "No recorded response in cassette for {}", request)
+            }
+            #[cfg(feature = "jsonschema")]
+            Error::InvalidSchema(e) => {
+                write!(f, "Failed to compile JSON schema, {}", e)
+            }
+            #[cfg(feature = "jsonschema")]
+            Error::ResponseValidation(violations) => {
+                write!(f, "Http json response does not match the schema")?;
+                for (index, violation) in violations.iter().enumerate() {
+                    write!(f, "{} {}", if index == 0 { ":" } else { ";" }, violation)?;
+                }
+                Ok(())
+            }
+            Error::WithRequestId(request_id, e) => {
+                write!(f, "{} (request id {})", e, request_id)
+            }
         }
     }
 }
@@ -55,25 +229,216 @@ impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Error::SocketConnectionInitiation(error) => Some(error),
+            Error::PathTooLong(_)
+            | Error::NotASocket(_)
+            | Error::PermissionDenied(_)
+            | Error::WorldWritableParent(_) => None,
+            Error::SocketOption(error) => Some(error),
+            Error::ConnectTimeout(_) => None,
             Error::SocketConnectionClosed(Some(error)) => Some(error),
             Error::SocketConnectionClosed(None) => None,
             Error::Handhsake(error) => Some(error),
             Error::RequestBuild(error) => Some(error),
+            Error::InvalidEndpoint(_) => None,
+            Error::QueueFull(_) => None,
+            Error::PoolTimeout(_, _) => None,
             Error::RequestSend(error) => Some(error),
+            Error::RequestCollect(error) => Some(error.as_ref()),
+            Error::ConnectionLost(Some(error)) => Some(error),
+            Error::ConnectionLost(None) => None,
             #[cfg(feature = "json")]
             Error::RequestParsing(error) => Some(error),
             Error::ResponseCollect(error) => Some(error),
+            Error::StreamTimeout(error) => Some(error),
             #[cfg(feature = "json")]
             Error::ResponseParsing(error) => Some(error),
+            #[cfg(feature = "simd-json")]
+            Error::ResponseParsingSimd(error) => Some(error),
+            Error::DownloadFile(error) => Some(error),
+            Error::DownloadRange(_) => None,
+            Error::RequestEncode(error) => Some(error.as_ref()),
+            Error::ResponseDecode(error) => Some(error.as_ref()),
+            Error::MultipartParsing(_) => None,
+            Error::StdcopyParsing(_) => None,
+            Error::RawExchange(error) => Some(error),
+            Error::RawResponseParsing(_) => None,
+            Error::Upgrade(error) => Some(error),
+            #[cfg(feature = "prometheus")]
+            Error::MetricsParsing(error) => Some(error),
+            #[cfg(feature = "json")]
+            Error::Cassette(error) => Some(error),
+            #[cfg(feature = "json")]
+            Error::CassetteMiss(_) => None,
+            #[cfg(feature = "jsonschema")]
+            Error::InvalidSchema(_) | Error::ResponseValidation(_) => None,
+            // Its message is already part of this one.
+            Error::WithRequestId(_, error) => error.source(),
         }
     }
 }
 
+/// Class of an [Error], returned by [Error::kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The socket could not be connected or the HTTP handshake failed.
+    Connect,
+    /// A connection, read or write timeout was exceeded.
+    Timeout,
+    /// The connection was closed by the server.
+    Closed,
+    /// The request could not be built, its body could not be serialized, it has no recorded response or its JSON schema is invalid.
+    Request,
+    /// The HTTP exchange failed while sending the request or reading the response.
+    Protocol,
+    /// The response body could not be parsed or doesn't match its JSON schema.
+    Decode,
+    /// A local file could not be read or written.
+    Io,
+    /// The request queue of the client is full or no pooled connection was freed in time, the server is falling behind.
+    Overloaded,
+}
+
+impl Error {
+    /// Class of the error, more stable than the variants when branching on it.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::SocketConnectionInitiation(_)
+            | Error::PathTooLong(_)
+            | Error::NotASocket(_)
+            | Error::PermissionDenied(_)
+            | Error::WorldWritableParent(_)
+            | Error::SocketOption(_)
+            | Error::Handhsake(_) => ErrorKind::Connect,
+            Error::ConnectTimeout(_) | Error::StreamTimeout(_) => ErrorKind::Timeout,
+            Error::RawExchange(e) if e.kind() == std::io::ErrorKind::TimedOut => ErrorKind::Timeout,
+            Error::SocketConnectionClosed(_) | Error::ConnectionLost(_) => ErrorKind::Closed,
+            Error::RequestBuild(_)
+            | Error::InvalidEndpoint(_)
+            | Error::RequestCollect(_)
+            | Error::RequestEncode(_) => ErrorKind::Request,
+            #[cfg(feature = "json")]
+            Error::RequestParsing(_) => ErrorKind::Request,
+            Error::RequestSend(_)
+            | Error::ResponseCollect(_)
+            | Error::Upgrade(_)
+            | Error::RawExchange(_)
+            | Error::RawResponseParsing(_)
+            | Error::DownloadRange(_) => ErrorKind::Protocol,
+            Error::ResponseDecode(_) | Error::MultipartParsing(_) | Error::StdcopyParsing(_) => {
+                ErrorKind::Decode
+            }
+            #[cfg(feature = "json")]
+            Error::ResponseParsing(_) => ErrorKind::Decode,
+            #[cfg(feature = "simd-json")]
+            Error::ResponseParsingSimd(_) => ErrorKind::Decode,
+            #[cfg(feature = "prometheus")]
+            Error::MetricsParsing(_) => ErrorKind::Decode,
+            Error::DownloadFile(_) => ErrorKind::Io,
+            Error::QueueFull(_) | Error::PoolTimeout(_, _) => ErrorKind::Overloaded,
+            #[cfg(feature = "json")]
+            Error::Cassette(_) => ErrorKind::Io,
+            #[cfg(feature = "json")]
+            Error::CassetteMiss(_) => ErrorKind::Request,
+            #[cfg(feature = "jsonschema")]
+            Error::InvalidSchema(_) => ErrorKind::Request,
+            #[cfg(feature = "jsonschema")]
+            Error::ResponseValidation(_) => ErrorKind::Decode,
+            Error::WithRequestId(_, e) => e.kind(),
+        }
+    }
+
+    /// [crate::RequestId] of the failed request, when [crate::ClientUnixBuilder::request_id] is enabled.
+    pub fn request_id(&self) -> Option<&crate::RequestId> {
+        match self {
+            Error::WithRequestId(request_id, _) => Some(request_id),
+            _ => None,
+        }
+    }
+
+    /// The error without its [crate::RequestId], to match on its variant.
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::WithRequestId(_, e) => e,
+            e => e,
+        }
+    }
+
+    /// Whether the connection to the server was lost, usually because it restarted, the client must be reconnected with [crate::ClientUnix::try_reconnect].
+    pub fn is_connection_lost(&self) -> bool {
+        match self.inner() {
+            Error::ConnectionLost(_) | Error::SocketConnectionClosed(_) => true,
+            Error::RequestSend(e) => e.is_canceled() || e.is_closed() || e.is_incomplete_message(),
+            Error::ResponseCollect(e) => e.is_incomplete_message(),
+            _ => false,
+        }
+    }
+
+    /// Whether sending the request again may succeed: the connection was lost or refused, or a timeout was exceeded. An invalid socket path is not retryable.
+    ///
+    /// The request may have been processed by the server, only retry idempotent requests.
+    pub fn is_retryable(&self) -> bool {
+        if matches!(
+            self.inner(),
+            Error::PathTooLong(_)
+                | Error::NotASocket(_)
+                | Error::PermissionDenied(_)
+                | Error::WorldWritableParent(_)
+        ) {
+            return false;
+        }
+        self.is_connection_lost() || matches!(self.kind(), ErrorKind::Timeout | ErrorKind::Connect)
+    }
+
+    /// Wrap a hyper error, using [Error::StreamTimeout] when it was caused by a read or write timeout.
+    pub(crate) fn from_hyper(error: hyper::Error, wrap: fn(hyper::Error) -> Error) -> Error {
+        let mut source = std::error::Error::source(&error);
+        while let Some(inner) = source {
+            if let Some(io_error) = inner.downcast_ref::<std::io::Error>()
+                && io_error.kind() == std::io::ErrorKind::TimedOut
+            {
+                return Error::StreamTimeout(error);
+            }
+            source = inner.source();
+        }
+        wrap(error)
+    }
+}
+
+/// Messages of the chain of sources of an error.
+#[cfg(feature = "json")]
+fn sources(error: &dyn std::error::Error) -> Vec<String> {
+    let mut sources = Vec::new();
+    let mut source = error.source();
+    while let Some(inner) = source {
+        sources.push(inner.to_string());
+        source = inner.source();
+    }
+    sources
+}
+
+/// Structured representation for JSON logs: `kind`, `message`, `status` and `sources` (the messages of the source chain) **(feature = json)**.
+#[cfg(feature = "json")]
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Error", 4)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("status", &None::<u16>)?;
+        state.serialize_field("sources", &sources(self))?;
+        state.end()
+    }
+}
+
 /// Error used by [crate::ClientUnix::send_request] to be able to return unsuccessful HTTP error body.
+///
+/// [ErrorAndResponse::RetriesExhausted] is returned instead of [ErrorAndResponse::ResponseUnsuccessful] when a [crate::RetryPolicy] is set and the server kept rejecting the request, with the `Retry-After` value of the last response.
 #[derive(Debug)]
 pub enum ErrorAndResponse {
     InternalError(Error),
     ResponseUnsuccessful(StatusCode, Vec<u8>),
+    RetriesExhausted(StatusCode, Option<Duration>, Vec<u8>),
 }
 impl std::fmt::Display for ErrorAndResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -88,48 +453,244 @@ impl std::fmt::Display for ErrorAndResponse {
                     status_code
                 )
             }
+            ErrorAndResponse::RetriesExhausted(status_code, retry_after, _) => {
+                write!(
+                    f,
+                    "HTTP request was still rejected after retrying, status code = {}, retry after = {:?}",
+                    status_code, retry_after
+                )
+            }
+        }
+    }
+}
+impl ErrorAndResponse {
+    /// See [Error::is_connection_lost].
+    pub fn is_connection_lost(&self) -> bool {
+        match self {
+            ErrorAndResponse::InternalError(e) => e.is_connection_lost(),
+            _ => false,
         }
     }
+
+    /// See [Error::is_retryable], unsuccessful responses are retryable when the server is overloaded (`429 Too Many Requests` or `503 Service Unavailable`).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ErrorAndResponse::InternalError(e) => e.is_retryable(),
+            ErrorAndResponse::ResponseUnsuccessful(status_code, _) => is_retryable(*status_code),
+            ErrorAndResponse::RetriesExhausted(_, _, _) => false,
+        }
+    }
+}
+/// Same representation as [Error], unsuccessful responses have the `ResponseUnsuccessful` or `RetriesExhausted` kind and their status code, the body is not included **(feature = json)**.
+#[cfg(feature = "json")]
+impl Serialize for ErrorAndResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (kind, status_code) = match self {
+            ErrorAndResponse::InternalError(e) => return e.serialize(serializer),
+            ErrorAndResponse::ResponseUnsuccessful(status_code, _) => {
+                ("ResponseUnsuccessful", status_code)
+            }
+            ErrorAndResponse::RetriesExhausted(status_code, _, _) => {
+                ("RetriesExhausted", status_code)
+            }
+        };
+        let mut state = serializer.serialize_struct("ErrorAndResponse", 4)?;
+        state.serialize_field("kind", kind)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("status", &status_code.as_u16())?;
+        state.serialize_field("sources", &Vec::<String>::new())?;
+        state.end()
+    }
 }
+
 impl std::error::Error for ErrorAndResponse {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ErrorAndResponse::InternalError(error) => error.source(),
             ErrorAndResponse::ResponseUnsuccessful(_, _) => None,
+            ErrorAndResponse::RetriesExhausted(_, _, _) => None,
         }
     }
 }
 
-/// Error used by [crate::ClientUnix::send_request_json] to be able to return unsuccessful HTTP error typed body **(feature = json)**.
-#[cfg(feature = "json")]
+/// Error used by [crate::ClientUnix::send_request_with_codec] to be able to return unsuccessful HTTP error typed body **(feature = codec)**.
+#[cfg(feature = "codec")]
 #[derive(Debug)]
-pub enum ErrorAndResponseJson<ERR: DeserializeOwned> {
+pub enum ErrorAndResponseTyped<ERR> {
     InternalError(Error),
     ResponseUnsuccessful(StatusCode, ERR),
+    RetriesExhausted(StatusCode, Option<Duration>, ERR),
 }
+/// Error used by [crate::ClientUnix::send_request_json] to be able to return unsuccessful HTTP error typed body **(feature = json)**.
 #[cfg(feature = "json")]
-impl<ERR: DeserializeOwned> std::fmt::Display for ErrorAndResponseJson<ERR> {
+pub type ErrorAndResponseJson<ERR> = ErrorAndResponseTyped<ERR>;
+#[cfg(feature = "json")]
+impl<ERR: DeserializeOwned> ErrorAndResponseTyped<ERR> {
+    /// Parse the body of an unsuccessful response as `ERR`.
+    pub(crate) fn from_error_and_response(error: ErrorAndResponse) -> Self {
+        let parse = |response: Vec<u8>| {
+            crate::json::from_bytes(response.into()).map_err(ErrorAndResponseTyped::InternalError)
+        };
+        let result = match error {
+            ErrorAndResponse::InternalError(e) => Err(ErrorAndResponseTyped::InternalError(e)),
+            ErrorAndResponse::ResponseUnsuccessful(status_code, response) => parse(response)
+                .map(|response| ErrorAndResponseTyped::ResponseUnsuccessful(status_code, response)),
+            ErrorAndResponse::RetriesExhausted(status_code, retry_after, response) => {
+                parse(response).map(|response| {
+                    ErrorAndResponseTyped::RetriesExhausted(status_code, retry_after, response)
+                })
+            }
+        };
+        result.unwrap_or_else(|e| e)
+    }
+}
+#[cfg(feature = "codec")]
+impl<ERR> ErrorAndResponseTyped<ERR> {
+    /// See [Error::is_connection_lost].
+    pub fn is_connection_lost(&self) -> bool {
+        match self {
+            ErrorAndResponseTyped::InternalError(e) => e.is_connection_lost(),
+            _ => false,
+        }
+    }
+
+    /// See [ErrorAndResponse::is_retryable].
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ErrorAndResponseTyped::InternalError(e) => e.is_retryable(),
+            ErrorAndResponseTyped::ResponseUnsuccessful(status_code, _) => {
+                is_retryable(*status_code)
+            }
+            ErrorAndResponseTyped::RetriesExhausted(_, _, _) => false,
+        }
+    }
+}
+#[cfg(feature = "codec")]
+impl<ERR> std::fmt::Display for ErrorAndResponseTyped<ERR> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            ErrorAndResponseJson::InternalError(e) => {
+            ErrorAndResponseTyped::InternalError(e) => {
                 write!(f, "Internal error, {}", e)
             }
-            ErrorAndResponseJson::ResponseUnsuccessful(status_code, _) => {
+            ErrorAndResponseTyped::ResponseUnsuccessful(status_code, _) => {
                 write!(
                     f,
                     "HTTP response was not successful, status code = {}",
                     status_code
                 )
             }
+            ErrorAndResponseTyped::RetriesExhausted(status_code, retry_after, _) => {
+                write!(
+                    f,
+                    "HTTP request was still rejected after retrying, status code = {}, retry after = {:?}",
+                    status_code, retry_after
+                )
+            }
         }
     }
 }
+/// Same representation as [ErrorAndResponse], with the typed error response in `body` **(feature = json)**.
 #[cfg(feature = "json")]
-impl<ERR: DeserializeOwned + std::fmt::Debug> std::error::Error for ErrorAndResponseJson<ERR> {
+impl<ERR: Serialize> Serialize for ErrorAndResponseTyped<ERR> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (kind, status_code, body) = match self {
+            ErrorAndResponseTyped::InternalError(e) => return e.serialize(serializer),
+            ErrorAndResponseTyped::ResponseUnsuccessful(status_code, body) => {
+                ("ResponseUnsuccessful", status_code, body)
+            }
+            ErrorAndResponseTyped::RetriesExhausted(status_code, _, body) => {
+                ("RetriesExhausted", status_code, body)
+            }
+        };
+        let mut state = serializer.serialize_struct("ErrorAndResponseTyped", 5)?;
+        state.serialize_field("kind", kind)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("status", &status_code.as_u16())?;
+        state.serialize_field("sources", &Vec::<String>::new())?;
+        state.serialize_field("body", body)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "codec")]
+impl<ERR: std::fmt::Debug> std::error::Error for ErrorAndResponseTyped<ERR> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            ErrorAndResponseJson::InternalError(error) => error.source(),
-            ErrorAndResponseJson::ResponseUnsuccessful(_, _) => None,
+            ErrorAndResponseTyped::InternalError(error) => error.source(),
+            ErrorAndResponseTyped::ResponseUnsuccessful(_, _) => None,
+            ErrorAndResponseTyped::RetriesExhausted(_, _, _) => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind() {
+        assert_eq!(
+            Error::ConnectTimeout(Duration::from_secs(1)).kind(),
+            ErrorKind::Timeout
+        );
+        assert_eq!(Error::ConnectionLost(None).kind(), ErrorKind::Closed);
+        assert_eq!(
+            Error::StdcopyParsing("unknown stream type").kind(),
+            ErrorKind::Decode
+        );
+        assert_eq!(Error::QueueFull(16).kind(), ErrorKind::Overloaded);
+        assert!(!Error::QueueFull(16).is_retryable());
+        assert_eq!(
+            Error::PoolTimeout(Duration::from_secs(1), 4).kind(),
+            ErrorKind::Overloaded
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn serialize() {
+        use serde_json::json;
+
+        assert_eq!(
+            serde_json::to_value(Error::ConnectTimeout(Duration::from_secs(1)))
+                .expect("serde_json::to_value"),
+            json!({
+                "kind": "Timeout",
+                "message": "Failed to connect to unix stream within 1s",
+                "status": null,
+                "sources": [],
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ErrorAndResponseJson::ResponseUnsuccessful(
+                StatusCode::NOT_FOUND,
+                json!({"msg": "not found"})
+            ))
+            .expect("serde_json::to_value"),
+            json!({
+                "kind": "ResponseUnsuccessful",
+                "message": "HTTP response was not successful, status code = 404 Not Found",
+                "status": 404,
+                "sources": [],
+                "body": {"msg": "not found"},
+            })
+        );
+    }
+
+    #[test]
+    fn retryable() {
+        assert!(Error::ConnectionLost(None).is_connection_lost());
+        assert!(Error::ConnectTimeout(Duration::from_secs(1)).is_retryable());
+        assert!(!Error::ConnectTimeout(Duration::from_secs(1)).is_connection_lost());
+        assert!(!Error::DownloadFile(std::io::ErrorKind::Other.into()).is_retryable());
+
+        assert!(
+            ErrorAndResponse::ResponseUnsuccessful(StatusCode::SERVICE_UNAVAILABLE, Vec::new())
+                .is_retryable()
+        );
+        assert!(
+            !ErrorAndResponse::ResponseUnsuccessful(StatusCode::NOT_FOUND, Vec::new())
+                .is_retryable()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{ClientUnix, Error, ErrorAndResponse, ErrorAndResponseJson, json};
+use hyper::{Method, StatusCode, body::Bytes};
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+
+type StatusMapper<E> = Box<dyn Fn(Bytes) -> Result<E, Error> + Send + Sync>;
+type FallbackMapper<E> = Box<dyn Fn(StatusCode, Vec<u8>) -> E + Send + Sync>;
+
+/// Typed errors built from the unsuccessful responses, with a different body type per status code **(feature = json)**
+///
+/// Used by [ClientUnix::send_request_json_mapped] when a single `ERR` type can't describe every failure of an API. Build it once and reuse it for every request.
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{ClientUnix, ErrorAndResponseJson, ErrorMapping, Method, StatusCode};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct NotFoundBody {
+///     resource: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct ConflictBody {
+///     current_version: u64,
+/// }
+///
+/// enum ApiError {
+///     NotFound(NotFoundBody),
+///     Conflict(ConflictBody),
+///     Other(StatusCode, Vec<u8>),
+/// }
+///
+/// pub async fn rename() {
+///     let client = ClientUnix::try_new("/tmp/unix.socket")
+///         .await
+///         .expect("ClientUnix::try_new");
+///     let errors = ErrorMapping::new(ApiError::Other)
+///         .status(StatusCode::NOT_FOUND, ApiError::NotFound)
+///         .status(StatusCode::CONFLICT, ApiError::Conflict);
+///
+///     match client
+///         .send_request_json_mapped::<_, (), _>("/items/42", Method::PATCH, &[], Some(&"nolanv"), &errors)
+///         .await
+///     {
+///         Ok(_) => println!("renamed"),
+///         Err(ErrorAndResponseJson::ResponseUnsuccessful(_, ApiError::Conflict(conflict))) => {
+///             println!("version {} already exists", conflict.current_version)
+///         }
+///         Err(_) => println!("failed"),
+///     }
+/// }
+/// ```
+pub struct ErrorMapping<E> {
+    statuses: HashMap<StatusCode, StatusMapper<E>>,
+    fallback: FallbackMapper<E>,
+}
+
+impl<E> std::fmt::Debug for ErrorMapping<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ErrorMapping")
+            .field("statuses", &self.statuses.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<E> ErrorMapping<E> {
+    /// Create a mapping where every status code is built by `fallback` from the raw body of the response.
+    pub fn new(fallback: impl Fn(StatusCode, Vec<u8>) -> E + Send + Sync + 'static) -> Self {
+        ErrorMapping {
+            statuses: HashMap::new(),
+            fallback: Box::new(fallback),
+        }
+    }
+
+    /// Deserialize the body of the responses with `status_code` as `B`, then turn it into an error with `map`.
+    ///
+    /// A body which can't be deserialized is returned as [Error::ResponseParsing].
+    pub fn status<B: DeserializeOwned>(
+        mut self,
+        status_code: StatusCode,
+        map: impl Fn(B) -> E + Send + Sync + 'static,
+    ) -> Self {
+        self.statuses.insert(
+            status_code,
+            Box::new(move |body| json::from_bytes(body).map(&map)),
+        );
+        self
+    }
+
+    fn map(&self, status_code: StatusCode, body: Vec<u8>) -> Result<E, Error> {
+        match self.statuses.get(&status_code) {
+            Some(map) => map(body.into()),
+            None => Ok((self.fallback)(status_code, body)),
+        }
+    }
+
+    fn map_error(&self, error: ErrorAndResponse) -> ErrorAndResponseJson<E> {
+        let result = match error {
+            ErrorAndResponse::InternalError(e) => Err(e),
+            ErrorAndResponse::ResponseUnsuccessful(status_code, body) => self
+                .map(status_code, body)
+                .map(|e| ErrorAndResponseJson::ResponseUnsuccessful(status_code, e)),
+            ErrorAndResponse::RetriesExhausted(status_code, retry_after, body) => self
+                .map(status_code, body)
+                .map(|e| ErrorAndResponseJson::RetriesExhausted(status_code, retry_after, e)),
+        };
+        result.unwrap_or_else(ErrorAndResponseJson::InternalError)
+    }
+}
+
+impl ClientUnix {
+    /// Send JSON HTTP request, building the typed error of unsuccessful responses with an [ErrorMapping] **(feature = json)**
+    ///
+    /// Same as [ClientUnix::send_request_json] but the body of an unsuccessful response is deserialized to the type registered for its status code in `errors`.
+    pub async fn send_request_json_mapped<IN: Serialize, OUT: DeserializeOwned, E>(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+        errors: &ErrorMapping<E>,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<E>> {
+        let (parts, body_response) = self
+            .send_json_unparsed(endpoint, method, headers, body_request)
+            .await
+            .map_err(|e| errors.map_error(e))?;
+        Ok((
+            parts.status,
+            json::from_bytes(body_response).map_err(ErrorAndResponseJson::InternalError)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct ConflictBody {
+        current_version: u64,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum ApiError {
+        Conflict(ConflictBody),
+        Other(StatusCode, Vec<u8>),
+    }
+
+    #[tokio::test]
+    async fn map_errors_by_status() {
+        let socket_path = make_socket_path_test("error_mapping", "map_errors_by_status");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            match request.starts_with(b"PUT") {
+                true => {
+                    b"HTTP/1.1 409 Conflict\r\ncontent-length: 23\r\n\r\n{\"current_version\": 42}"
+                        .to_vec()
+                }
+                false => b"HTTP/1.1 404 Not Found\r\ncontent-length: 4\r\n\r\nnope".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+        let errors =
+            ErrorMapping::new(ApiError::Other).status(StatusCode::CONFLICT, ApiError::Conflict);
+
+        let result = client
+            .send_request_json_mapped::<(), (), _>("/nolanv", Method::PUT, &[], None, &errors)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponseJson::ResponseUnsuccessful(
+                StatusCode::CONFLICT,
+                ApiError::Conflict(ConflictBody {
+                    current_version: 42
+                })
+            ))
+        ));
+
+        let result = client
+            .send_request_json_mapped::<(), (), _>("/nolanv", Method::GET, &[], None, &errors)
+            .await;
+        match result {
+            Err(ErrorAndResponseJson::ResponseUnsuccessful(StatusCode::NOT_FOUND, error)) => {
+                assert_eq!(
+                    error,
+                    ApiError::Other(StatusCode::NOT_FOUND, b"nope".to_vec())
+                )
+            }
+            result => panic!(
+                "unexpected {:?}",
+                result.map(|(status_code, _)| status_code)
+            ),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+/// Faults injected in the requests sent by a client, set with [crate::ClientUnixBuilder::fault_injection].
+///
+/// Each fault is drawn independently for every request sent to the server, to test the resilience of an application to a flaky server without modifying it. Injected errors are reported as [crate::Error::ConnectionLost], like when the server crashes.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{ClientUnix, FaultInjection};
+/// use std::time::Duration;
+///
+/// pub async fn new_flaky_client() {
+///     ClientUnix::builder("/tmp/unix.socket")
+///         .fault_injection(
+///             FaultInjection::new()
+///                 .delay(0.2, Duration::from_millis(500))
+///                 .error(0.05),
+///         )
+///         .try_build()
+///         .await
+///         .expect("ClientUnixBuilder::try_build");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FaultInjection {
+    delay: Option<(f64, Duration)>,
+    error: f64,
+    truncate: f64,
+    state: Arc<AtomicU64>,
+}
+
+impl Default for FaultInjection {
+    fn default() -> Self {
+        FaultInjection {
+            delay: None,
+            error: 0.0,
+            truncate: 0.0,
+            state: Arc::new(AtomicU64::new(RandomState::new().hash_one(0))),
+        }
+    }
+}
+
+/// Faults drawn for a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Fault {
+    pub(crate) delay: Option<Duration>,
+    pub(crate) error: bool,
+    /// Random number picking the length at which the response body is cut.
+    pub(crate) truncate: Option<u64>,
+}
+
+impl FaultInjection {
+    /// Inject no fault, add them with the other methods.
+    pub fn new() -> Self {
+        FaultInjection::default()
+    }
+
+    /// Delay `probability` of the requests by `delay` before sending them.
+    ///
+    /// # Panics
+    /// Panics if `probability` is not between 0 and 1.
+    pub fn delay(mut self, probability: f64, delay: Duration) -> Self {
+        assert_probability(probability);
+        self.delay = Some((probability, delay));
+        self
+    }
+
+    /// Fail `probability` of the requests without sending them.
+    ///
+    /// # Panics
+    /// Panics if `probability` is not between 0 and 1.
+    pub fn error(mut self, probability: f64) -> Self {
+        assert_probability(probability);
+        self.error = probability;
+        self
+    }
+
+    /// Cut the body of `probability` of the responses at a random length.
+    ///
+    /// # Panics
+    /// Panics if `probability` is not between 0 and 1.
+    pub fn truncate(mut self, probability: f64) -> Self {
+        assert_probability(probability);
+        self.truncate = probability;
+        self
+    }
+
+    /// Seed the random generator to inject the same faults on every run. Default is a random seed.
+    pub fn seed(self, seed: u64) -> Self {
+        self.state.store(seed, Ordering::Relaxed);
+        self
+    }
+
+    pub(crate) fn draw(&self) -> Fault {
+        Fault {
+            delay: self
+                .delay
+                .and_then(|(probability, delay)| (self.random() < probability).then_some(delay)),
+            error: self.random() < self.error,
+            truncate: (self.random() < self.truncate).then(|| self.next()),
+        }
+    }
+
+    /// Uniform number in `[0, 1)`.
+    fn random(&self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// SplitMix64, shared by every request of the client without locking.
+    fn next(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9e3779b97f4a7c15, Ordering::Relaxed)
+            .wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl Fault {
+    /// Length of the truncated body, always shorter than `len` when it is not empty.
+    pub(crate) fn truncated_len(&self, len: usize) -> Option<usize> {
+        let truncate = self.truncate?;
+        (len > 0).then(|| (truncate % len as u64) as usize)
+    }
+}
+
+fn assert_probability(probability: f64) {
+    assert!(
+        (0.0..=1.0).contains(&probability),
+        "probability must be between 0 and 1"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, Error, ErrorAndResponse,
+        test_helpers::{server::Server, util::*},
+    };
+    use hyper::Method;
+
+    #[test]
+    fn draw_probabilities() {
+        let fault_injection = FaultInjection::new().error(0.25).seed(42);
+        let errors = (0..10_000).filter(|_| fault_injection.draw().error).count();
+        assert!((2_000..3_000).contains(&errors));
+
+        assert_eq!(FaultInjection::new().draw(), Fault::default());
+    }
+
+    #[tokio::test]
+    async fn fault_injection() {
+        let socket_path = make_socket_path_test("fault", "fault_injection");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .fault_injection(FaultInjection::new().truncate(1.0))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert!(response.len() < "Hello nolanv".len());
+
+        let client = ClientUnix::builder(&socket_path)
+            .fault_injection(FaultInjection::new().error(1.0))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let result = client.send_request("/nolanv", Method::GET, &[], None).await;
+        assert!(matches!(
+            result.err(),
+            Some(ErrorAndResponse::InternalError(Error::ConnectionLost(None)))
+        ));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::Body;
+use crate::{
+    Error,
+    headers::{REDACTED, SECRET_HEADERS, merge_redacted},
+    timing::Timing,
+};
+use http_body_util::BodyExt;
+use hyper::{
+    HeaderMap, Request, Version,
+    body::Bytes,
+    header::{CONTENT_TYPE, HeaderName},
+    http::response::Parts,
+};
+use serde_json::{Value, json};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Recorder of the client traffic in the HTTP Archive (HAR) format, set with [crate::ClientUnixBuilder::har_recorder] **(feature = json)**
+///
+/// Every request whose response body is read by the client is recorded while the recorder is enabled, streamed responses are not recorded. The values of the `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers, and of those given to [crate::ClientUnixBuilder::redact_header], are redacted. Clones share the same entries, keep one to export them.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{ClientUnix, HarRecorder, Method};
+///
+/// pub async fn record() {
+///     let recorder = HarRecorder::new();
+///     let client = ClientUnix::builder("/tmp/unix.socket")
+///         .har_recorder(recorder.clone())
+///         .try_build()
+///         .await
+///         .expect("ClientUnixBuilder::try_build");
+///
+///     client
+///         .send_request("/nolanv", Method::GET, &[], None)
+///         .await
+///         .expect("client.send_request");
+///     std::fs::write("/tmp/traffic.har", recorder.to_har().to_string()).expect("fs::write");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HarRecorder {
+    enabled: Arc<AtomicBool>,
+    redacted_headers: Arc<Vec<HeaderName>>,
+    entries: Arc<Mutex<Vec<Value>>>,
+}
+
+impl Default for HarRecorder {
+    fn default() -> Self {
+        HarRecorder {
+            enabled: Arc::new(AtomicBool::new(true)),
+            redacted_headers: Arc::new(SECRET_HEADERS.to_vec()),
+            entries: Arc::default(),
+        }
+    }
+}
+
+/// Sent request kept in the response extensions until the response body is read.
+#[derive(Debug, Clone)]
+pub(crate) struct HarRequest {
+    started: SystemTime,
+    entry: Value,
+}
+
+impl HarRecorder {
+    /// Create an enabled recorder.
+    pub fn new() -> Self {
+        HarRecorder::default()
+    }
+
+    /// Redact the value of another header, like a custom token header.
+    pub fn redact_header(mut self, name: HeaderName) -> Self {
+        Arc::make_mut(&mut self.redacted_headers).push(name);
+        self
+    }
+
+    /// Also redact the headers given to [crate::ClientUnixBuilder::redact_header].
+    pub(crate) fn redact_headers(&mut self, redacted: &[HeaderName]) {
+        merge_redacted(Arc::make_mut(&mut self.redacted_headers), redacted);
+    }
+
+    /// Start or stop recording, the entries already recorded are kept.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Forget the recorded entries.
+    pub fn clear(&self) {
+        self.entries.lock().expect("HarRecorder lock").clear();
+    }
+
+    /// Export the recorded entries as a HAR 1.2 document, readable by the browser devtools.
+    pub fn to_har(&self) -> Value {
+        let entries = self.entries.lock().expect("HarRecorder lock").clone();
+        json!({
+            "log": {
+                "version": "1.2",
+                "creator": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "entries": entries,
+            }
+        })
+    }
+
+    /// Snapshot the request before it is sent, its body is buffered.
+    pub(crate) async fn request(
+        &self,
+        request: Request<Body>,
+    ) -> Result<(Request<Body>, Option<HarRequest>), Error> {
+        if !self.is_enabled() {
+            return Ok((request, None));
+        }
+        let (parts, body) = request.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(Error::RequestCollect)?
+            .to_bytes();
+
+        let query_string: Vec<Value> = parts
+            .uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                json!({ "name": name, "value": value })
+            })
+            .collect();
+        let mut entry = json!({
+            "method": parts.method.as_str(),
+            "url": parts.uri.to_string(),
+            "httpVersion": http_version(parts.version),
+            "cookies": [],
+            "headers": self.headers(&parts.headers),
+            "queryString": query_string,
+            "headersSize": -1,
+            "bodySize": body.len(),
+        });
+        if !body.is_empty() {
+            entry["postData"] = json!({
+                "mimeType": mime_type(&parts.headers),
+                "text": String::from_utf8_lossy(&body),
+            });
+        }
+
+        let request = Request::from_parts(parts, Body::from(body));
+        let started = SystemTime::now();
+        Ok((request, Some(HarRequest { started, entry })))
+    }
+
+    /// Record the exchange once the response body is read.
+    pub(crate) fn record(&self, request: HarRequest, parts: &Parts, body: &Bytes) {
+        let timing = parts
+            .extensions
+            .get::<Timing>()
+            .copied()
+            .unwrap_or_default();
+        let mut content = json!({
+            "size": body.len(),
+            "mimeType": mime_type(&parts.headers),
+        });
+        match std::str::from_utf8(body) {
+            Ok(text) => content["text"] = json!(text),
+            Err(_) => {
+                content["text"] = json!(base64(body));
+                content["encoding"] = json!("base64");
+            }
+        }
+
+        let entry = json!({
+            "startedDateTime": iso_8601(request.started),
+            "time": millis(timing.total()),
+            "request": request.entry,
+            "response": {
+                "status": parts.status.as_u16(),
+                "statusText": parts.status.canonical_reason().unwrap_or_default(),
+                "httpVersion": http_version(parts.version),
+                "cookies": [],
+                "headers": self.headers(&parts.headers),
+                "content": content,
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": body.len(),
+            },
+            "cache": {},
+            "timings": {
+                "blocked": millis(timing.queue),
+                "send": millis(timing.write),
+                "wait": millis(timing.time_to_first_byte.saturating_sub(timing.write)),
+                "receive": millis(timing.body),
+            },
+        });
+        self.entries.lock().expect("HarRecorder lock").push(entry);
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> Vec<Value> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = match self.redacted_headers.contains(name) {
+                    true => REDACTED.into(),
+                    false => String::from_utf8_lossy(value.as_bytes()),
+                };
+                json!({ "name": name.as_str(), "value": value })
+            })
+            .collect()
+    }
+}
+
+fn http_version(version: Version) -> String {
+    format!("{:?}", version)
+}
+
+fn mime_type(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+        .unwrap_or_default()
+}
+
+fn millis(duration: std::time::Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Format a date as `2025-08-14T09:30:12.345Z`.
+fn iso_8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (hour, minute, second) = (seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+    // Civil date from the number of days since 1970-01-01, by Howard Hinnant.
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        since_epoch.subsec_millis()
+    )
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk.iter().enumerate().fold(0u32, |triple, (i, byte)| {
+            triple | u32::from(*byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix,
+        test_helpers::{server::Server, util::*},
+    };
+    use hyper::Method;
+    use std::time::Duration;
+
+    #[test]
+    fn format_date_and_base64() {
+        assert_eq!(
+            iso_8601(UNIX_EPOCH + Duration::from_millis(1_755_163_812_345)),
+            "2025-08-14T09:30:12.345Z"
+        );
+        assert_eq!(base64(b"baguette\xff"), "YmFndWV0dGX/");
+        assert_eq!(base64(b"pain"), "cGFpbg==");
+    }
+
+    #[tokio::test]
+    async fn har_recorder() {
+        let socket_path = make_socket_path_test("har", "har_recorder");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let recorder = HarRecorder::new();
+        let client = ClientUnix::builder(&socket_path)
+            .har_recorder(recorder.clone())
+            .redact_header(HeaderName::from_static("x-token"))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        client
+            .send_request(
+                "/nolanv?page=2",
+                Method::GET,
+                &[("Authorization", "Bearer secret"), ("X-Token", "secret")],
+                None,
+            )
+            .await
+            .expect("client.send_request");
+        recorder.set_enabled(false);
+        client
+            .send_request("/nolanv2", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+
+        let har = recorder.to_har();
+        let entries = har["log"]["entries"].as_array().expect("entries");
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry["request"]["url"], "http://unix.socket/nolanv?page=2");
+        assert_eq!(
+            entry["request"]["queryString"],
+            json!([{ "name": "page", "value": "2" }])
+        );
+        let headers = entry["request"]["headers"].as_array().expect("headers");
+        assert!(headers.contains(&json!({ "name": "authorization", "value": "<redacted>" })));
+        assert!(headers.contains(&json!({ "name": "x-token", "value": "<redacted>" })));
+        assert_eq!(entry["response"]["status"], 200);
+        assert_eq!(entry["response"]["content"]["text"], "Hello nolanv");
+    }
+}
@@ -0,0 +1,145 @@
+use hyper::{
+    HeaderMap, Version,
+    header::{CONNECTION, Entry, HeaderName, HeaderValue},
+    http::response::Parts,
+};
+
+/// Headers carrying credentials, redacted by default when requests are logged or recorded.
+pub(crate) const SECRET_HEADERS: [HeaderName; 4] = [
+    hyper::header::AUTHORIZATION,
+    hyper::header::PROXY_AUTHORIZATION,
+    hyper::header::COOKIE,
+    hyper::header::SET_COOKIE,
+];
+
+/// Value written in place of a redacted header value.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Copy of `headers` with the values of the `redacted` headers replaced by [REDACTED].
+pub(crate) fn redact(headers: &HeaderMap, redacted: &[HeaderName]) -> HeaderMap {
+    let mut headers = headers.clone();
+    for name in redacted {
+        if let Entry::Occupied(mut entry) = headers.entry(name) {
+            for value in entry.iter_mut() {
+                *value = HeaderValue::from_static(REDACTED);
+            }
+        }
+    }
+    headers
+}
+
+/// Whether the server keeps the connection open after a response, from its `Connection` header and HTTP version.
+pub(crate) fn keeps_alive(parts: &Parts) -> bool {
+    let has_token = |token: &str| {
+        parts
+            .headers
+            .get_all(CONNECTION)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(token))
+    };
+    match parts.version {
+        Version::HTTP_10 => has_token("keep-alive"),
+        _ => !has_token("close"),
+    }
+}
+
+/// Add the names of `redacted` missing from `names`.
+#[cfg(any(feature = "json", feature = "debug-wire"))]
+pub(crate) fn merge_redacted(names: &mut Vec<HeaderName>, redacted: &[HeaderName]) {
+    for name in redacted {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+}
+
+/// Response headers, in the order they were received.
+///
+/// When [crate::ClientUnixBuilder::preserve_header_case] is enabled, header names keep the casing written by the server, otherwise they are lowercased by hyper.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OriginalHeaders(Vec<(String, HeaderValue)>);
+
+impl OriginalHeaders {
+    pub(crate) fn from_header_map(header_map: &HeaderMap) -> Self {
+        OriginalHeaders(
+            header_map
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn from_httparse(headers: &[httparse::Header]) -> Self {
+        OriginalHeaders(
+            headers
+                .iter()
+                .filter_map(|header| {
+                    HeaderValue::from_bytes(header.value)
+                        .ok()
+                        .map(|value| (header.name.to_string(), value))
+                })
+                .collect(),
+        )
+    }
+
+    /// Parse the headers of a raw HTTP/1 response head.
+    pub(crate) fn parse(head: &[u8]) -> Option<Self> {
+        let mut headers = vec![httparse::EMPTY_HEADER; 100];
+        let mut response = httparse::Response::new(&mut headers);
+        response.parse(head).ok()?;
+
+        Some(OriginalHeaders::from_httparse(response.headers))
+    }
+
+    /// Get the first value of a header, the name is compared case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&HeaderValue> {
+        self.0
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Iterate over the headers, in the order they were received.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &HeaderValue)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for OriginalHeaders {
+    type Item = (String, HeaderValue);
+    type IntoIter = std::vec::IntoIter<(String, HeaderValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keep_case_and_order() {
+        let headers = OriginalHeaders::parse(
+            b"HTTP/1.1 200 OK\r\nx-Bread: Baguette\r\nContent-Length: 0\r\nX-BREAD: Pain\r\n\r\n",
+        )
+        .expect("OriginalHeaders::parse");
+
+        let names: Vec<&str> = headers.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["x-Bread", "Content-Length", "X-BREAD"]);
+        assert_eq!(
+            headers.get("x-bread"),
+            Some(&HeaderValue::from_static("Baguette"))
+        );
+    }
+}
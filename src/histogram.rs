@@ -0,0 +1,148 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Each power of two is split in `2^SUB_BUCKET_BITS` buckets, bounding the error of a value to about 3%.
+const SUB_BUCKET_BITS: u32 = 5;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+const BUCKETS: usize = (SUB_BUCKETS * (64 - SUB_BUCKET_BITS as u64 + 1)) as usize;
+
+/// Latencies of the requests, in microseconds, in log-linear buckets like an HDR histogram.
+#[derive(Debug)]
+pub(crate) struct LatencyRecorder {
+    buckets: Box<[AtomicU64]>,
+}
+
+impl Default for LatencyRecorder {
+    fn default() -> Self {
+        LatencyRecorder {
+            buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+}
+
+impl LatencyRecorder {
+    pub(crate) fn record(&self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.buckets[bucket(micros)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> LatencyHistogram {
+        LatencyHistogram {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.load(Ordering::Relaxed))
+                .collect(),
+        }
+    }
+}
+
+fn bucket(micros: u64) -> usize {
+    if micros < SUB_BUCKETS {
+        return micros as usize;
+    }
+    let exponent = 63 - micros.leading_zeros();
+    let sub_bucket = (micros >> (exponent - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+    (SUB_BUCKETS * u64::from(exponent - SUB_BUCKET_BITS + 1) + sub_bucket) as usize
+}
+
+/// Highest value counted in the bucket.
+fn highest_value(bucket: usize) -> u64 {
+    let bucket = bucket as u64;
+    if bucket < SUB_BUCKETS {
+        return bucket;
+    }
+    let shift = bucket / SUB_BUCKETS - 1;
+    let lowest = (SUB_BUCKETS + bucket % SUB_BUCKETS) << shift;
+    lowest + ((1 << shift) - 1)
+}
+
+/// Distribution of the request latencies, in [crate::ClientStats::latency].
+///
+/// The latency of a request goes from queueing it until its response body is read, see [crate::Timing::total]. Streamed responses are not measured. Values are rounded up with a precision of about 3%.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+}
+
+impl LatencyHistogram {
+    /// Number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Latency under which `percentile` percent of the requests completed, [None] when nothing was recorded.
+    ///
+    /// # Panics
+    /// Panics if `percentile` is not between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((percentile / 100.0 * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        let bucket = self.buckets.iter().position(|bucket| {
+            seen += bucket;
+            seen >= rank
+        })?;
+        Some(Duration::from_micros(highest_value(bucket)))
+    }
+
+    pub fn p50(&self) -> Option<Duration> {
+        self.percentile(50.0)
+    }
+
+    pub fn p95(&self) -> Option<Duration> {
+        self.percentile(95.0)
+    }
+
+    pub fn p99(&self) -> Option<Duration> {
+        self.percentile(99.0)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.percentile(100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_bounds() {
+        for micros in [0, 1, 31, 32, 33, 63, 64, 1_000, 123_456_789, u64::MAX] {
+            let bucket = bucket(micros);
+            assert!(highest_value(bucket) >= micros);
+            assert!(highest_value(bucket) - micros <= micros / SUB_BUCKETS);
+        }
+        assert_eq!(bucket(u64::MAX), BUCKETS - 1);
+    }
+
+    #[test]
+    fn percentiles() {
+        let recorder = LatencyRecorder::default();
+        assert_eq!(recorder.snapshot().p50(), None);
+        for millis in 1..=1000 {
+            recorder.record(Duration::from_millis(millis));
+        }
+
+        let histogram = recorder.snapshot();
+        assert_eq!(histogram.count(), 1000);
+        let close_to = |value: Option<Duration>, expected: u64| {
+            let value = value.expect("percentile").as_secs_f64() * 1000.0;
+            value >= expected as f64 && value <= expected as f64 * 1.04
+        };
+        assert!(close_to(histogram.p50(), 500));
+        assert!(close_to(histogram.p95(), 950));
+        assert!(close_to(histogram.p99(), 990));
+        assert!(close_to(histogram.max(), 1000));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::request_id::RequestIdGenerator;
+use hyper::Method;
+
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// New `Idempotency-Key` for the POST and PATCH requests not having one, see [crate::ClientUnixBuilder::idempotency_keys].
+pub(crate) fn idempotency_key(
+    generator: &RequestIdGenerator,
+    method: &Method,
+    headers: &[(&str, &str)],
+) -> Option<String> {
+    let has_key = headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER));
+    (!has_key && (method == Method::POST || method == Method::PATCH)).then(|| generator.generate())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Method, RetryPolicy,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+    use std::{
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn same_key_across_retries() {
+        let socket_path = make_socket_path_test("idempotency", "same_key_across_retries");
+        let keys = Arc::new(Mutex::new(Vec::new()));
+        let received = keys.clone();
+        let attempts = AtomicUsize::new(0);
+        let _server = RawServer::try_new_with(&socket_path, false, move |request| {
+            let request = String::from_utf8_lossy(request);
+            received.lock().unwrap().push(
+                request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("idempotency-key: "))
+                    .map(str::to_string),
+            );
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n".to_vec(),
+                _ => b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .idempotency_keys(true)
+            .retry_policy(RetryPolicy::new(2).base_delay(Duration::from_millis(1)))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        client
+            .send_request("/nolanv", Method::POST, &[], None)
+            .await
+            .expect("client.send_request");
+        client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        client
+            .send_request("/nolanv", Method::PATCH, &[("Idempotency-Key", "42")], None)
+            .await
+            .expect("client.send_request");
+
+        let keys = keys.lock().unwrap().clone();
+        assert_eq!(keys.len(), 4);
+        assert_eq!(keys[0].as_ref().map(String::len), Some(36));
+        assert_eq!(keys[0], keys[1]);
+        assert_eq!(keys[2], None);
+        assert_eq!(keys[3].as_deref(), Some("42"));
+    }
+}
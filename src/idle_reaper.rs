@@ -0,0 +1,23 @@
+use crate::{Clock, pool::Pool};
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+/// Close the connections of `pools` idle for `idle_timeout`, until they are dropped with the client and all its clones.
+///
+/// The pools are checked every half timeout, so a connection is closed after being idle between one and one and a half times the timeout.
+pub(crate) fn spawn(idle_timeout: Duration, clock: Arc<dyn Clock>, pools: Vec<Weak<Pool>>) {
+    tokio::task::spawn(async move {
+        loop {
+            clock.sleep(idle_timeout / 2).await;
+            let pools: Vec<Arc<Pool>> = pools.iter().filter_map(Weak::upgrade).collect();
+            if pools.is_empty() {
+                return;
+            }
+            for pool in pools {
+                pool.reap_idle(idle_timeout);
+            }
+        }
+    });
+}
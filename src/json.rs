@@ -0,0 +1,192 @@
+use crate::Body;
+use crate::Error;
+use hyper::{StatusCode, body::Bytes};
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeOwned, Visitor},
+};
+
+/// Serialize a JSON request body.
+pub(crate) fn to_body<IN: Serialize>(body_request: Option<&IN>) -> Result<Body, Error> {
+    match body_request {
+        Some(body_request) => Ok(Body::from(
+            serde_json::to_vec(body_request).map_err(Error::RequestParsing)?,
+        )),
+        None => Ok(Body::empty()),
+    }
+}
+
+/// Empty bodies (`204 No Content`, ...) are deserialized as `null`, so they can be received as an [Option] or `()`.
+fn is_empty(body: &[u8]) -> bool {
+    body.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Error of [Discard], the type is not `()`.
+#[derive(Debug)]
+struct NotUnit;
+
+impl std::fmt::Display for NotUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not a unit type")
+    }
+}
+impl std::error::Error for NotUnit {}
+impl serde::de::Error for NotUnit {
+    fn custom<E: std::fmt::Display>(_: E) -> Self {
+        NotUnit
+    }
+}
+
+/// Deserializer only able to build `()` and unit structs, so ignored bodies are never parsed.
+struct Discard;
+
+impl<'de> serde::Deserializer<'de> for Discard {
+    type Error = NotUnit;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, NotUnit> {
+        Err(NotUnit)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NotUnit> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NotUnit> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option newtype_struct seq tuple tuple_struct map struct
+        enum identifier ignored_any
+    }
+}
+
+/// Deserialize a JSON response body, straight from the collected bytes.
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_bytes<T: DeserializeOwned>(body: Bytes) -> Result<T, Error> {
+    if let Ok(value) = T::deserialize(Discard) {
+        return Ok(value);
+    }
+    from_slice(&body)
+}
+
+/// Deserialize a JSON response body with simd-json, which parses in place so the bytes are only copied when they are shared.
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_bytes<T: DeserializeOwned>(body: Bytes) -> Result<T, Error> {
+    if let Ok(value) = T::deserialize(Discard) {
+        return Ok(value);
+    }
+    let mut body = match is_empty(&body) {
+        true => b"null".to_vec(),
+        false => Vec::from(body),
+    };
+    let mut deserializer =
+        simd_json::Deserializer::from_slice(&mut body).map_err(Error::ResponseParsingSimd)?;
+    deserialize(&mut deserializer).map_err(Error::ResponseParsingSimd)
+}
+
+fn from_slice<'de, T: Deserialize<'de>>(body: &'de [u8]) -> Result<T, Error> {
+    let body = match is_empty(body) {
+        true => b"null",
+        false => body,
+    };
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = deserialize(&mut deserializer).map_err(Error::ResponseParsing)?;
+    deserializer.end().map_err(Error::ResponseParsing)?;
+    Ok(value)
+}
+
+#[cfg(not(feature = "path-to-error"))]
+fn deserialize<'de, T: Deserialize<'de>, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize(deserializer)
+}
+
+/// Deserialize tracking the path of the current field, so errors tell where the document is invalid.
+#[cfg(feature = "path-to-error")]
+fn deserialize<'de, T: Deserialize<'de>, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        match path.as_str() {
+            "." => e.into_inner(),
+            _ => serde::de::Error::custom(format!("{}: {}", path, e.into_inner())),
+        }
+    })
+}
+
+/// Response body kept alive by [crate::ClientUnix::send_request_json_borrowed] **(feature = json)**.
+///
+/// The body is deserialized on demand with [BorrowedJson::parse], types borrowing from it (`&str`, `&[u8]`, `&RawValue`, ...) are valid as long as the [BorrowedJson] is.
+#[derive(Debug, Clone)]
+pub struct BorrowedJson {
+    status_code: StatusCode,
+    body: Bytes,
+}
+
+impl BorrowedJson {
+    pub(crate) fn new(status_code: StatusCode, body: Bytes) -> Self {
+        BorrowedJson { status_code, body }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    /// Raw response body.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Deserialize the body, borrowing from it when possible.
+    pub fn parse<'de, T: Deserialize<'de>>(&'de self) -> Result<T, Error> {
+        from_slice(&self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Item {
+        id: u32,
+    }
+
+    #[cfg(feature = "path-to-error")]
+    #[test]
+    fn error_path() {
+        let error =
+            from_slice::<Vec<Item>>(br#"[{"id": 1}, {"id": "2"}]"#).expect_err("from_slice");
+        assert!(error.to_string().contains("[1].id: invalid type"));
+    }
+
+    #[test]
+    fn discard_unit() {
+        from_bytes::<()>(Bytes::from_static(b"not even json")).expect("from_bytes");
+        assert!(from_bytes::<Option<()>>(Bytes::from_static(b"not even json")).is_err());
+        assert_eq!(
+            from_bytes::<Option<()>>(Bytes::from_static(b"")).expect("from_bytes"),
+            None
+        );
+    }
+
+    #[test]
+    fn borrowed_parse() {
+        let body = BorrowedJson::new(StatusCode::OK, Bytes::from_static(br#"{"id": 1} "#));
+        assert_eq!(body.parse::<Item>().expect("parse").id, 1);
+        assert!(
+            BorrowedJson::new(StatusCode::OK, Bytes::from_static(br#"{"id": 1} x"#))
+                .parse::<Item>()
+                .is_err()
+        );
+    }
+}
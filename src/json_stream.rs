@@ -0,0 +1,259 @@
+use crate::{ClientUnix, Error, body::ResponseBody, error::ErrorAndResponseJson, json};
+use futures_util::{Stream, stream};
+use hyper::{Method, StatusCode, http::Extensions};
+use serde::{Serialize, de::DeserializeOwned};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    BeforeArray,
+    BeforeElement,
+    InElement,
+    AfterArray,
+}
+
+/// Incremental splitter of the elements of a JSON array, fed with chunks of the body.
+#[derive(Debug)]
+struct ArraySplitter {
+    buffer: Vec<u8>,
+    position: usize,
+    element_start: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    state: State,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::ResponseParsing(serde::de::Error::custom(message))
+}
+
+impl ArraySplitter {
+    fn new() -> Self {
+        ArraySplitter {
+            buffer: Vec::new(),
+            position: 0,
+            element_start: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            state: State::BeforeArray,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Next complete element of the array, [None] when more bytes are needed.
+    fn next_element(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        while let Some(&byte) = self.buffer.get(self.position) {
+            match self.state {
+                _ if byte.is_ascii_whitespace() && self.state != State::InElement => {}
+                State::BeforeArray if byte == b'[' => self.state = State::BeforeElement,
+                State::BeforeArray => return Err(invalid("expected a JSON array")),
+                State::BeforeElement if byte == b']' => self.state = State::AfterArray,
+                State::BeforeElement => {
+                    self.state = State::InElement;
+                    self.element_start = self.position;
+                    continue;
+                }
+                State::InElement if self.in_string => match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                },
+                State::InElement => match byte {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' if self.depth > 0 => self.depth -= 1,
+                    b',' | b']' if self.depth == 0 => {
+                        let element = self.buffer[self.element_start..self.position].to_vec();
+                        self.state = match byte {
+                            b',' => State::BeforeElement,
+                            _ => State::AfterArray,
+                        };
+                        self.buffer.drain(..=self.position);
+                        self.position = 0;
+                        return Ok(Some(element));
+                    }
+                    b'}' => return Err(invalid("unbalanced JSON array element")),
+                    _ => {}
+                },
+                State::AfterArray => return Err(invalid("trailing characters after JSON array")),
+            }
+            self.position += 1;
+        }
+
+        // Only keep the bytes of the element being read.
+        if self.state != State::InElement {
+            self.buffer.clear();
+            self.position = 0;
+        }
+        Ok(None)
+    }
+
+    fn finish(&self) -> Result<(), Error> {
+        match self.state {
+            State::AfterArray => Ok(()),
+            _ => Err(invalid("unexpected end of JSON array")),
+        }
+    }
+}
+
+impl ClientUnix {
+    /// Send JSON HTTP request and deserialize the response array element by element **(feature = json)**
+    ///
+    /// The response body must be a JSON array, its elements are yielded as soon as they are received, without keeping the whole array in memory. The connection is released once the stream is fully read, dropping it before closes the connection. Unlike [ClientUnix::send_request_json], the response is not cached, coalesced nor retried.
+    /// # Example
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    /// use serde_json::Value;
+    ///
+    /// pub async fn list_events() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, events) = client
+    ///         .send_request_json_stream::<(), Value, Value>("/events", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_json_stream");
+    ///     let mut events = std::pin::pin!(events);
+    ///     while let Some(event) = events.next().await {
+    ///         println!("{:?}", event.expect("event"));
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_json_stream<
+        IN: Serialize,
+        OUT: DeserializeOwned,
+        ERR: DeserializeOwned,
+    >(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<
+        (
+            StatusCode,
+            impl Stream<Item = Result<OUT, Error>> + Send + 'static,
+        ),
+        ErrorAndResponseJson<ERR>,
+    > {
+        let mut headers = headers.to_vec();
+        headers.push(("Content-Type", "application/json"));
+        let body_request =
+            json::to_body(body_request).map_err(ErrorAndResponseJson::InternalError)?;
+
+        let (parts, body_response) = self
+            .start(
+                endpoint,
+                method,
+                &headers,
+                Some(body_request),
+                &Extensions::new(),
+            )
+            .await
+            .map_err(ErrorAndResponseJson::InternalError)?;
+
+        if !parts.status.is_success() {
+            let body_response = body_response
+                .collect()
+                .await
+                .map_err(ErrorAndResponseJson::InternalError)?;
+            return Err(ErrorAndResponseJson::from_error_and_response(
+                self.unsuccessful(&parts, body_response),
+            ));
+        }
+        Ok((parts.status, elements(body_response)))
+    }
+}
+
+fn elements<OUT: DeserializeOwned>(
+    body_response: ResponseBody,
+) -> impl Stream<Item = Result<OUT, Error>> + Send + 'static {
+    stream::unfold(
+        Some((body_response, ArraySplitter::new())),
+        |state| async move {
+            let (mut body_response, mut splitter) = state?;
+            loop {
+                match splitter.next_element() {
+                    Ok(Some(element)) => {
+                        let element = json::from_bytes(element.into());
+                        return Some((element, Some((body_response, splitter))));
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Some((Err(e), None)),
+                }
+                match body_response.data().await {
+                    Some(Ok(data)) => splitter.push(&data),
+                    Some(Err(e)) => return Some((Err(e), None)),
+                    None => return splitter.finish().err().map(|e| (Err(e), None)),
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use futures_util::StreamExt;
+    use serde_json::{Value, json};
+
+    fn split(chunks: &[&str]) -> Result<Vec<String>, Error> {
+        let mut splitter = ArraySplitter::new();
+        let mut elements = Vec::new();
+        for chunk in chunks {
+            splitter.push(chunk.as_bytes());
+            while let Some(element) = splitter.next_element()? {
+                elements.push(String::from_utf8(element).expect("String::from_utf8"));
+            }
+        }
+        splitter.finish()?;
+        Ok(elements)
+    }
+
+    #[test]
+    fn split_elements() {
+        assert_eq!(
+            split(&[" [1, {\"a\": [2, ", "\"],\\\"\"]}", ", \"x\"", "] "]).expect("split"),
+            vec!["1", "{\"a\": [2, \"],\\\"\"]}", "\"x\""]
+        );
+        assert_eq!(split(&["[", "]"]).expect("split"), Vec::<String>::new());
+        assert!(split(&["{}"]).is_err());
+        assert!(split(&["[1, 2"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn stream_elements() {
+        let socket_path = make_socket_path_test("json_stream", "stream_elements");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n7\r\n[{\"id\":\r\na\r\n 1}, {\"id\"\r\n5\r\n: 2}]\r\n0\r\n\r\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        for _ in 0..2 {
+            let (status_code, elements) = client
+                .send_request_json_stream::<(), Value, Value>("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request_json_stream");
+            let elements: Vec<Value> = elements
+                .map(|element| element.expect("element"))
+                .collect()
+                .await;
+
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(elements, vec![json!({"id": 1}), json!({"id": 2})]);
+        }
+    }
+}
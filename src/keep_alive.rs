@@ -0,0 +1,103 @@
+use crate::Body;
+use crate::{Clock, builder::ClientConfig, pool::Pool};
+use hyper::{Method, Request};
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+/// Request sent on the idle connections, set with [crate::ClientUnixBuilder::keep_alive_ping].
+#[derive(Debug, Clone)]
+pub(crate) struct KeepAlive {
+    pub(crate) interval: Duration,
+    pub(crate) method: Method,
+    pub(crate) endpoint: String,
+}
+
+impl KeepAlive {
+    pub(crate) fn request(&self, config: &ClientConfig) -> Request<Body> {
+        let mut request = Request::new(Body::empty());
+        *request.method_mut() = self.method.clone();
+        *request.version_mut() = config.http_version;
+        let uri = match config.absolute_form {
+            true => config.request_defaults.uri(&self.endpoint),
+            false => self.endpoint.clone(),
+        };
+        if let Ok(uri) = uri.parse() {
+            *request.uri_mut() = uri;
+        }
+        if let Some(host) = config.request_defaults.host() {
+            request
+                .headers_mut()
+                .insert(hyper::header::HOST, host.clone());
+        }
+        request
+    }
+}
+
+/// Ping the idle connections of `pools` every interval, until they are dropped with the client and all its clones.
+pub(crate) fn spawn(keep_alive: KeepAlive, clock: Arc<dyn Clock>, pools: Vec<Weak<Pool>>) {
+    tokio::task::spawn(async move {
+        loop {
+            clock.sleep(keep_alive.interval).await;
+            let pools: Vec<Arc<Pool>> = pools.iter().filter_map(Weak::upgrade).collect();
+            if pools.is_empty() {
+                return;
+            }
+            for pool in pools {
+                pool.keep_alive(&keep_alive).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Method,
+        test_helpers::{raw_server::RawServer, util::make_socket_path_test},
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn ping_idle_connections() {
+        let socket_path = make_socket_path_test("keep_alive", "ping_idle_connections");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+        let server = RawServer::try_new_with(&socket_path, false, move |request| {
+            let line = request
+                .split(|byte| *byte == b'\r')
+                .next()
+                .unwrap_or_default();
+            received.lock().unwrap().push(line.to_vec());
+            b"HTTP/1.1 204 No Content\r\n\r\n".to_vec()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .keep_alive_ping(Duration::from_millis(20), Method::HEAD, "/_ping")
+            .absolute_form(false)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        let pings = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|line| line.as_slice() == b"HEAD /_ping HTTP/1.1")
+            .count();
+        assert!(pings >= 2, "{} pings", pings);
+        assert_eq!(server.connections(), 1);
+        assert_eq!(client.stats().requests.get(&Method::HEAD), None);
+    }
+}
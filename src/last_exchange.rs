@@ -0,0 +1,158 @@
+use crate::{Error, headers::redact};
+use hyper::{
+    HeaderMap, Method, Request, StatusCode, Uri, body::Bytes, header::HeaderName,
+    http::response::Parts,
+};
+use std::sync::{
+    Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Most recent request of a client with its response, returned by [crate::ClientUnix::last_exchange].
+///
+/// The values of the `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers, and of those given to [crate::ClientUnixBuilder::redact_header], are redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastExchange {
+    pub method: Method,
+    pub uri: Uri,
+    pub request_headers: HeaderMap,
+    /// [None] until the response head is received, or when the request failed without a response.
+    pub status: Option<StatusCode>,
+    pub response_headers: HeaderMap,
+    /// Start of the response body, empty until it is read. Streamed response bodies are not captured.
+    pub response_body: Bytes,
+    /// The response body was longer than the size given to [crate::ClientUnixBuilder::capture_last_exchange].
+    pub response_body_truncated: bool,
+    /// Error which ended the exchange.
+    pub error: Option<String>,
+}
+
+/// Id of a captured exchange, kept in the response extensions until the response body is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExchangeId(u64);
+
+/// Last exchange shared by a client and all its clones, an exchange is only updated while it is the most recent one.
+#[derive(Debug)]
+pub(crate) struct LastExchangeRecorder {
+    max_body_size: usize,
+    redacted_headers: Vec<HeaderName>,
+    next_id: AtomicU64,
+    last: Mutex<Option<(ExchangeId, LastExchange)>>,
+}
+
+impl LastExchangeRecorder {
+    pub(crate) fn new(max_body_size: usize, redacted_headers: Vec<HeaderName>) -> Self {
+        LastExchangeRecorder {
+            max_body_size,
+            redacted_headers,
+            next_id: AtomicU64::new(0),
+            last: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn request<B>(&self, request: &Request<B>) -> ExchangeId {
+        let id = ExchangeId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let exchange = LastExchange {
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+            request_headers: redact(request.headers(), &self.redacted_headers),
+            status: None,
+            response_headers: HeaderMap::new(),
+            response_body: Bytes::new(),
+            response_body_truncated: false,
+            error: None,
+        };
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some((id, exchange));
+        }
+        id
+    }
+
+    pub(crate) fn response(&self, id: ExchangeId, parts: &Parts) {
+        self.update(id, |exchange| {
+            exchange.status = Some(parts.status);
+            exchange.response_headers = redact(&parts.headers, &self.redacted_headers);
+        });
+    }
+
+    pub(crate) fn body(&self, id: ExchangeId, body: &Bytes) {
+        self.update(id, |exchange| {
+            exchange.response_body = body.slice(..body.len().min(self.max_body_size));
+            exchange.response_body_truncated = body.len() > self.max_body_size;
+        });
+    }
+
+    pub(crate) fn error(&self, id: ExchangeId, error: &Error) {
+        self.update(id, |exchange| exchange.error = Some(error.to_string()));
+    }
+
+    pub(crate) fn last(&self) -> Option<LastExchange> {
+        let last = self.last.lock().ok()?;
+        last.as_ref().map(|(_, exchange)| exchange.clone())
+    }
+
+    fn update(&self, id: ExchangeId, update: impl FnOnce(&mut LastExchange)) {
+        if let Ok(mut last) = self.last.lock()
+            && let Some((_, exchange)) = last.as_mut().filter(|(last_id, _)| *last_id == id)
+        {
+            update(exchange);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, ErrorAndResponse,
+        test_helpers::{server::Server, util::make_socket_path_test},
+    };
+
+    #[tokio::test]
+    async fn last_exchange() {
+        let socket_path = make_socket_path_test("last_exchange", "last_exchange");
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .capture_last_exchange(4)
+            .redact_header(HeaderName::from_static("x-token"))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        assert_eq!(client.last_exchange(), None);
+
+        client
+            .send_request(
+                "/nolanv",
+                Method::GET,
+                &[("Authorization", "Bearer nolanv"), ("X-Token", "nolanv")],
+                None,
+            )
+            .await
+            .expect("client.send_request");
+        let exchange = client.last_exchange().expect("client.last_exchange");
+        assert_eq!(exchange.method, Method::GET);
+        assert_eq!(exchange.uri.path(), "/nolanv");
+        assert_eq!(exchange.request_headers["authorization"], "<redacted>");
+        assert_eq!(exchange.request_headers["x-token"], "<redacted>");
+        assert_eq!(exchange.status, Some(StatusCode::OK));
+        assert_eq!(exchange.response_body, Bytes::from_static(b"Hell"));
+        assert!(exchange.response_body_truncated);
+        assert_eq!(exchange.error, None);
+
+        let result = client
+            .send_request("/nolanv/nope", Method::GET, &[], None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponse::ResponseUnsuccessful(
+                StatusCode::NOT_FOUND,
+                _
+            ))
+        ));
+        let exchange = client.last_exchange().expect("client.last_exchange");
+        assert_eq!(exchange.uri.path(), "/nolanv/nope");
+        assert_eq!(exchange.status, Some(StatusCode::NOT_FOUND));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{ClientUnix, Error};
+use std::sync::Arc;
+
+impl ClientUnix {
+    /// Run `f` with a client sending all its requests on a single pooled connection, given back to the pool once the future of `f` completes.
+    ///
+    /// The requests of the leased client and its clones are sent in turn on the same connection, so a sequence of requests (e.g. a login then stateful follow-ups) reaches the same server worker. The connection counts against [crate::ClientUnixBuilder::max_connections] for the whole lease, and the response cache and request coalescing are skipped. When the server closes the connection during the lease, e.g. with a `Connection: close` response, the next request opens a new one. Calling it on an already leased client reuses the same connection.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn login_then_query() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let result = client
+    ///         .with_connection(|client| async move {
+    ///             client.send_request("/login", Method::POST, &[], None).await?;
+    ///             client.send_request("/session", Method::GET, &[], None).await
+    ///         })
+    ///         .await
+    ///         .expect("ClientUnix::with_connection");
+    ///     println!("{:?}", result.map(|(status_code, _)| status_code));
+    /// }
+    /// ```
+    pub async fn with_connection<T, Fut>(
+        &self,
+        f: impl FnOnce(ClientUnix) -> Fut,
+    ) -> Result<T, Error>
+    where
+        Fut: Future<Output = T>,
+    {
+        if self.lease.is_some() {
+            return Ok(f(self.clone()).await);
+        }
+        let lease = self.select_pool().lease(self.priority).await?;
+        let mut client = self.clone();
+        client.lease = Some(Arc::new(lease));
+        Ok(f(client).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Method,
+        test_helpers::{raw_server::RawServer, util::make_socket_path_test},
+    };
+
+    #[tokio::test]
+    async fn lease_connection() {
+        let socket_path = make_socket_path_test("lease", "lease_connection");
+        let server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(2)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let outside = client.clone();
+        client
+            .with_connection(|client| async move {
+                for _ in 0..3 {
+                    let (status_code, _) = client
+                        .send_request("/nolanv", Method::GET, &[], None)
+                        .await
+                        .expect("ClientUnix::send_request");
+                    assert_eq!(status_code, 200);
+                }
+                assert_eq!(client.stats().pool.in_use, 1);
+                assert_eq!(server.connections(), 1);
+
+                // Other requests can't use the leased connection.
+                outside
+                    .send_request("/nolanv", Method::GET, &[], None)
+                    .await
+                    .expect("ClientUnix::send_request");
+                assert_eq!(server.connections(), 2);
+            })
+            .await
+            .expect("ClientUnix::with_connection");
+
+        let stats = client.stats().pool;
+        assert_eq!((stats.in_use, stats.idle), (0, 2));
+    }
+}
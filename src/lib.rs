@@ -11,7 +11,7 @@
 //! use http_client_unix_domain_socket::{ClientUnix, Method, StatusCode, ErrorAndResponse};
 //!
 //! pub async fn get_hello_world() {
-//!     let mut client = ClientUnix::try_new("/tmp/unix.socket")
+//!     let client = ClientUnix::try_new("/tmp/unix.socket")
 //!         .await
 //!         .expect("ClientUnix::try_new");
 //!
@@ -54,7 +54,7 @@
 //! }
 //!
 //! pub async fn post_hello_world() {
-//!     let mut client = ClientUnix::try_new("/tmp/unix.socket")
+//!     let client = ClientUnix::try_new("/tmp/unix.socket")
 //!         .await
 //!         .expect("ClientUnix::try_new");
 //!
@@ -82,16 +82,154 @@
 //! ```
 //! ## Feature flags
 //! - `json`(default): Add `send_request_json` which enable automatic parsing of request/response body with `serde_json` and add `Content-Type` header.
+//! - `codec`: Add `send_request_with_codec` which serialize the request/response bodies with a custom `Codec` (bincode, a proprietary format, ...) over `serde`. Enabled by `json`.
+//! - `prometheus`: Add `get_metrics` which scrape a metrics endpoint and parse the Prometheus text exposition format.
+//! - `simd-json`: Deserialize the responses of `send_request_json` with `simd-json`, faster on very large documents. Enable `json`.
+//! - `path-to-error`: Add the path of the invalid field (`items[3].name`) to the JSON response parsing errors, with `serde_path_to_error`. Enable `json`.
+//! - `opentelemetry`: Inject the current OpenTelemetry context in every request (`traceparent`/`tracestate` headers with the W3C propagator) using the global text map propagator, so traces continue into the server. Headers given by the caller are kept.
+//! - `debug-wire`: Add `ClientUnixBuilder::debug_wire` which log the request and response heads, with optional body snippets and redaction of the secret headers, with the `log` crate.
+//! - `server`: Add `ServerUnix` which serve a tower service (like an `axum::Router`) on a unix socket, removing stale socket files and setting the socket permissions, and `ClientUnix::builder_in_process` which connect the client to a tower service through in-process pipes, without socket files.
+//! - `cli`: Build the `uds-http` binary, a small curl for unix sockets sending requests with headers and raw or JSON bodies, and pretty-printing the JSON responses. Enable `json`.
+//! - `openapi`: Add `OpenApiGenerator` which generate, usually from a build script, a typed client over `ClientUnix` with a method per operation of an OpenAPI 3 or Swagger 2 spec in JSON (like the Docker and Podman ones). Enable `json`.
+//! - `jsonschema`: Add `send_request_json_validated` which validate the JSON responses against a `ResponseSchema` before deserializing them, returning every violation. Enable `json`.
+//! - `typed-headers`: Add `TypedHeaders` which build the request headers from the typed headers of the `headers` crate (`Authorization`, `IfMatch`, custom ones...), and `Response::typed_header` which extract them from the responses. Use version 0.4 of the `headers` crate.
 
+mod balancing;
+mod batch;
+mod body;
+mod body_rewrite;
+mod builder;
+mod cache;
+#[cfg(feature = "json")]
+mod cassette;
 mod client;
+mod clock;
+mod coalesce;
+#[cfg(feature = "codec")]
+mod codec;
+mod conditional;
+mod connection;
+mod curl;
+#[cfg(feature = "debug-wire")]
+mod debug_wire;
+mod download;
+mod endpoint;
 mod error;
+#[cfg(feature = "json")]
+mod error_mapping;
+mod fault;
+#[cfg(feature = "json")]
+mod har;
+mod headers;
+mod histogram;
+mod idempotency;
+mod idle_reaper;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+mod json_stream;
+mod keep_alive;
+mod last_exchange;
+mod lease;
+mod long_poll;
+mod multipart;
+#[cfg(feature = "openapi")]
+mod openapi;
+mod pool;
+mod priority;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+mod queue;
+mod rate_limit;
+mod raw;
+mod registry;
+mod request_defaults;
+mod request_id;
+mod response;
+mod retry;
+#[cfg(feature = "jsonschema")]
+mod schema;
+#[cfg(feature = "server")]
+mod server;
+mod signing;
+mod socket_path;
+mod stats;
+mod status;
+mod stdcopy;
+mod stream;
 #[cfg(test)]
 pub mod test_helpers;
+// Let the generated OpenAPI client fixture name the crate as its users do.
+#[cfg(all(test, feature = "openapi"))]
+extern crate self as http_client_unix_domain_socket;
+mod text;
+mod throttle;
+mod timing;
+#[cfg(feature = "opentelemetry")]
+mod trace_context;
+#[cfg(feature = "typed-headers")]
+mod typed_headers;
+mod upgrade;
+mod verbs;
+mod wire;
 
-pub use axum_core::body::Body;
+pub use balancing::LoadBalancing;
+pub use batch::RequestDescription;
+pub use body::Body;
+pub use builder::ClientUnixBuilder;
+pub use bytes::BytesMut;
+#[cfg(feature = "json")]
+pub use cassette::Cassette;
 pub use client::ClientUnix;
+pub use clock::{Clock, TokioClock};
+#[cfg(feature = "codec")]
+pub use codec::Codec;
+#[cfg(feature = "json")]
+pub use codec::JsonCodec;
+pub use conditional::Conditional;
+#[cfg(feature = "debug-wire")]
+pub use debug_wire::DebugWire;
+pub use endpoint::{join_path, path_segment};
 #[cfg(feature = "json")]
 pub use error::ErrorAndResponseJson;
-pub use error::{Error, ErrorAndResponse};
+#[cfg(feature = "codec")]
+pub use error::ErrorAndResponseTyped;
+pub use error::{Error, ErrorAndResponse, ErrorKind};
+#[cfg(feature = "json")]
+pub use error_mapping::ErrorMapping;
+pub use fault::FaultInjection;
+#[cfg(feature = "json")]
+pub use har::HarRecorder;
+pub use headers::OriginalHeaders;
+pub use histogram::LatencyHistogram;
 pub use hyper::Method;
 pub use hyper::StatusCode;
+pub use hyper::Version;
+pub use hyper::upgrade::Upgraded;
+pub use hyper_util::rt::TokioIo;
+#[cfg(feature = "json")]
+pub use json::BorrowedJson;
+pub use last_exchange::LastExchange;
+pub use multipart::Part;
+#[cfg(feature = "openapi")]
+pub use openapi::{OpenApiError, OpenApiGenerator};
+pub use priority::Priority;
+#[cfg(feature = "prometheus")]
+pub use prometheus::{MetricFamily, MetricType, MetricsParseError, Sample, parse_metrics};
+pub use registry::ClientRegistry;
+pub use request_id::RequestId;
+pub use response::Response;
+pub use retry::RetryPolicy;
+#[cfg(feature = "jsonschema")]
+pub use schema::{ResponseSchema, SchemaViolation};
+#[cfg(feature = "json")]
+pub use serde_json::value::RawValue;
+#[cfg(feature = "server")]
+pub use server::{ServerError, ServerUnix, ServerUnixBuilder};
+pub use stats::{ClientStats, PoolStats, ResponseCounts};
+pub use status::{StatusClass, StatusResultExt};
+pub use stdcopy::LogChunk;
+pub use timing::Timing;
+#[cfg(feature = "typed-headers")]
+pub use typed_headers::TypedHeaders;
+pub use wire::WireBytes;
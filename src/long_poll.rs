@@ -0,0 +1,154 @@
+use crate::{ClientUnix, Error, endpoint::percent_encode, error::ErrorAndResponse};
+use futures_util::{Stream, stream};
+use hyper::{Method, StatusCode};
+
+/// State carried between the requests of [ClientUnix::long_poll].
+struct LongPollState {
+    client: ClientUnix,
+    endpoint: String,
+    index_param: String,
+    index_header: String,
+    index: Option<String>,
+}
+
+impl LongPollState {
+    /// Endpoint with the last seen index added to the query.
+    fn endpoint(&self) -> String {
+        let Some(index) = &self.index else {
+            return self.endpoint.clone();
+        };
+        let separator = match self.endpoint.contains('?') {
+            true => '&',
+            false => '?',
+        };
+        format!(
+            "{}{}{}={}",
+            self.endpoint,
+            separator,
+            self.index_param,
+            percent_encode(index)
+        )
+    }
+
+    async fn next(&mut self) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        loop {
+            let endpoint = self.endpoint();
+            match self.client.send(&endpoint, Method::GET, &[], None).await {
+                Ok((parts, body_response)) if parts.status.is_success() => {
+                    if let Some(index) = parts
+                        .headers
+                        .get(self.index_header.as_str())
+                        .and_then(|index| index.to_str().ok())
+                    {
+                        self.index = Some(index.to_string());
+                    }
+                    return Ok((parts.status, body_response.into()));
+                }
+                Ok((parts, body_response)) => {
+                    return Err(self.client.unsuccessful(&parts, body_response));
+                }
+                // The server didn't answer within the read timeout, ask again.
+                Err(e) if matches!(e.inner(), Error::StreamTimeout(_)) => {}
+                Err(e) if matches!(e.inner(), Error::ConnectionLost(_)) => {
+                    if let Err(e) = self.client.clone().try_reconnect().await {
+                        return Err(ErrorAndResponse::InternalError(e));
+                    }
+                }
+                Err(e) => return Err(ErrorAndResponse::InternalError(e)),
+            }
+        }
+    }
+}
+
+impl ClientUnix {
+    /// Repeatedly send a long-poll HTTP GET request, yielding each response.
+    ///
+    /// The value of the `index_header` response header is sent back in the `index_param` query parameter of the next request, so the server only answers once its state changed (the blocking queries of Consul-like agents). Requests interrupted by the [crate::ClientUnixBuilder::read_timeout] are sent again and lost connections are reconnected transparently. Other errors are yielded, polling the stream again sends a new request.
+    /// # Example
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn watch_services() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let mut updates = std::pin::pin!(client.long_poll(
+    ///         "/v1/catalog/services?wait=5m",
+    ///         "index",
+    ///         "X-Consul-Index"
+    ///     ));
+    ///     while let Some(Ok((_, services))) = updates.next().await {
+    ///         println!("{}", String::from_utf8_lossy(&services));
+    ///     }
+    /// }
+    /// ```
+    pub fn long_poll(
+        &self,
+        endpoint: &str,
+        index_param: &str,
+        index_header: &str,
+    ) -> impl Stream<Item = Result<(StatusCode, Vec<u8>), ErrorAndResponse>> + Send + 'static {
+        let state = LongPollState {
+            client: self.clone(),
+            endpoint: endpoint.to_string(),
+            index_param: index_param.to_string(),
+            index_header: index_header.to_string(),
+            index: None,
+        };
+        stream::unfold(state, |mut state| async move {
+            let item = state.next().await;
+            Some((item, state))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn encode_index() {
+        assert_eq!(percent_encode("42"), "42");
+        assert_eq!(percent_encode("a b&c"), "a%20b%26c");
+    }
+
+    #[tokio::test]
+    async fn follow_index() {
+        let socket_path = make_socket_path_test("long_poll", "follow_index");
+        // Closing the connection after each response forces a reconnection between polls.
+        let _server = RawServer::try_new_with(&socket_path, true, |request| {
+            let request = String::from_utf8_lossy(request);
+            let index: u32 = request
+                .split_once("index=")
+                .and_then(|(_, index)| index.split(' ').next()?.parse().ok())
+                .unwrap_or(0);
+            format!(
+                "HTTP/1.1 200 OK\r\nx-index: {}\r\ncontent-length: 1\r\n\r\n{}",
+                index + 1,
+                index
+            )
+            .into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let responses: Vec<_> = client
+            .long_poll("/watch?wait=1s", "index", "X-Index")
+            .take(3)
+            .collect()
+            .await;
+
+        let bodies: Vec<Vec<u8>> = responses
+            .into_iter()
+            .map(|response| response.expect("long_poll").1)
+            .collect();
+        assert_eq!(bodies, vec![b"0".to_vec(), b"1".to_vec(), b"2".to_vec()]);
+    }
+}
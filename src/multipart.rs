@@ -0,0 +1,270 @@
+use crate::Body;
+use crate::{
+    ClientUnix, Error, OriginalHeaders, body::ResponseBody, error::ErrorAndResponse,
+    text::content_type_parameter,
+};
+use futures_util::{Stream, stream};
+use hyper::{Method, StatusCode, http::Extensions};
+
+/// Part of a `multipart/*` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub headers: OriginalHeaders,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Preamble,
+    AfterDelimiter,
+    InPart,
+    Epilogue,
+}
+
+/// Incremental parser of a multipart body (RFC 2046), fed with chunks of the body.
+#[derive(Debug)]
+struct MultipartParser {
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+    state: State,
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+impl MultipartParser {
+    fn new(boundary: &str) -> Self {
+        MultipartParser {
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+            // The first delimiter is not preceded by a line break when there is no preamble.
+            buffer: b"\r\n".to_vec(),
+            state: State::Preamble,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        if self.state != State::Epilogue {
+            self.buffer.extend_from_slice(bytes);
+        }
+    }
+
+    /// Next complete part, [None] when more bytes are needed.
+    fn next_part(&mut self) -> Result<Option<Part>, Error> {
+        loop {
+            match self.state {
+                State::Preamble => {
+                    let Some(position) = find(&self.buffer, &self.delimiter) else {
+                        let keep = self.buffer.len().min(self.delimiter.len() - 1);
+                        self.buffer.drain(..self.buffer.len() - keep);
+                        return Ok(None);
+                    };
+                    self.buffer.drain(..position + self.delimiter.len());
+                    self.state = State::AfterDelimiter;
+                }
+                State::AfterDelimiter => {
+                    if self.buffer.starts_with(b"--") {
+                        self.buffer.clear();
+                        self.state = State::Epilogue;
+                        return Ok(None);
+                    }
+                    let Some(position) = find(&self.buffer, b"\r\n") else {
+                        return Ok(None);
+                    };
+                    if self.buffer[..position]
+                        .iter()
+                        .any(|byte| !matches!(byte, b' ' | b'\t'))
+                    {
+                        return Err(Error::MultipartParsing("invalid multipart delimiter"));
+                    }
+                    self.buffer.drain(..position + 2);
+                    self.state = State::InPart;
+                }
+                State::InPart => {
+                    let Some(position) = find(&self.buffer, &self.delimiter) else {
+                        return Ok(None);
+                    };
+                    let part = parse_part(&self.buffer[..position])?;
+                    self.buffer.drain(..position + self.delimiter.len());
+                    self.state = State::AfterDelimiter;
+                    return Ok(Some(part));
+                }
+                State::Epilogue => return Ok(None),
+            }
+        }
+    }
+
+    fn finish(&self) -> Result<(), Error> {
+        match self.state {
+            State::Epilogue => Ok(()),
+            _ => Err(Error::MultipartParsing("unexpected end of multipart body")),
+        }
+    }
+}
+
+fn parse_part(part: &[u8]) -> Result<Part, Error> {
+    let mut headers = vec![httparse::EMPTY_HEADER; 100];
+    let Ok(httparse::Status::Complete((length, headers))) =
+        httparse::parse_headers(part, &mut headers)
+    else {
+        return Err(Error::MultipartParsing("invalid multipart part headers"));
+    };
+    Ok(Part {
+        headers: OriginalHeaders::from_httparse(headers),
+        body: part[length..].to_vec(),
+    })
+}
+
+impl ClientUnix {
+    /// Send a raw HTTP request and parse the `multipart/*` response part by part.
+    ///
+    /// The boundary is read from the `Content-Type` response header, each part is yielded with its headers as soon as it is fully received. The connection is released once the stream is fully read, dropping it before closes the connection. Unlike [ClientUnix::send_request], the response is not cached, coalesced nor retried.
+    /// # Example
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_manifests() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, parts) = client
+    ///         .send_request_multipart("/manifests", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_multipart");
+    ///     let mut parts = std::pin::pin!(parts);
+    ///     while let Some(part) = parts.next().await {
+    ///         println!("{:?}", part.expect("part").headers);
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_multipart(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<
+        (
+            StatusCode,
+            impl Stream<Item = Result<Part, Error>> + Send + 'static,
+        ),
+        ErrorAndResponse,
+    > {
+        let (parts, body_response) = self
+            .start(endpoint, method, headers, body_request, &Extensions::new())
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            let body_response = body_response
+                .collect()
+                .await
+                .map_err(ErrorAndResponse::InternalError)?;
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        let boundary = content_type_parameter(&parts.headers, "boundary").ok_or(
+            ErrorAndResponse::InternalError(Error::MultipartParsing("missing multipart boundary")),
+        )?;
+        Ok((
+            parts.status,
+            self::parts(body_response, MultipartParser::new(&boundary)),
+        ))
+    }
+}
+
+fn parts(
+    body_response: ResponseBody,
+    parser: MultipartParser,
+) -> impl Stream<Item = Result<Part, Error>> + Send + 'static {
+    stream::unfold(Some((body_response, parser)), |state| async move {
+        let (mut body_response, mut parser) = state?;
+        loop {
+            match parser.next_part() {
+                Ok(Some(part)) => return Some((Ok(part), Some((body_response, parser)))),
+                Ok(None) => {}
+                Err(e) => return Some((Err(e), None)),
+            }
+            match body_response.data().await {
+                Some(Ok(data)) => parser.push(&data),
+                Some(Err(e)) => return Some((Err(e), None)),
+                None => return parser.finish().err().map(|e| (Err(e), None)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use futures_util::StreamExt;
+
+    fn parse(chunks: &[&[u8]]) -> Result<Vec<Part>, Error> {
+        let mut parser = MultipartParser::new("frontier");
+        let mut parts = Vec::new();
+        for chunk in chunks {
+            parser.push(chunk);
+            while let Some(part) = parser.next_part()? {
+                parts.push(part);
+            }
+        }
+        parser.finish()?;
+        Ok(parts)
+    }
+
+    #[test]
+    fn parse_split_parts() {
+        let parts = parse(&[
+            b"preamble\r\n--fron",
+            b"tier\r\nContent-Type: text/plain\r\n\r\nfirst\r\n--frontier  \r\n",
+            b"\r\nsec",
+            b"ond\r\n--frontier--\r\nepilogue",
+        ])
+        .expect("parse");
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0].headers.get("content-type").map(|v| v.as_bytes()),
+            Some(&b"text/plain"[..])
+        );
+        assert_eq!(parts[0].body, b"first");
+        assert!(parts[1].headers.is_empty());
+        assert_eq!(parts[1].body, b"second");
+
+        assert!(parse(&[b"--frontier\r\n\r\nfirst"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn send_request_multipart() {
+        let socket_path = make_socket_path_test("multipart", "send_request_multipart");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-type: multipart/mixed; boundary=\"frontier\"\r\ncontent-length: 50\r\n\r\n--frontier\r\nX-Nolan: v\r\n\r\nbaguette\r\n--frontier--\r\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        for _ in 0..2 {
+            let (status_code, parts) = client
+                .send_request_multipart("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request_multipart");
+            let parts: Vec<Part> = parts.map(|part| part.expect("part")).collect().await;
+
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(parts.len(), 1);
+            assert_eq!(
+                parts[0].headers.get("x-nolan").map(|v| v.as_bytes()),
+                Some(&b"v"[..])
+            );
+            assert_eq!(parts[0].body, b"baguette");
+        }
+    }
+}
@@ -0,0 +1,900 @@
+use serde_json::{Map, Value};
+use std::{collections::HashSet, fmt::Write};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Headers which can't be described as parameters, see the OpenAPI specification.
+const IGNORED_HEADERS: [&str; 3] = ["accept", "content-type", "authorization"];
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "box", "try",
+];
+
+/// Generate a typed client over [crate::ClientUnix] from an OpenAPI spec, usually in a build script **(feature = openapi)**
+///
+/// OpenAPI 3 and Swagger 2 specs in JSON are supported, like the ones published by Docker and Podman. The generated code has:
+/// - a struct for every object schema of `components/schemas` (or `definitions`), other schemas become type aliases,
+/// - a client struct wrapping a [crate::ClientUnix], with an async method for every operation, named after its `operationId`.
+///
+/// Operations returning JSON are sent with [crate::ClientUnix::send_request_json] and return the typed response, the others with [crate::ClientUnix::send_request] and return the raw body. Inline object schemas, `allOf`, `oneOf` and `anyOf` are typed as `serde_json::Value`. The crate including the generated code must depend on `serde` (with `derive`) and `serde_json`.
+///
+/// # Example
+/// ```rust,no_run
+/// // build.rs
+/// use http_client_unix_domain_socket::OpenApiGenerator;
+///
+/// fn main() {
+///     let spec = std::fs::read_to_string("docker.json").expect("fs::read_to_string");
+///     let code = OpenApiGenerator::new("DockerClient")
+///         .generate(&spec)
+///         .expect("OpenApiGenerator::generate");
+///     let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR");
+///     std::fs::write(format!("{}/docker.rs", out_dir), code).expect("fs::write");
+///     println!("cargo:rerun-if-changed=docker.json");
+/// }
+///
+/// // src/lib.rs
+/// // mod docker {
+/// //     include!(concat!(env!("OUT_DIR"), "/docker.rs"));
+/// // }
+/// // let docker = docker::DockerClient::new(ClientUnix::try_new("/var/run/docker.sock").await?);
+/// // let containers = docker.container_list(Some(true), None, None, None).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApiGenerator {
+    client_name: String,
+}
+
+/// Error returned by [OpenApiGenerator::generate] **(feature = openapi)**
+#[derive(Debug)]
+pub enum OpenApiError {
+    Parse(serde_json::Error),
+    /// The spec doesn't follow the OpenAPI specification, with the reason.
+    Invalid(String),
+}
+
+impl std::fmt::Display for OpenApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OpenApiError::Parse(e) => write!(f, "Failed to parse OpenAPI spec, {}", e),
+            OpenApiError::Invalid(reason) => write!(f, "Invalid OpenAPI spec, {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for OpenApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OpenApiError::Parse(e) => Some(e),
+            OpenApiError::Invalid(_) => None,
+        }
+    }
+}
+
+impl OpenApiGenerator {
+    /// `client_name` is the name of the generated client struct, like `DockerClient`.
+    pub fn new(client_name: impl Into<String>) -> Self {
+        OpenApiGenerator {
+            client_name: client_name.into(),
+        }
+    }
+
+    /// Generate the Rust code of the client for the `spec` in JSON.
+    pub fn generate(&self, spec: &str) -> Result<String, OpenApiError> {
+        let spec: Value = serde_json::from_str(spec).map_err(OpenApiError::Parse)?;
+        if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+            return Err(OpenApiError::Invalid(
+                "missing the `openapi` or `swagger` version".into(),
+            ));
+        }
+        let paths = spec
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| OpenApiError::Invalid("missing the `paths` object".into()))?;
+        let spec = Spec { root: &spec };
+
+        let mut code = String::from(
+            "// Generated by http-client-unix-domain-socket from an OpenAPI spec, do not edit.\n",
+        );
+        let schemas = spec
+            .root
+            .pointer("/components/schemas")
+            .or_else(|| spec.root.get("definitions"))
+            .and_then(Value::as_object);
+        for (name, schema) in schemas.into_iter().flatten() {
+            spec.schema_item(&mut code, name, schema);
+        }
+
+        let client_name = type_name(&self.client_name);
+        let _ = write!(
+            code,
+            "
+/// Client generated from an OpenAPI spec, wrapping a [http_client_unix_domain_socket::ClientUnix].
+#[derive(Debug, Clone)]
+pub struct {client_name} {{
+    client: http_client_unix_domain_socket::ClientUnix,
+}}
+
+#[allow(clippy::too_many_arguments)]
+impl {client_name} {{
+    pub fn new(client: http_client_unix_domain_socket::ClientUnix) -> Self {{
+        {client_name} {{ client }}
+    }}
+
+    pub fn client(&self) -> &http_client_unix_domain_socket::ClientUnix {{
+        &self.client
+    }}
+"
+        );
+        let mut method_names = HashSet::new();
+        for (path, item) in paths {
+            let path_parameters = item.get("parameters").and_then(Value::as_array);
+            for method in METHODS {
+                let Some(operation) = item.get(method).and_then(Value::as_object) else {
+                    continue;
+                };
+                let mut method_name = operation
+                    .get("operationId")
+                    .and_then(Value::as_str)
+                    .map(snake_name)
+                    .unwrap_or_else(|| snake_name(&format!("{} {}", method, path)));
+                while !method_names.insert(method_name.clone()) {
+                    method_name.push('_');
+                }
+                spec.operation(
+                    &mut code,
+                    &method_name,
+                    method,
+                    path,
+                    path_parameters,
+                    operation,
+                );
+            }
+        }
+        code.push_str(HELPERS);
+        code.push_str("}\n");
+        Ok(code)
+    }
+}
+
+/// Private helpers of the generated client.
+const HELPERS: &str = r#"
+    fn encode(value: &str) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    fn endpoint(path: String, query: &[(&str, String)]) -> String {
+        let mut endpoint = path;
+        for (index, (name, value)) in query.iter().enumerate() {
+            endpoint.push(if index == 0 { '?' } else { '&' });
+            endpoint.push_str(&Self::encode(name));
+            endpoint.push('=');
+            endpoint.push_str(&Self::encode(value));
+        }
+        endpoint
+    }
+"#;
+
+/// Parameter of an operation, as an argument of the generated method.
+struct Parameter {
+    name: String,
+    argument: String,
+    location: String,
+    rust_type: String,
+    required: bool,
+    array: bool,
+}
+
+/// Body of an operation.
+enum RequestBody {
+    Json { rust_type: String, required: bool },
+    Raw { required: bool },
+}
+
+struct Spec<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Spec<'a> {
+    /// Follow a local `$ref`.
+    fn resolve(&self, value: &'a Value) -> &'a Value {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .unwrap_or(value),
+            None => value,
+        }
+    }
+
+    fn schema_item(&self, code: &mut String, name: &str, schema: &Value) {
+        let name = type_name(name);
+        let _ = writeln!(code);
+        doc(code, "", schema.get("description"));
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let is_object = schema.get("type").and_then(Value::as_str) == Some("object")
+            || schema.get("type").is_none() && properties.is_some();
+        let Some(properties) = properties.filter(|_| is_object) else {
+            let _ = writeln!(code, "pub type {} = {};", name, rust_type(schema, false));
+            return;
+        };
+
+        let required = required(schema);
+        let _ = writeln!(
+            code,
+            "#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]\npub struct {} {{",
+            name
+        );
+        let mut fields = HashSet::new();
+        for (property, property_schema) in properties {
+            let mut field = snake_name(property);
+            while !fields.insert(field.clone()) {
+                field.push('_');
+            }
+            let mut field_type = rust_type(property_schema, false);
+            if field_type == name {
+                field_type = format!("Box<{}>", field_type);
+            }
+            doc(code, "    ", property_schema.get("description"));
+            let rename = match field.trim_start_matches("r#") == property {
+                true => String::new(),
+                false => format!("rename = {:?}, ", property),
+            };
+            match required.contains(property.as_str()) && !is_nullable(property_schema) {
+                true => {
+                    if !rename.is_empty() {
+                        let _ = writeln!(code, "    #[serde({})]", rename.trim_end_matches(", "));
+                    }
+                    let _ = writeln!(code, "    pub {}: {},", field, field_type);
+                }
+                false => {
+                    let _ = writeln!(
+                        code,
+                        "    #[serde({}default, skip_serializing_if = \"Option::is_none\")]\n    pub {}: Option<{}>,",
+                        rename, field, field_type
+                    );
+                }
+            }
+        }
+        code.push_str("}\n");
+    }
+
+    fn parameters(
+        &self,
+        path_parameters: Option<&'a Vec<Value>>,
+        operation: &'a Map<String, Value>,
+    ) -> (Vec<Parameter>, Option<&'a Value>) {
+        let mut parameters: Vec<Parameter> = Vec::new();
+        let mut body_schema = None;
+        let operation_parameters = operation.get("parameters").and_then(Value::as_array);
+        // Operation parameters override the path item ones with the same name and location.
+        let all_parameters: Vec<&Value> = operation_parameters
+            .into_iter()
+            .flatten()
+            .chain(path_parameters.into_iter().flatten())
+            .map(|parameter| self.resolve(parameter))
+            .collect();
+        let mut arguments = HashSet::from(["body".to_string()]);
+        for parameter in all_parameters {
+            let (Some(name), Some(location)) = (
+                parameter.get("name").and_then(Value::as_str),
+                parameter.get("in").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            match location {
+                "body" => {
+                    body_schema = body_schema.or(parameter.get("schema"));
+                    continue;
+                }
+                "path" | "query" => {}
+                "header" if !IGNORED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) => {}
+                _ => continue,
+            }
+            if parameters
+                .iter()
+                .any(|other| other.name == name && other.location == location)
+            {
+                continue;
+            }
+
+            // Swagger 2 parameters have their type inline, OpenAPI 3 in a schema.
+            let schema = parameter
+                .get("schema")
+                .map(|schema| self.resolve(schema))
+                .unwrap_or(parameter);
+            let array = schema.get("type").and_then(Value::as_str) == Some("array");
+            let item_schema = match array {
+                true => schema
+                    .get("items")
+                    .map(|items| self.resolve(items))
+                    .unwrap_or(&Value::Null),
+                false => schema,
+            };
+            let rust_type = match item_schema.get("type").and_then(Value::as_str) {
+                Some("integer" | "number" | "boolean") => rust_type(item_schema, false),
+                _ => "&str".into(),
+            };
+            let mut argument = snake_name(name);
+            if !arguments.insert(argument.clone()) {
+                argument = format!("{}_{}", argument.trim_start_matches("r#"), location);
+                arguments.insert(argument.clone());
+            }
+            parameters.push(Parameter {
+                name: name.to_string(),
+                argument,
+                location: location.to_string(),
+                rust_type,
+                required: location == "path"
+                    || parameter.get("required").and_then(Value::as_bool) == Some(true),
+                array,
+            });
+        }
+        (parameters, body_schema)
+    }
+
+    fn request_body(
+        &self,
+        operation: &'a Map<String, Value>,
+        body_schema: Option<&'a Value>,
+    ) -> Option<RequestBody> {
+        if let Some(schema) = body_schema {
+            return Some(RequestBody::Json {
+                rust_type: rust_type(schema, false),
+                required: true,
+            });
+        }
+        let request_body = self.resolve(operation.get("requestBody")?);
+        let required = request_body.get("required").and_then(Value::as_bool) == Some(true);
+        let content = request_body.get("content").and_then(Value::as_object)?;
+        Some(match json_content(content) {
+            Some(schema) => RequestBody::Json {
+                rust_type: rust_type(schema, false),
+                required,
+            },
+            None => RequestBody::Raw { required },
+        })
+    }
+
+    /// Type of the JSON body of the first successful response.
+    fn response_type(&self, operation: &Map<String, Value>) -> Option<String> {
+        let responses = operation.get("responses").and_then(Value::as_object)?;
+        let mut successful: Vec<(&String, &Value)> = responses
+            .iter()
+            .filter(|(code, _)| code.starts_with('2'))
+            .collect();
+        successful.sort_by_key(|(code, _)| *code);
+        let response = self.resolve(successful.first()?.1);
+        if let Some(content) = response.get("content").and_then(Value::as_object) {
+            return json_content(content).map(|schema| rust_type(schema, false));
+        }
+
+        let produces = operation
+            .get("produces")
+            .or_else(|| self.root.get("produces"))
+            .and_then(Value::as_array);
+        let produces_json = produces.is_none_or(|produces| {
+            produces
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|media_type| media_type.contains("json"))
+        });
+        let schema = response.get("schema")?;
+        produces_json.then(|| rust_type(schema, false))
+    }
+
+    fn operation(
+        &self,
+        code: &mut String,
+        method_name: &str,
+        method: &str,
+        path: &str,
+        path_parameters: Option<&'a Vec<Value>>,
+        operation: &'a Map<String, Value>,
+    ) {
+        let (parameters, body_schema) = self.parameters(path_parameters, operation);
+        let request_body = self.request_body(operation, body_schema);
+        let response_type = match request_body {
+            Some(RequestBody::Raw { .. }) => None,
+            _ => self.response_type(operation),
+        };
+
+        let _ = writeln!(code);
+        let documented = match operation.get("summary") {
+            Some(summary) => doc(code, "    ", Some(summary)),
+            None => doc(code, "    ", operation.get("description")),
+        };
+        if documented {
+            let _ = writeln!(code, "    ///");
+        }
+        let _ = writeln!(code, "    /// `{} {}`", method.to_uppercase(), path);
+        if operation.get("deprecated").and_then(Value::as_bool) == Some(true) {
+            let _ = writeln!(code, "    #[deprecated]");
+        }
+
+        let mut arguments = String::new();
+        for parameter in &parameters {
+            let rust_type = match parameter.array {
+                true => format!("&[{}]", parameter.rust_type),
+                false => parameter.rust_type.clone(),
+            };
+            let _ = match parameter.required {
+                true => write!(arguments, ", {}: {}", parameter.argument, rust_type),
+                false => write!(arguments, ", {}: Option<{}>", parameter.argument, rust_type),
+            };
+        }
+        match &request_body {
+            Some(RequestBody::Json {
+                rust_type,
+                required: true,
+            }) => {
+                let _ = write!(arguments, ", body: &{}", rust_type);
+            }
+            Some(RequestBody::Json { rust_type, .. }) => {
+                let _ = write!(arguments, ", body: Option<&{}>", rust_type);
+            }
+            Some(RequestBody::Raw { required: true }) => {
+                arguments.push_str(", body: http_client_unix_domain_socket::Body");
+            }
+            Some(RequestBody::Raw { .. }) => {
+                arguments.push_str(", body: Option<http_client_unix_domain_socket::Body>");
+            }
+            None => {}
+        }
+        let returned = match &response_type {
+            Some(rust_type) => format!(
+                "Result<{}, http_client_unix_domain_socket::ErrorAndResponseJson<serde_json::Value>>",
+                rust_type
+            ),
+            None => "Result<Vec<u8>, http_client_unix_domain_socket::ErrorAndResponse>".into(),
+        };
+        let _ = writeln!(
+            code,
+            "    pub async fn {}(&self{}) -> {} {{",
+            method_name, arguments, returned
+        );
+
+        // Endpoint with the path parameters and the query string.
+        let mut path_code = String::new();
+        for (index, segment) in path.split('{').enumerate() {
+            let (name, literal) = match index {
+                0 => (None, segment),
+                _ => match segment.split_once('}') {
+                    Some((name, literal)) => (Some(name), literal),
+                    None => (None, segment),
+                },
+            };
+            let parameter = name.and_then(|name| {
+                parameters
+                    .iter()
+                    .find(|parameter| parameter.location == "path" && parameter.name == name)
+            });
+            match (name, parameter) {
+                (_, Some(parameter)) if parameter.rust_type == "&str" => {
+                    let _ = write!(path_code, "&Self::encode({}), ", parameter.argument);
+                }
+                (_, Some(parameter)) => {
+                    let _ = write!(
+                        path_code,
+                        "&Self::encode(&{}.to_string()), ",
+                        parameter.argument
+                    );
+                }
+                (Some(name), None) => {
+                    let _ = write!(path_code, "{:?}, ", format!("{{{}}}", name));
+                }
+                (None, None) => {}
+            }
+            if !literal.is_empty() {
+                let _ = write!(path_code, "{:?}, ", literal);
+            }
+        }
+        let _ = writeln!(
+            code,
+            "        let path = [{}].concat();",
+            path_code.trim_end_matches(", ")
+        );
+        let has_query = parameters
+            .iter()
+            .any(|parameter| parameter.location == "query");
+        let has_headers = parameters
+            .iter()
+            .any(|parameter| parameter.location == "header");
+        let raw_json_body =
+            response_type.is_none() && matches!(request_body, Some(RequestBody::Json { .. }));
+        match has_query {
+            true => code.push_str("        let mut query: Vec<(&str, String)> = Vec::new();\n"),
+            false => code.push_str("        let query: Vec<(&str, String)> = Vec::new();\n"),
+        }
+        match has_headers || raw_json_body {
+            true => code.push_str("        let mut headers: Vec<(&str, String)> = Vec::new();\n"),
+            false => code.push_str("        let headers: Vec<(&str, String)> = Vec::new();\n"),
+        }
+        for parameter in &parameters {
+            let list = match parameter.location.as_str() {
+                "query" => "query",
+                "header" => "headers",
+                _ => continue,
+            };
+            let push = match parameter.array {
+                true => format!(
+                    "for item in {} {{ {}.push(({:?}, item.to_string())); }}",
+                    parameter.argument, list, parameter.name
+                ),
+                false => format!(
+                    "{}.push(({:?}, {}.to_string()));",
+                    list, parameter.name, parameter.argument
+                ),
+            };
+            let _ = match parameter.required {
+                true => writeln!(code, "        {}", push),
+                false => writeln!(
+                    code,
+                    "        if let Some({}) = {} {{ {} }}",
+                    parameter.argument, parameter.argument, push
+                ),
+            };
+        }
+        code.push_str("        let endpoint = Self::endpoint(path, &query);\n");
+
+        let method = format!(
+            "http_client_unix_domain_socket::Method::{}",
+            method.to_uppercase()
+        );
+        let body = match &request_body {
+            Some(RequestBody::Json { required: true, .. }) => "Some(body)",
+            Some(RequestBody::Raw { required: true }) => "Some(body)",
+            Some(_) => "body",
+            None => "None",
+        };
+        match (&response_type, &request_body) {
+            (Some(rust_type), _) => {
+                let in_type = match &request_body {
+                    Some(RequestBody::Json { rust_type, .. }) => rust_type.as_str(),
+                    _ => "()",
+                };
+                code.push_str("        let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();\n");
+                let _ = writeln!(
+                    code,
+                    "        self.client\n            .send_request_json::<{}, {}, serde_json::Value>(&endpoint, {}, &headers, {})\n            .await\n            .map(|(_, response)| response)",
+                    in_type, rust_type, method, body
+                );
+            }
+            (None, Some(RequestBody::Json { required, .. })) => {
+                let encode = "Some(http_client_unix_domain_socket::Body::from(\n            serde_json::to_vec(body).map_err(|e| {\n                http_client_unix_domain_socket::ErrorAndResponse::InternalError(\n                    http_client_unix_domain_socket::Error::RequestParsing(e),\n                )\n            })?,\n        ))";
+                let _ = match required {
+                    true => writeln!(code, "        let body = {};", encode),
+                    false => writeln!(
+                        code,
+                        "        let body = match body {{\n            Some(body) => {},\n            None => None,\n        }};",
+                        encode
+                    ),
+                };
+                code.push_str(
+                    "        headers.push((\"Content-Type\", \"application/json\".to_string()));\n",
+                );
+                code.push_str("        let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();\n");
+                let _ = writeln!(
+                    code,
+                    "        self.client\n            .send_request(&endpoint, {}, &headers, body)\n            .await\n            .map(|(_, response)| response)",
+                    method
+                );
+            }
+            (None, _) => {
+                code.push_str("        let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();\n");
+                let _ = writeln!(
+                    code,
+                    "        self.client\n            .send_request(&endpoint, {}, &headers, {})\n            .await\n            .map(|(_, response)| response)",
+                    method, body
+                );
+            }
+        }
+        code.push_str("    }\n");
+    }
+}
+
+/// Schema of the JSON media type of a `content` object.
+fn json_content(content: &Map<String, Value>) -> Option<&Value> {
+    content
+        .iter()
+        .find(|(media_type, _)| media_type.contains("json"))
+        .and_then(|(_, media_type)| media_type.get("schema"))
+}
+
+fn required(schema: &Value) -> HashSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    schema.get("nullable").and_then(Value::as_bool) == Some(true)
+        || schema.get("x-nullable").and_then(Value::as_bool) == Some(true)
+}
+
+/// Rust type of a schema, references are kept as the name of the referenced type.
+fn rust_type(schema: &Value, nested: bool) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return match reference.rsplit_once('/') {
+            Some((_, name)) => type_name(&name.replace("~1", "/").replace("~0", "~")),
+            None => "serde_json::Value".into(),
+        };
+    }
+    let rust_type = match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "String".into(),
+        Some("integer") => match schema.get("format").and_then(Value::as_str) {
+            Some("int32") => "i32".into(),
+            Some("uint32") => "u32".into(),
+            Some("uint64") => "u64".into(),
+            _ => "i64".into(),
+        },
+        Some("number") => match schema.get("format").and_then(Value::as_str) {
+            Some("float") => "f32".into(),
+            _ => "f64".into(),
+        },
+        Some("boolean") => "bool".into(),
+        Some("array") => format!(
+            "Vec<{}>",
+            schema
+                .get("items")
+                .map(|items| rust_type(items, true))
+                .unwrap_or_else(|| "serde_json::Value".into())
+        ),
+        Some("object") | None
+            if schema.get("properties").is_none()
+                && schema
+                    .get("additionalProperties")
+                    .is_some_and(Value::is_object) =>
+        {
+            format!(
+                "std::collections::HashMap<String, {}>",
+                rust_type(&schema["additionalProperties"], true)
+            )
+        }
+        _ => "serde_json::Value".into(),
+    };
+    match nested && is_nullable(schema) {
+        true => format!("Option<{}>", rust_type),
+        false => rust_type,
+    }
+}
+
+/// Write the description as a doc comment, returns whether it was written.
+fn doc(code: &mut String, indent: &str, description: Option<&Value>) -> bool {
+    let Some(description) = description.and_then(Value::as_str) else {
+        return false;
+    };
+    for line in description.trim().lines() {
+        let line = line.trim_end();
+        let _ = match line.is_empty() {
+            true => writeln!(code, "{}///", indent),
+            false => writeln!(code, "{}/// {}", indent, line),
+        };
+    }
+    true
+}
+
+/// `PascalCase` name of a type.
+fn type_name(name: &str) -> String {
+    let mut type_name: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    if type_name.is_empty() || type_name.starts_with(|c: char| c.is_ascii_digit()) {
+        type_name.insert(0, 'T');
+    }
+    match type_name.as_str() {
+        "Self" => "Self_".into(),
+        _ => type_name,
+    }
+}
+
+/// `snake_case` name of a field, an argument or a method, raw identifier for keywords.
+fn snake_name(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake_name = String::with_capacity(name.len() + 4);
+    for (index, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            snake_name.push('_');
+            continue;
+        }
+        if c.is_ascii_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_lowercase = chars.get(index + 1).is_some_and(char::is_ascii_lowercase);
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lowercase)
+            {
+                snake_name.push('_');
+            }
+        }
+        snake_name.push(c.to_ascii_lowercase());
+    }
+
+    let mut snake_name = snake_name
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if snake_name.is_empty() || snake_name.starts_with(|c: char| c.is_ascii_digit()) {
+        snake_name.insert(0, '_');
+    }
+    match snake_name.as_str() {
+        "self" | "super" | "crate" => format!("{}_", snake_name),
+        name if KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ => snake_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix,
+        test_helpers::{raw_server::RawServer, util::make_socket_path_test},
+    };
+
+    /// Client generated from [DOCKER_SPEC], checked in to compile it with the tests.
+    #[allow(dead_code)]
+    mod docker {
+        include!("openapi/docker_client.rs");
+    }
+
+    const DOCKER_SPEC: &str = r##"{
+        "swagger": "2.0",
+        "produces": ["application/json"],
+        "definitions": {
+            "Container": {
+                "type": "object",
+                "required": ["Id"],
+                "properties": {
+                    "Id": {"type": "string", "description": "The ID of the container"},
+                    "Names": {"type": "array", "items": {"type": "string"}},
+                    "Labels": {"type": "object", "additionalProperties": {"type": "string"}},
+                    "type": {"type": "integer", "format": "int32"}
+                }
+            },
+            "Names": {"type": "array", "items": {"$ref": "#/definitions/Container"}}
+        },
+        "paths": {
+            "/containers/{id}/json": {
+                "get": {
+                    "operationId": "ContainerInspect",
+                    "summary": "Inspect a container",
+                    "parameters": [
+                        {"name": "id", "in": "path", "required": true, "type": "string"},
+                        {"name": "size", "in": "query", "type": "boolean"}
+                    ],
+                    "responses": {"200": {"schema": {"$ref": "#/definitions/Container"}}}
+                }
+            },
+            "/containers/{id}/start": {
+                "post": {
+                    "operationId": "ContainerStart",
+                    "parameters": [{"name": "id", "in": "path", "type": "string"}],
+                    "responses": {"204": {"description": "no error"}}
+                }
+            }
+        }
+    }"##;
+
+    #[test]
+    fn names() {
+        assert_eq!(snake_name("ContainerList"), "container_list");
+        assert_eq!(snake_name("ContainerJSONBase"), "container_json_base");
+        assert_eq!(snake_name("HostConfig.Memory"), "host_config_memory");
+        assert_eq!(snake_name("type"), "r#type");
+        assert_eq!(snake_name("self"), "self_");
+        assert_eq!(snake_name("2fa"), "_2fa");
+        assert_eq!(type_name("io.k8s.Pod"), "IoK8sPod");
+        assert_eq!(type_name("containerSummary"), "ContainerSummary");
+    }
+
+    #[test]
+    fn generate_swagger_2() {
+        let code = OpenApiGenerator::new("DockerClient")
+            .generate(DOCKER_SPEC)
+            .expect("OpenApiGenerator::generate");
+        for expected in [
+            "pub struct Container {",
+            "    /// The ID of the container\n    #[serde(rename = \"Id\")]\n    pub id: String,",
+            "    pub names: Option<Vec<String>>,",
+            "    pub labels: Option<std::collections::HashMap<String, String>>,",
+            "    pub r#type: Option<i32>,",
+            "pub type Names = Vec<Container>;",
+            "pub struct DockerClient {",
+            "    pub async fn container_inspect(&self, id: &str, size: Option<bool>) -> Result<Container, http_client_unix_domain_socket::ErrorAndResponseJson<serde_json::Value>> {",
+            "        let path = [\"/containers/\", &Self::encode(id), \"/json\"].concat();",
+            "        if let Some(size) = size { query.push((\"size\", size.to_string())); }",
+            "    pub async fn container_start(&self, id: &str) -> Result<Vec<u8>, http_client_unix_domain_socket::ErrorAndResponse> {",
+        ] {
+            assert!(code.contains(expected), "{} not in\n{}", expected, code);
+        }
+    }
+
+    #[test]
+    fn generated_client_up_to_date() {
+        let code = OpenApiGenerator::new("DockerClient")
+            .generate(DOCKER_SPEC)
+            .expect("OpenApiGenerator::generate");
+        assert_eq!(code, include_str!("openapi/docker_client.rs"));
+    }
+
+    #[tokio::test]
+    async fn generated_client_encoding() {
+        let socket_path = make_socket_path_test("openapi", "generated_client_encoding");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            let request = String::from_utf8_lossy(request);
+            if request.starts_with("GET http://unix.socket/containers/a%20b%2Fc%3F/json?size=true HTTP/1.1\r\n") {
+                b"HTTP/1.1 200 OK\r\ncontent-length: 30\r\n\r\n{\"Id\":\"nolanv\",\"Names\":[\"/a\"]}"
+                    .to_vec()
+            } else if request.starts_with("POST http://unix.socket/containers/%C3%A9t%C3%A9/start HTTP/1.1\r\n") {
+                b"HTTP/1.1 204 No Content\r\n\r\n".to_vec()
+            } else {
+                b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\n\r\n".to_vec()
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = docker::DockerClient::new(
+            ClientUnix::try_new(&socket_path)
+                .await
+                .expect("ClientUnix::try_new"),
+        );
+
+        let container = client
+            .container_inspect("a b/c?", Some(true))
+            .await
+            .expect("DockerClient::container_inspect");
+        assert_eq!(
+            container,
+            docker::Container {
+                id: "nolanv".into(),
+                names: Some(vec!["/a".into()]),
+                ..Default::default()
+            }
+        );
+        client
+            .container_start("été")
+            .await
+            .expect("DockerClient::container_start");
+    }
+
+    #[test]
+    fn invalid_spec() {
+        let generator = OpenApiGenerator::new("Client");
+        assert!(matches!(
+            generator.generate("{"),
+            Err(OpenApiError::Parse(_))
+        ));
+        assert!(matches!(
+            generator.generate(r#"{"openapi": "3.0.0"}"#),
+            Err(OpenApiError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+// Generated by http-client-unix-domain-socket from an OpenAPI spec, do not edit.
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Container {
+    /// The ID of the container
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "Labels", default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<std::collections::HashMap<String, String>>,
+    #[serde(rename = "Names", default, skip_serializing_if = "Option::is_none")]
+    pub names: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<i32>,
+}
+
+pub type Names = Vec<Container>;
+
+/// Client generated from an OpenAPI spec, wrapping a [http_client_unix_domain_socket::ClientUnix].
+#[derive(Debug, Clone)]
+pub struct DockerClient {
+    client: http_client_unix_domain_socket::ClientUnix,
+}
+
+#[allow(clippy::too_many_arguments)]
+impl DockerClient {
+    pub fn new(client: http_client_unix_domain_socket::ClientUnix) -> Self {
+        DockerClient { client }
+    }
+
+    pub fn client(&self) -> &http_client_unix_domain_socket::ClientUnix {
+        &self.client
+    }
+
+    /// Inspect a container
+    ///
+    /// `GET /containers/{id}/json`
+    pub async fn container_inspect(&self, id: &str, size: Option<bool>) -> Result<Container, http_client_unix_domain_socket::ErrorAndResponseJson<serde_json::Value>> {
+        let path = ["/containers/", &Self::encode(id), "/json"].concat();
+        let mut query: Vec<(&str, String)> = Vec::new();
+        let headers: Vec<(&str, String)> = Vec::new();
+        if let Some(size) = size { query.push(("size", size.to_string())); }
+        let endpoint = Self::endpoint(path, &query);
+        let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        self.client
+            .send_request_json::<(), Container, serde_json::Value>(&endpoint, http_client_unix_domain_socket::Method::GET, &headers, None)
+            .await
+            .map(|(_, response)| response)
+    }
+
+    /// `POST /containers/{id}/start`
+    pub async fn container_start(&self, id: &str) -> Result<Vec<u8>, http_client_unix_domain_socket::ErrorAndResponse> {
+        let path = ["/containers/", &Self::encode(id), "/start"].concat();
+        let query: Vec<(&str, String)> = Vec::new();
+        let headers: Vec<(&str, String)> = Vec::new();
+        let endpoint = Self::endpoint(path, &query);
+        let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        self.client
+            .send_request(&endpoint, http_client_unix_domain_socket::Method::POST, &headers, None)
+            .await
+            .map(|(_, response)| response)
+    }
+
+    fn encode(value: &str) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    fn endpoint(path: String, query: &[(&str, String)]) -> String {
+        let mut endpoint = path;
+        for (index, (name, value)) in query.iter().enumerate() {
+            endpoint.push(if index == 0 { '?' } else { '&' });
+            endpoint.push_str(&Self::encode(name));
+            endpoint.push('=');
+            endpoint.push_str(&Self::encode(value));
+        }
+        endpoint
+    }
+}
@@ -0,0 +1,671 @@
+#[cfg(feature = "json")]
+use crate::Cassette;
+use crate::{
+    Error, PoolStats,
+    builder::ClientConfig,
+    connection::Connection,
+    keep_alive::KeepAlive,
+    priority::{Priority, PriorityPermit, PrioritySemaphore},
+};
+use std::{
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::OwnedMutexGuard;
+
+/// Connections to a single socket, reused between requests.
+#[derive(Debug)]
+pub(crate) struct Pool {
+    socket_path: PathBuf,
+    config: ClientConfig,
+    idle: Mutex<Vec<Connection>>,
+    permits: Arc<PrioritySemaphore>,
+    in_flight: AtomicUsize,
+    /// Connections borrowed by a [PooledConnection].
+    in_use: AtomicUsize,
+}
+
+impl Pool {
+    /// Create the pool and open its first connection, so an unreachable server is reported right away.
+    pub(crate) async fn try_new(socket_path: PathBuf, config: ClientConfig) -> Result<Self, Error> {
+        // Replayed cassettes never use the socket, it may not exist.
+        #[cfg(feature = "json")]
+        let replaying = config.cassette.as_ref().is_some_and(Cassette::is_replaying);
+        #[cfg(not(feature = "json"))]
+        let replaying = false;
+        let mut idle = Vec::new();
+        if !replaying {
+            idle.push(Connection::try_connect(&socket_path, &config).await?);
+        }
+
+        Ok(Pool {
+            permits: Arc::new(PrioritySemaphore::new(config.max_connections)),
+            idle: Mutex::new(idle),
+            in_flight: AtomicUsize::new(0),
+            in_use: AtomicUsize::new(0),
+            socket_path,
+            config,
+        })
+    }
+
+    pub(crate) fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Number of requests using or waiting for a connection of this pool.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        let idle = self.idle.lock().map(|idle| idle.len()).unwrap_or_default();
+        let in_use = self.in_use.load(Ordering::Relaxed);
+        PoolStats {
+            total: idle + in_use,
+            idle,
+            in_use,
+            waiting: self.permits.waiting(),
+        }
+    }
+
+    /// Wait for a free slot, given by `priority`, then reuse an idle connection or open a new one.
+    ///
+    /// [Error::PoolTimeout] is returned when no slot is freed during [ClientConfig::pool_timeout].
+    pub(crate) async fn acquire(
+        self: &Arc<Self>,
+        priority: Priority,
+    ) -> Result<PooledConnection, Error> {
+        let usage = PoolUsage::new(self.clone());
+        let permit = match self.config.pool_timeout {
+            Some(pool_timeout) => {
+                tokio::time::timeout(pool_timeout, self.permits.acquire(priority))
+                    .await
+                    .map_err(|_| Error::PoolTimeout(pool_timeout, self.permits.waiting()))?
+            }
+            None => self.permits.acquire(priority).await,
+        };
+
+        let connection = match self.pop_idle() {
+            Some(connection) => {
+                log::trace!("reusing an idle connection to {:?}", self.socket_path);
+                connection
+            }
+            None => {
+                log::trace!("opening a new connection to {:?}", self.socket_path);
+                Connection::try_connect(&self.socket_path, &self.config).await?
+            }
+        };
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+
+        Ok(PooledConnection {
+            connection: Slot::Owned(Some(connection)),
+            pool: self.clone(),
+            reusable: false,
+            keep_alive: true,
+            _permit: Some(permit),
+            _usage: usage,
+            _lease: None,
+        })
+    }
+
+    /// Most recently used idle connection still open, the ones closed by the server meanwhile are dropped.
+    fn pop_idle(&self) -> Option<Connection> {
+        let mut idle = self.idle.lock().ok()?;
+        while let Some(connection) = idle.pop() {
+            if !connection.sender.is_closed() {
+                return Some(connection);
+            }
+            log::debug!(
+                "dropping an idle connection to {:?} closed by the server",
+                self.socket_path
+            );
+            connection.close();
+        }
+        None
+    }
+
+    /// Borrow a connection for a sequence of requests, it is given back to the pool when the lease is dropped.
+    pub(crate) async fn lease(
+        self: &Arc<Self>,
+        priority: Priority,
+    ) -> Result<ConnectionLease, Error> {
+        let mut pooled = self.acquire(priority).await?;
+        // Still counted in use, until the lease is dropped.
+        let connection = pooled.connection.take();
+        Ok(ConnectionLease {
+            connection: Arc::new(tokio::sync::Mutex::new(connection)),
+            pool: self.clone(),
+            _permit: pooled._permit.take(),
+        })
+    }
+
+    /// Give a connection which is no longer used back to the idle ones, unless [ClientConfig::max_idle_connections] are already idle.
+    fn release(&self, mut connection: Connection) {
+        let max_idle_connections = self.config.max_idle_connections;
+        if let Ok(mut idle) = self.idle.lock()
+            && max_idle_connections.is_none_or(|max| idle.len() < max)
+        {
+            log::trace!("releasing a connection to {:?}", self.socket_path);
+            connection.set_idle(self.config.clock.now());
+            idle.push(connection);
+            return;
+        }
+        log::debug!(
+            "closing a connection to {:?} above max_idle_connections",
+            self.socket_path
+        );
+        connection.close();
+    }
+
+    /// Ping the connections idle for at least the keep-alive interval, closing those that fail.
+    ///
+    /// A free slot is taken for each pinged connection, so the pings never exceed [ClientConfig::max_connections] and busy pools are not pinged.
+    pub(crate) async fn keep_alive(&self, keep_alive: &KeepAlive) {
+        let connections: Vec<_> = match self.idle.lock() {
+            Ok(mut idle) => {
+                let mut connections = Vec::new();
+                let now = self.config.clock.now();
+                let mut index = 0;
+                while index < idle.len() {
+                    if now.saturating_duration_since(idle[index].idle_since()) < keep_alive.interval
+                    {
+                        index += 1;
+                        continue;
+                    }
+                    let Some(permit) = self.permits.try_acquire() else {
+                        break;
+                    };
+                    connections.push((idle.swap_remove(index), permit));
+                }
+                connections
+            }
+            Err(_) => Vec::new(),
+        };
+
+        for (mut connection, _permit) in connections {
+            match connection.ping(keep_alive.request(&self.config)).await {
+                Ok(()) => {
+                    connection.set_idle(self.config.clock.now());
+                    if let Ok(mut idle) = self.idle.lock() {
+                        idle.push(connection);
+                    }
+                }
+                Err(e) => {
+                    log::debug!("keep-alive ping failed, closing the connection: {}", e);
+                    connection.close();
+                }
+            }
+        }
+    }
+
+    /// Close the connections idle for at least `idle_timeout`, the oldest first, keeping [ClientConfig::min_idle_connections].
+    pub(crate) fn reap_idle(&self, idle_timeout: Duration) {
+        let reaped = match self.idle.lock() {
+            Ok(mut idle) => {
+                let mut reaped = Vec::new();
+                let now = self.config.clock.now();
+                let mut index = 0;
+                while index < idle.len() && idle.len() > self.config.min_idle_connections {
+                    match now.saturating_duration_since(idle[index].idle_since()) >= idle_timeout {
+                        true => reaped.push(idle.remove(index)),
+                        false => index += 1,
+                    }
+                }
+                reaped
+            }
+            Err(_) => Vec::new(),
+        };
+
+        if !reaped.is_empty() {
+            log::debug!(
+                "closing {} idle connections to {:?}",
+                reaped.len(),
+                self.socket_path
+            );
+        }
+        for connection in reaped {
+            connection.close();
+        }
+    }
+
+    /// Close every idle connection, returning the first error reported by one of them.
+    pub(crate) async fn close_idle(&self) -> Option<Error> {
+        let connections = match self.idle.lock() {
+            Ok(mut idle) => std::mem::take(&mut *idle),
+            Err(_) => Vec::new(),
+        };
+
+        let mut error = None;
+        for connection in connections {
+            let connection_error = connection.abort().await;
+            error = error.or(connection_error);
+        }
+        error
+    }
+
+    /// Replace every idle connection by a new one.
+    pub(crate) async fn try_reconnect(&self) -> Result<(), Error> {
+        self.close_idle().await;
+        let connection = Connection::try_connect(&self.socket_path, &self.config).await?;
+        self.config.stats.reconnect();
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push(connection);
+        }
+        Ok(())
+    }
+}
+
+/// A [Connection] borrowed from a [Pool], given back when dropped if it can be reused.
+#[derive(Debug)]
+pub(crate) struct PooledConnection {
+    // Dropped before the lease, so the lease can give the connection back to the pool.
+    connection: Slot,
+    pool: Arc<Pool>,
+    reusable: bool,
+    keep_alive: bool,
+    /// [None] when the connection is leased, the lease holds the permit.
+    _permit: Option<PriorityPermit>,
+    _usage: PoolUsage,
+    _lease: Option<Arc<ConnectionLease>>,
+}
+
+/// Connection of a [PooledConnection], owned or borrowed from a [ConnectionLease] for a single request.
+#[derive(Debug)]
+enum Slot {
+    Owned(Option<Connection>),
+    Leased(OwnedMutexGuard<Option<Connection>>),
+}
+
+impl Slot {
+    fn get(&self) -> Option<&Connection> {
+        match self {
+            Slot::Owned(connection) => connection.as_ref(),
+            Slot::Leased(connection) => connection.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self) -> Option<&mut Connection> {
+        match self {
+            Slot::Owned(connection) => connection.as_mut(),
+            Slot::Leased(connection) => connection.as_mut(),
+        }
+    }
+
+    fn take(&mut self) -> Option<Connection> {
+        match self {
+            Slot::Owned(connection) => connection.take(),
+            Slot::Leased(connection) => connection.take(),
+        }
+    }
+
+    fn replace(&mut self, new_connection: Connection) -> Option<Connection> {
+        match self {
+            Slot::Owned(connection) => connection.replace(new_connection),
+            Slot::Leased(connection) => connection.replace(new_connection),
+        }
+    }
+}
+
+/// Connection borrowed from a [Pool] by [crate::ClientUnix::with_connection], every request of the lease is sent on it in turn.
+///
+/// It holds a slot of the pool until dropped, then the connection is given back to the pool.
+#[derive(Debug)]
+pub(crate) struct ConnectionLease {
+    /// [None] once the connection was closed, until the next request of the lease.
+    connection: Arc<tokio::sync::Mutex<Option<Connection>>>,
+    pool: Arc<Pool>,
+    _permit: Option<PriorityPermit>,
+}
+
+impl ConnectionLease {
+    /// Wait for the previous request of the lease to be complete, then borrow the connection.
+    ///
+    /// A new connection is opened when the previous one was closed, by the server or after a `Connection: close` response.
+    pub(crate) async fn acquire(self: &Arc<Self>) -> Result<PooledConnection, Error> {
+        let usage = PoolUsage::new(self.pool.clone());
+        let mut connection = self.connection.clone().lock_owned().await;
+        if connection
+            .as_ref()
+            .is_none_or(|connection| connection.sender.is_closed())
+        {
+            if let Some(closed) = connection.take() {
+                closed.close();
+            }
+            log::trace!(
+                "reopening a leased connection to {:?}",
+                self.pool.socket_path
+            );
+            *connection =
+                Some(Connection::try_connect(&self.pool.socket_path, &self.pool.config).await?);
+        }
+        Ok(PooledConnection {
+            connection: Slot::Leased(connection),
+            pool: self.pool.clone(),
+            reusable: false,
+            keep_alive: true,
+            _permit: None,
+            _usage: usage,
+            _lease: Some(self.clone()),
+        })
+    }
+}
+
+impl Drop for ConnectionLease {
+    fn drop(&mut self) {
+        self.pool.in_use.fetch_sub(1, Ordering::Relaxed);
+        let connection = self
+            .connection
+            .try_lock()
+            .ok()
+            .and_then(|mut connection| connection.take());
+        match connection {
+            Some(connection) if !connection.sender.is_closed() => self.pool.release(connection),
+            Some(connection) => connection.close(),
+            None => {}
+        }
+    }
+}
+
+/// Count a request in [Pool::in_flight] until dropped.
+#[derive(Debug)]
+struct PoolUsage(Arc<Pool>);
+
+impl PoolUsage {
+    fn new(pool: Arc<Pool>) -> Self {
+        pool.in_flight.fetch_add(1, Ordering::Relaxed);
+        PoolUsage(pool)
+    }
+}
+
+impl Drop for PoolUsage {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl PooledConnection {
+    /// Replace the connection by a new one.
+    pub(crate) async fn reconnect(&mut self) -> Result<(), Error> {
+        let connection = Connection::try_connect(&self.pool.socket_path, &self.pool.config).await?;
+        self.pool.config.stats.reconnect();
+        if let Some(old_connection) = self.connection.replace(connection) {
+            old_connection.abort().await;
+        }
+        Ok(())
+    }
+
+    /// Mark the exchange as complete, the connection can be given back to the pool unless the server closes it.
+    pub(crate) fn set_reusable(&mut self) {
+        self.reusable = self.keep_alive;
+    }
+
+    /// The server closes the connection after the response, so it is dropped instead of being given back to the pool.
+    pub(crate) fn close_after_response(&mut self) {
+        self.keep_alive = false;
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection
+            .get()
+            .expect("PooledConnection always holds a connection")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .get_mut()
+            .expect("PooledConnection always holds a connection")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let reusable = self.reusable
+            && self
+                .connection
+                .get()
+                .is_some_and(|connection| !connection.sender.is_closed());
+        if reusable && matches!(self.connection, Slot::Leased(_)) {
+            // Kept by the lease for its next request.
+            return;
+        }
+        let Some(connection) = self.connection.take() else {
+            return;
+        };
+        if matches!(self.connection, Slot::Owned(_)) {
+            self.pool.in_use.fetch_sub(1, Ordering::Relaxed);
+        }
+        if !reusable {
+            log::trace!(
+                "closing a connection to {:?} not reusable",
+                self.pool.socket_path
+            );
+            connection.close();
+            return;
+        }
+        self.pool.release(connection);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Clock, Error, ErrorAndResponse, Method, PoolStats,
+        test_helpers::{raw_server::RawServer, server::Server, util::*},
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+    use tokio::time::Instant;
+
+    /// Clock running ahead of the tokio timer by a shift set by the test.
+    #[derive(Debug, Clone, Default)]
+    struct ShiftedClock(Arc<Mutex<Duration>>);
+
+    impl Clock for ShiftedClock {
+        fn now(&self) -> Instant {
+            Instant::now() + *self.0.lock().expect("ShiftedClock lock")
+        }
+    }
+
+    #[tokio::test]
+    async fn reap_idle_connections_with_clock() {
+        let socket_path = make_socket_path_test("pool", "reap_idle_connections_with_clock");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let clock = ShiftedClock::default();
+        let client = ClientUnix::builder(&socket_path)
+            .clock(clock.clone())
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        let pool = client.select_pool();
+
+        pool.reap_idle(Duration::from_secs(60));
+        assert_eq!(client.stats().pool.idle, 1);
+
+        *clock.0.lock().expect("ShiftedClock lock") = Duration::from_secs(61);
+        pool.reap_idle(Duration::from_secs(60));
+        assert_eq!(client.stats().pool.idle, 0);
+    }
+
+    #[tokio::test]
+    async fn pool_stats() {
+        let socket_path = make_socket_path_test("pool", "pool_stats");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(1)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let stats = |total, idle, in_use, waiting| PoolStats {
+            total,
+            idle,
+            in_use,
+            waiting,
+        };
+        assert_eq!(client.stats().pool, stats(1, 1, 0, 0));
+
+        // Sent once the connection is leased.
+        let waiting = tokio::task::spawn({
+            let client = client.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                client.send_request("/nolanv", Method::GET, &[], None).await
+            }
+        });
+        client
+            .with_connection(|leased| async move {
+                assert_eq!(leased.stats().pool, stats(1, 0, 1, 0));
+                tokio::time::sleep(Duration::from_millis(40)).await;
+                assert_eq!(leased.stats().pool, stats(1, 0, 1, 1));
+            })
+            .await
+            .expect("ClientUnix::with_connection");
+
+        waiting
+            .await
+            .expect("waiting")
+            .expect("client.send_request");
+        assert_eq!(client.stats().pool, stats(1, 1, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn reap_idle_connections() {
+        let socket_path = make_socket_path_test("pool", "reap_idle_connections");
+        let server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(3)
+            .max_idle_connections(2)
+            .min_idle_connections(1)
+            .idle_timeout(Duration::from_millis(50))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let idle = || client.select_pool().idle.lock().expect("idle").len();
+
+        let responses = tokio::join!(
+            client.send_request("/nolanv", Method::GET, &[], None),
+            client.send_request("/nolanv", Method::GET, &[], None),
+            client.send_request("/nolanv", Method::GET, &[], None)
+        );
+        for result in [responses.0, responses.1, responses.2] {
+            result.expect("client.send_request");
+        }
+        assert_eq!(server.connections(), 3);
+        assert_eq!(idle(), 2);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(idle(), 1);
+    }
+
+    #[tokio::test]
+    async fn pool_timeout() {
+        let socket_path = make_socket_path_test("pool", "pool_timeout");
+        // Requests to /hang never get their whole body, keeping the connection busy.
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            match request.starts_with(b"GET http://unix.socket/hang") {
+                true => b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnol".to_vec(),
+                false => b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(1)
+            .pool_timeout(Duration::from_millis(50))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let hanging = tokio::task::spawn({
+            let client = client.clone();
+            async move { client.send_request("/hang", Method::GET, &[], None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let waiting = tokio::task::spawn({
+            let client = client.clone();
+            async move { client.send_request("/nolanv", Method::GET, &[], None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // The first waiter times out while this request is still waiting.
+        let result = client.send_request("/nolanv", Method::GET, &[], None).await;
+        assert!(matches!(
+            waiting.await.expect("waiting"),
+            Err(ErrorAndResponse::InternalError(Error::PoolTimeout(_, 1)))
+        ));
+        assert!(matches!(
+            result,
+            Err(ErrorAndResponse::InternalError(Error::PoolTimeout(_, 0)))
+        ));
+
+        hanging.abort();
+        let (_, response) = client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(response, b"nolanv");
+    }
+
+    #[tokio::test]
+    async fn drop_closed_idle_connections() {
+        let socket_path = make_socket_path_test("client", "drop_closed_idle_connections");
+        let (server, client) = make_client_server("drop_closed_idle_connections").await;
+        let lease_client = client.clone();
+        lease_client
+            .with_connection(|lease_client| async move {
+                for client in [&client, &lease_client] {
+                    client
+                        .send_request("/nolanv", Method::GET, &[], None)
+                        .await
+                        .expect("ClientUnix::send_request");
+                }
+                // The server closes the idle connections without a `Connection: close` response.
+                server.abort().await;
+                let _server = Server::try_new(&socket_path)
+                    .await
+                    .expect("Server::try_new");
+                tokio::time::sleep(Duration::from_millis(20)).await;
+
+                for client in [&client, &lease_client] {
+                    let (status_code, _) = client
+                        .send_request("/nolanv", Method::GET, &[], None)
+                        .await
+                        .expect("ClientUnix::send_request");
+                    assert_eq!(status_code, 200);
+                }
+            })
+            .await
+            .expect("ClientUnix::with_connection");
+    }
+}
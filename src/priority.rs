@@ -0,0 +1,179 @@
+use crate::ClientUnix;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::oneshot;
+
+/// Priority of the requests of a [ClientUnix], set with [ClientUnix::with_priority].
+///
+/// Requests waiting for a connection of the pool or for [crate::ClientUnixBuilder::max_in_flight_requests] are served by priority, then in their arrival order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Semaphore giving its permits to the waiters of the highest [Priority] first.
+#[derive(Debug)]
+pub(crate) struct PrioritySemaphore(Mutex<Waiters>);
+
+#[derive(Debug)]
+struct Waiters {
+    available: usize,
+    // Indexed by priority, a waiter is removed when its future is dropped or when it is given a permit.
+    queues: [VecDeque<oneshot::Sender<PriorityPermit>>; 3],
+}
+
+/// Permit of a [PrioritySemaphore], given to the next waiter when dropped.
+#[derive(Debug)]
+pub(crate) struct PriorityPermit(Option<Arc<PrioritySemaphore>>);
+
+impl PrioritySemaphore {
+    pub(crate) fn new(permits: usize) -> Self {
+        PrioritySemaphore(Mutex::new(Waiters {
+            available: permits,
+            queues: Default::default(),
+        }))
+    }
+
+    /// Wait for a permit, after the waiters of a higher priority and those of the same priority arrived before.
+    pub(crate) async fn acquire(self: &Arc<Self>, priority: Priority) -> PriorityPermit {
+        let receiver = {
+            let mut waiters = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            for queue in waiters.queues.iter_mut() {
+                queue.retain(|waiter| !waiter.is_closed());
+            }
+            if waiters.available > 0 && waiters.queues.iter().all(VecDeque::is_empty) {
+                waiters.available -= 1;
+                return PriorityPermit(Some(self.clone()));
+            }
+            let (sender, receiver) = oneshot::channel();
+            waiters.queues[priority as usize].push_back(sender);
+            receiver
+        };
+        receiver
+            .await
+            .expect("Waiters are only removed to be given a permit")
+    }
+
+    /// Take a permit if one is available and nobody is waiting for it.
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Option<PriorityPermit> {
+        let mut waiters = self.0.lock().ok()?;
+        if waiters.available == 0 || waiters.queues.iter().flatten().any(|w| !w.is_closed()) {
+            return None;
+        }
+        waiters.available -= 1;
+        Some(PriorityPermit(Some(self.clone())))
+    }
+
+    /// Number of waiters still waiting for a permit.
+    pub(crate) fn waiting(&self) -> usize {
+        let waiters = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        waiters
+            .queues
+            .iter()
+            .flatten()
+            .filter(|w| !w.is_closed())
+            .count()
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut waiters = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        for queue in waiters.queues.iter_mut().rev() {
+            while let Some(waiter) = queue.pop_front() {
+                match waiter.send(PriorityPermit(Some(self.clone()))) {
+                    Ok(()) => return,
+                    // The waiter was dropped, its permit must not be released again.
+                    Err(mut permit) => permit.0 = None,
+                }
+            }
+        }
+        waiters.available += 1;
+    }
+}
+
+impl Drop for PriorityPermit {
+    fn drop(&mut self) {
+        if let Some(semaphore) = self.0.take() {
+            semaphore.release();
+        }
+    }
+}
+
+impl ClientUnix {
+    /// Get a clone of the client sending its requests with `priority`, sharing the same connections.
+    ///
+    /// When all the connections are busy, the requests of a higher priority get the next free one, so latency-critical calls don't wait behind bulk transfers. Requests being sent are never interrupted. Default is [Priority::Normal].
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method, Priority};
+    ///
+    /// pub async fn get_health() {
+    ///     let client = ClientUnix::builder("/tmp/unix.socket")
+    ///         .max_connections(4)
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    ///
+    ///     let (status_code, _) = client
+    ///         .with_priority(Priority::High)
+    ///         .send_request("/health", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request");
+    ///     println!("{}", status_code);
+    /// }
+    /// ```
+    pub fn with_priority(&self, priority: Priority) -> ClientUnix {
+        let mut client = self.clone();
+        client.priority = priority;
+        client
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serve_by_priority() {
+        let semaphore = Arc::new(PrioritySemaphore::new(1));
+        let permit = semaphore.acquire(Priority::Normal).await;
+        assert!(semaphore.try_acquire().is_none());
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut tasks = Vec::new();
+        for (name, priority) in [
+            ("low", Priority::Low),
+            ("normal", Priority::Normal),
+            ("cancelled", Priority::High),
+            ("high", Priority::High),
+        ] {
+            let (semaphore, sender) = (semaphore.clone(), sender.clone());
+            tasks.push(tokio::task::spawn(async move {
+                let _permit = semaphore.acquire(priority).await;
+                sender.send(name).expect("sender.send");
+            }));
+            tokio::task::yield_now().await;
+        }
+        tasks.remove(2).abort();
+        tokio::task::yield_now().await;
+
+        drop(permit);
+        for task in tasks {
+            task.await.expect("task");
+        }
+        let mut order = Vec::new();
+        while let Ok(name) = receiver.try_recv() {
+            order.push(name);
+        }
+        assert_eq!(order, ["high", "normal", "low"]);
+        assert!(semaphore.try_acquire().is_some());
+    }
+}
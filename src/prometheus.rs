@@ -0,0 +1,355 @@
+use crate::{ClientUnix, Error, error::ErrorAndResponse};
+use hyper::Method;
+
+/// Type of a [MetricFamily] declared by its `# TYPE` line **(feature = prometheus)**.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+    Summary,
+    Untyped,
+}
+
+impl MetricType {
+    fn parse(metric_type: &str) -> Option<Self> {
+        match metric_type {
+            "counter" => Some(MetricType::Counter),
+            "gauge" => Some(MetricType::Gauge),
+            "histogram" => Some(MetricType::Histogram),
+            "summary" => Some(MetricType::Summary),
+            "untyped" => Some(MetricType::Untyped),
+            _ => None,
+        }
+    }
+
+    /// Suffixes of the samples belonging to a family of this type.
+    fn suffixes(&self) -> &'static [&'static str] {
+        match self {
+            MetricType::Histogram => &["_bucket", "_sum", "_count"],
+            MetricType::Summary => &["_sum", "_count"],
+            _ => &[],
+        }
+    }
+}
+
+/// Single sample of a metric **(feature = prometheus)**.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// Full sample name, including the `_bucket`, `_sum` or `_count` suffix.
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+    /// Milliseconds since the epoch.
+    pub timestamp: Option<i64>,
+}
+
+impl Sample {
+    /// Get the value of a label.
+    pub fn label(&self, name: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(label_name, _)| label_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Samples sharing the same metric name **(feature = prometheus)**.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFamily {
+    pub name: String,
+    pub help: Option<String>,
+    pub metric_type: MetricType,
+    pub samples: Vec<Sample>,
+}
+
+impl MetricFamily {
+    fn new(name: &str) -> Self {
+        MetricFamily {
+            name: name.to_string(),
+            help: None,
+            metric_type: MetricType::Untyped,
+            samples: Vec::new(),
+        }
+    }
+
+    fn owns(&self, sample_name: &str) -> bool {
+        sample_name == self.name
+            || sample_name
+                .strip_prefix(self.name.as_str())
+                .is_some_and(|suffix| self.metric_type.suffixes().contains(&suffix))
+    }
+}
+
+/// Error returned when a line of the exposition format is invalid **(feature = prometheus)**.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsParseError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for MetricsParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+impl std::error::Error for MetricsParseError {}
+
+/// Parse the Prometheus text exposition format (version 0.0.4).
+pub fn parse_metrics(text: &str) -> Result<Vec<MetricFamily>, MetricsParseError> {
+    let mut families: Vec<MetricFamily> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let error = |message| MetricsParseError {
+            line: index + 1,
+            message,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, ' ');
+            let (Some(keyword @ ("HELP" | "TYPE")), Some(name)) = (parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let value = parts.next().unwrap_or("").trim();
+            let family = family_mut(&mut families, name);
+            match keyword {
+                "HELP" => family.help = Some(unescape(value, false)),
+                _ => {
+                    family.metric_type =
+                        MetricType::parse(value).ok_or_else(|| error("unknown metric type"))?
+                }
+            }
+            continue;
+        }
+
+        let sample = parse_sample(line).ok_or_else(|| error("invalid sample"))?;
+        let family = match families
+            .iter()
+            .rposition(|family| family.owns(&sample.name))
+        {
+            Some(position) => &mut families[position],
+            None => family_mut(&mut families, &sample.name),
+        };
+        family.samples.push(sample);
+    }
+    Ok(families)
+}
+
+fn family_mut<'a>(families: &'a mut Vec<MetricFamily>, name: &str) -> &'a mut MetricFamily {
+    let position = match families.iter().position(|family| family.name == name) {
+        Some(position) => position,
+        None => {
+            families.push(MetricFamily::new(name));
+            families.len() - 1
+        }
+    };
+    &mut families[position]
+}
+
+fn parse_sample(line: &str) -> Option<Sample> {
+    let name_end = line.find(['{', ' ', '\t'])?;
+    let (name, mut rest) = line.split_at(name_end);
+
+    let mut labels = Vec::new();
+    if let Some(after_brace) = rest.strip_prefix('{') {
+        rest = after_brace;
+        loop {
+            rest = rest.trim_start_matches([' ', ',']);
+            if let Some(after_brace) = rest.strip_prefix('}') {
+                rest = after_brace;
+                break;
+            }
+            let (label_name, after_name) = rest.split_once('=')?;
+            let after_quote = after_name.trim_start().strip_prefix('"')?;
+            let value_end = closing_quote(after_quote)?;
+            labels.push((
+                label_name.trim().to_string(),
+                unescape(&after_quote[..value_end], true),
+            ));
+            rest = &after_quote[value_end + 1..];
+        }
+    }
+
+    let mut fields = rest.split_whitespace();
+    let value = parse_value(fields.next()?)?;
+    let timestamp = match fields.next() {
+        Some(timestamp) => Some(timestamp.parse().ok()?),
+        None => None,
+    };
+    Some(Sample {
+        name: name.to_string(),
+        labels,
+        value,
+        timestamp,
+    })
+}
+
+/// Position of the first unescaped quote.
+fn closing_quote(value: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (position, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(position),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_value(value: &str) -> Option<f64> {
+    match value {
+        "+Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        value => value.parse().ok(),
+    }
+}
+
+/// Unescape `\\` and `\n`, and `\"` in label values.
+fn unescape(value: &str, label_value: bool) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('\\')) => unescaped.push('\\'),
+            ('\\', Some('n')) => unescaped.push('\n'),
+            ('\\', Some('"')) if label_value => unescaped.push('"'),
+            _ => {
+                unescaped.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    unescaped
+}
+
+impl ClientUnix {
+    /// Scrape a Prometheus metrics endpoint **(feature = prometheus)**.
+    ///
+    /// Send an HTTP GET request asking for the text exposition format and parse the response with [parse_metrics].
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn scrape() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     for family in client.get_metrics("/metrics").await.expect("client.get_metrics") {
+    ///         println!("{}: {} samples", family.name, family.samples.len());
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_metrics(&self, endpoint: &str) -> Result<Vec<MetricFamily>, ErrorAndResponse> {
+        let (_, response) = self
+            .send_request(
+                endpoint,
+                Method::GET,
+                &[("Accept", "text/plain; version=0.0.4")],
+                None,
+            )
+            .await?;
+        parse_metrics(&String::from_utf8_lossy(&response))
+            .map_err(|e| ErrorAndResponse::InternalError(Error::MetricsParsing(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+
+    const METRICS: &str = r#"# HELP http_requests_total The total number of HTTP requests.
+# TYPE http_requests_total counter
+http_requests_total{method="post",code="200"} 1027 1395066363000
+http_requests_total{method="post",code="400"}    3 1395066363000
+
+# A normal comment.
+# HELP request_duration_seconds Request duration, with a \\ backslash.
+# TYPE request_duration_seconds histogram
+request_duration_seconds_bucket{le="0.1"} 33444
+request_duration_seconds_bucket{le="+Inf"} 144320
+request_duration_seconds_sum 53423
+request_duration_seconds_count 144320
+msdos_file_access_time_seconds{path="C:\\DIR\\FILE.TXT",error="Cannot find file:\n\"FILE.TXT\""} 1.458255915e9
+up NaN
+temperature -Inf
+"#;
+
+    #[test]
+    fn parse_exposition_format() {
+        let families = parse_metrics(METRICS).expect("parse_metrics");
+        let names: Vec<&str> = families.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "http_requests_total",
+                "request_duration_seconds",
+                "msdos_file_access_time_seconds",
+                "up",
+                "temperature"
+            ]
+        );
+
+        let requests = &families[0];
+        assert_eq!(requests.metric_type, MetricType::Counter);
+        assert_eq!(requests.samples[1].label("code"), Some("400"));
+        assert_eq!(requests.samples[1].value, 3.0);
+        assert_eq!(requests.samples[1].timestamp, Some(1395066363000));
+
+        let duration = &families[1];
+        assert_eq!(duration.metric_type, MetricType::Histogram);
+        assert_eq!(
+            duration.help.as_deref(),
+            Some("Request duration, with a \\ backslash.")
+        );
+        assert_eq!(duration.samples.len(), 4);
+        assert_eq!(duration.samples[1].value, 144320.0);
+        assert_eq!(duration.samples[1].label("le"), Some("+Inf"));
+
+        let access_time = &families[2].samples[0];
+        assert_eq!(access_time.label("path"), Some("C:\\DIR\\FILE.TXT"));
+        assert_eq!(
+            access_time.label("error"),
+            Some("Cannot find file:\n\"FILE.TXT\"")
+        );
+        assert!(families[3].samples[0].value.is_nan());
+        assert_eq!(families[4].samples[0].value, f64::NEG_INFINITY);
+
+        assert_eq!(
+            parse_metrics("up{job=\"api\" 1"),
+            Err(MetricsParseError {
+                line: 1,
+                message: "invalid sample"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_metrics() {
+        let socket_path = make_socket_path_test("prometheus", "get_metrics");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 30\r\n\r\n# TYPE up gauge\nup{job=\"a\"} 1\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let families = client.get_metrics("/metrics").await.expect("get_metrics");
+
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].metric_type, MetricType::Gauge);
+        assert_eq!(families[0].samples[0].label("job"), Some("a"));
+    }
+}
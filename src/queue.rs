@@ -0,0 +1,133 @@
+use crate::{Body, ClientUnix, Error, ErrorAndResponse, Method, StatusCode};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Bounded queue of the requests waiting to be sent, set with [crate::ClientUnixBuilder::max_queued_requests].
+#[derive(Debug)]
+pub(crate) struct RequestQueue {
+    max_queued_requests: usize,
+    room: Arc<Semaphore>,
+    timeout: Option<Duration>,
+}
+
+impl RequestQueue {
+    pub(crate) fn new(max_queued_requests: usize, timeout: Option<Duration>) -> Self {
+        RequestQueue {
+            max_queued_requests,
+            room: Arc::new(Semaphore::new(max_queued_requests)),
+            timeout,
+        }
+    }
+
+    /// Take a place in the queue, held until the request gets its connection.
+    ///
+    /// When the queue is full, [Error::QueueFull] is returned right away if `wait` is false, otherwise after waiting for a place during the queue timeout.
+    pub(crate) async fn enter(&self, wait: bool) -> Result<OwnedSemaphorePermit, Error> {
+        let full = || Error::QueueFull(self.max_queued_requests);
+        if !wait {
+            return self.room.clone().try_acquire_owned().map_err(|_| full());
+        }
+        let place = self.room.clone().acquire_owned();
+        let place = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, place)
+                .await
+                .map_err(|_| full())?,
+            None => place.await,
+        };
+        Ok(place.expect("Queue semaphore is never closed"))
+    }
+}
+
+impl ClientUnix {
+    /// Send a raw HTTP request, unless the request queue is full.
+    ///
+    /// Same as [ClientUnix::send_request] but [Error::QueueFull] is returned right away instead of waiting for a place when [crate::ClientUnixBuilder::max_queued_requests] requests are already waiting for a connection, so producers can slow down or shed load when the server falls behind. Without a queue limit, it behaves like [ClientUnix::send_request].
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Error, ErrorAndResponse, Method};
+    ///
+    /// pub async fn push_metrics() {
+    ///     let client = ClientUnix::builder("/tmp/unix.socket")
+    ///         .max_queued_requests(16)
+    ///         .try_build()
+    ///         .await
+    ///         .expect("ClientUnixBuilder::try_build");
+    ///
+    ///     match client.try_send_request("/metrics", Method::POST, &[], None).await {
+    ///         Err(ErrorAndResponse::InternalError(Error::QueueFull(_))) => println!("dropped"),
+    ///         result => println!("{:?}", result.map(|(status_code, _)| status_code)),
+    ///     }
+    /// }
+    /// ```
+    pub async fn try_send_request(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        let mut client = self.clone();
+        client.wait_for_queue = false;
+        client
+            .send_request(endpoint, method, headers, body_request)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::make_socket_path_test};
+
+    #[tokio::test]
+    async fn queue_full() {
+        let socket_path = make_socket_path_test("queue", "queue_full");
+        // Requests to /hang never get their whole body, keeping the connection busy.
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            match request.starts_with(b"GET http://unix.socket/hang") {
+                true => b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnol".to_vec(),
+                false => b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec(),
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .max_connections(1)
+            .max_queued_requests(1)
+            .queue_timeout(Duration::from_millis(10))
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let hanging = tokio::task::spawn({
+            let client = client.clone();
+            async move { client.send_request("/hang", Method::GET, &[], None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let queued = tokio::task::spawn({
+            let client = client.clone();
+            async move { client.send_request("/nolanv", Method::GET, &[], None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let try_result = client
+            .try_send_request("/nolanv", Method::GET, &[], None)
+            .await;
+        let timeout_result = client.send_request("/nolanv", Method::GET, &[], None).await;
+        for result in [try_result, timeout_result] {
+            assert!(matches!(
+                result,
+                Err(ErrorAndResponse::InternalError(Error::QueueFull(1)))
+            ));
+        }
+
+        hanging.abort();
+        let (_, response) = queued.await.expect("queued").expect("client.send_request");
+        assert_eq!(response, b"nolanv");
+        let (_, response) = client
+            .try_send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.try_send_request");
+        assert_eq!(response, b"nolanv");
+    }
+}
@@ -0,0 +1,73 @@
+use crate::clock::Clock;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Mutex, time::Instant};
+
+/// Token bucket limiting the rate of the requests.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: f64, burst: u32, clock: Arc<dyn Clock>) -> Self {
+        RateLimiter {
+            requests_per_second,
+            burst: burst.into(),
+            bucket: Mutex::new(Bucket {
+                tokens: burst.into(),
+                refilled_at: clock.now(),
+            }),
+            clock,
+        }
+    }
+
+    /// Wait until a request can be sent, requests are served in order.
+    pub(crate) async fn acquire(&self) {
+        let mut bucket = self.bucket.lock().await;
+        loop {
+            let now = self.clock.now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+            bucket.refilled_at = now;
+
+            if bucket.tokens >= 1.0 {
+                bucket.tokens -= 1.0;
+                return;
+            }
+            let missing = 1.0 - bucket.tokens;
+            self.clock
+                .sleep(Duration::from_secs_f64(missing / self.requests_per_second))
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TokioClock;
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_burst_then_rate() {
+        let rate_limiter = RateLimiter::new(10.0, 2, Arc::new(TokioClock));
+        let start = Instant::now();
+
+        rate_limiter.acquire().await;
+        rate_limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        rate_limiter.acquire().await;
+        rate_limiter.acquire().await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_millis(210));
+    }
+}
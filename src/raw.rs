@@ -0,0 +1,383 @@
+use crate::{
+    ClientUnix, Error, OriginalHeaders, Response,
+    connection::{bandwidths, open_socket},
+    stream::{ClientStream, MAX_HEAD_SIZE},
+};
+use hyper::{
+    HeaderMap, Method, StatusCode, Version,
+    header::{CONTENT_LENGTH, HeaderName, HeaderValue, TRANSFER_ENCODING},
+    http::response::Parts,
+};
+use std::{
+    sync::{Arc, Mutex, atomic::AtomicBool},
+    time::Instant,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+const MAX_HEADERS: usize = 100;
+
+/// Response read from a raw exchange, with all the bytes received.
+struct RawResponse {
+    bytes: Vec<u8>,
+    parts: Parts,
+    body: Vec<u8>,
+}
+
+impl ClientUnix {
+    /// Write pre-serialized request bytes on a new connection and parse the response.
+    ///
+    /// Escape hatch for non-conforming servers expecting requests that can't be built with [hyper], like an invalid header name or a bare `\n` line ending. The bytes are written as-is on a dedicated connection, closed after the response. The pool, hooks and recorders of the client don't apply, only the timeouts and bandwidth limits do. The response is returned whatever its status code, framed by its `Transfer-Encoding` or `Content-Length` header or read until the server closes the connection. [Error::RawResponseParsing] is returned when the response head is over 64 KiB.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    ///
+    /// pub async fn get_legacy() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let response = client
+    ///         .send_raw_bytes(b"GET /legacy HTTP/1.1\nHost: unix.socket\nX Legacy: 1\n\n")
+    ///         .await
+    ///         .expect("client.send_raw_bytes");
+    ///     println!("{} {:?}", response.status_code(), response.body());
+    /// }
+    /// ```
+    pub async fn send_raw_bytes(&self, request: &[u8]) -> Result<Response<Vec<u8>>, Error> {
+        let start = Instant::now();
+        let raw_response = self.raw_exchange(request).await?;
+        Ok(Response::new(
+            raw_response.parts,
+            raw_response.body,
+            start.elapsed(),
+        ))
+    }
+
+    /// Write pre-serialized request bytes on a new connection and return the response bytes as received.
+    ///
+    /// Same as [ClientUnix::send_raw_bytes] but the response is not parsed: its head and body are returned unchanged, including the chunked encoding.
+    pub async fn send_raw_bytes_unparsed(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(self.raw_exchange(request).await?.bytes)
+    }
+
+    async fn raw_exchange(&self, request: &[u8]) -> Result<RawResponse, Error> {
+        let config = self.config();
+        let socket_path = self.select_pool().socket_path();
+        let socket = match config.connect_timeout {
+            Some(connect_timeout) => {
+                tokio::time::timeout(connect_timeout, open_socket(socket_path, config))
+                    .await
+                    .map_err(|_| Error::ConnectTimeout(connect_timeout))??
+            }
+            None => open_socket(socket_path, config).await?,
+        };
+        config.stats.connection();
+        // The read timeout applies to the whole exchange, the connection is never idle.
+        let mut stream = ClientStream::new(
+            socket,
+            None,
+            None,
+            Arc::new(AtomicBool::new(true)),
+            config,
+            bandwidths(config),
+            Arc::new(Mutex::new(None)),
+        );
+
+        let method = request
+            .split(|byte| *byte == b' ')
+            .next()
+            .and_then(|method| Method::from_bytes(method).ok());
+        if let Some(method) = &method {
+            config.stats.request(method);
+        }
+        let result = async {
+            stream
+                .write_all(request)
+                .await
+                .map_err(Error::RawExchange)?;
+            stream.flush().await.map_err(Error::RawExchange)?;
+            read_response(&mut stream, method == Some(Method::HEAD)).await
+        }
+        .await;
+        config.stats.response(
+            result
+                .as_ref()
+                .ok()
+                .map(|raw_response| raw_response.parts.status),
+        );
+        result
+    }
+}
+
+/// Read a whole response, skipping the interim `1xx` responses.
+async fn read_response<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    head_request: bool,
+) -> Result<RawResponse, Error> {
+    let mut bytes = Vec::new();
+    let mut start = 0;
+    let (head_len, parts) = loop {
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut response = httparse::Response::new(&mut headers);
+        match response
+            .parse(&bytes[start..])
+            .map_err(|_| Error::RawResponseParsing("invalid response head"))?
+        {
+            httparse::Status::Complete(len) => {
+                let status = StatusCode::from_u16(response.code.unwrap_or_default())
+                    .map_err(|_| Error::RawResponseParsing("invalid status code"))?;
+                if status.is_informational() && status != StatusCode::SWITCHING_PROTOCOLS {
+                    start += len;
+                    continue;
+                }
+                let mut parts = hyper::Response::new(()).into_parts().0;
+                parts.status = status;
+                parts.version = match response.version {
+                    Some(0) => Version::HTTP_10,
+                    _ => Version::HTTP_11,
+                };
+                parts.headers = header_map(response.headers)?;
+                parts
+                    .extensions
+                    .insert(OriginalHeaders::from_httparse(response.headers));
+                break (start + len, parts);
+            }
+            httparse::Status::Partial => {
+                // Only response heads are read so far.
+                if bytes.len() > MAX_HEAD_SIZE {
+                    return Err(Error::RawResponseParsing("response head too large"));
+                }
+                if !read_more(stream, &mut bytes).await? {
+                    return Err(Error::RawResponseParsing("unexpected end of response head"));
+                }
+            }
+        }
+    };
+
+    let chunked = parts
+        .headers
+        .get_all(TRANSFER_ENCODING)
+        .iter()
+        .any(|value| value.as_bytes().eq_ignore_ascii_case(b"chunked"));
+    let content_length = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<usize>().ok())
+                .ok_or(Error::RawResponseParsing("invalid content-length"))
+        })
+        .transpose()?;
+    let body = if head_request
+        || parts.status == StatusCode::SWITCHING_PROTOCOLS
+        || parts.status == StatusCode::NO_CONTENT
+        || parts.status == StatusCode::NOT_MODIFIED
+    {
+        bytes.truncate(head_len);
+        Vec::new()
+    } else if chunked {
+        let (body, end) = read_chunked(stream, &mut bytes, head_len).await?;
+        bytes.truncate(end);
+        body
+    } else if let Some(content_length) = content_length {
+        let end = head_len
+            .checked_add(content_length)
+            .ok_or(Error::RawResponseParsing("invalid content-length"))?;
+        fill(stream, &mut bytes, end).await?;
+        bytes.truncate(end);
+        bytes[head_len..].to_vec()
+    } else {
+        while read_more(stream, &mut bytes).await? {}
+        bytes[head_len..].to_vec()
+    };
+    Ok(RawResponse { bytes, parts, body })
+}
+
+/// Decode a chunked body starting at `offset`, reading until its last chunk and trailers are received. Returns the body and the position of its end.
+async fn read_chunked<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    bytes: &mut Vec<u8>,
+    mut offset: usize,
+) -> Result<(Vec<u8>, usize), Error> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = fill_line(stream, bytes, offset).await?;
+        let size = std::str::from_utf8(&bytes[offset..line_end])
+            .ok()
+            .and_then(|line| line.split(';').next())
+            .and_then(|size| usize::from_str_radix(size.trim(), 16).ok())
+            .ok_or(Error::RawResponseParsing("invalid chunk size"))?;
+        offset = line_end + 2;
+        if size == 0 {
+            break;
+        }
+        // Chunk data followed by its CRLF.
+        let end = offset
+            .checked_add(size)
+            .and_then(|end| end.checked_add(2))
+            .ok_or(Error::RawResponseParsing("invalid chunk size"))?;
+        fill(stream, bytes, end).await?;
+        body.extend_from_slice(&bytes[offset..end - 2]);
+        offset = end;
+    }
+    // Trailers, up to the empty line.
+    loop {
+        let line_end = fill_line(stream, bytes, offset).await?;
+        if line_end == offset {
+            return Ok((body, line_end + 2));
+        }
+        offset = line_end + 2;
+    }
+}
+
+/// Position of the next `\r\n` after `offset`, reading until it is received.
+async fn fill_line<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    bytes: &mut Vec<u8>,
+    offset: usize,
+) -> Result<usize, Error> {
+    loop {
+        if let Some(position) = bytes[offset..]
+            .windows(2)
+            .position(|window| window == b"\r\n")
+        {
+            return Ok(offset + position);
+        }
+        if !read_more(stream, bytes).await? {
+            return Err(Error::RawResponseParsing("unexpected end of chunked body"));
+        }
+    }
+}
+
+/// Read until `bytes` holds at least `len` bytes.
+async fn fill<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    bytes: &mut Vec<u8>,
+    len: usize,
+) -> Result<(), Error> {
+    while bytes.len() < len {
+        if !read_more(stream, bytes).await? {
+            return Err(Error::RawResponseParsing("unexpected end of response body"));
+        }
+    }
+    Ok(())
+}
+
+/// Append the next bytes received, false when the server closed the connection.
+async fn read_more<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    bytes: &mut Vec<u8>,
+) -> Result<bool, Error> {
+    let mut buffer = [0; 8 * 1024];
+    let read = stream.read(&mut buffer).await.map_err(Error::RawExchange)?;
+    bytes.extend_from_slice(&buffer[..read]);
+    Ok(read > 0)
+}
+
+fn header_map(headers: &[httparse::Header]) -> Result<HeaderMap, Error> {
+    let mut header_map = HeaderMap::with_capacity(headers.len());
+    for header in headers {
+        let name = HeaderName::from_bytes(header.name.as_bytes())
+            .map_err(|_| Error::RawResponseParsing("invalid header name"))?;
+        let value = HeaderValue::from_bytes(header.value)
+            .map_err(|_| Error::RawResponseParsing("invalid header value"))?;
+        header_map.append(name, value);
+    }
+    Ok(header_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+
+    #[tokio::test]
+    async fn send_raw_bytes() {
+        let socket_path = make_socket_path_test("raw", "send_raw_bytes");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        // Header names with spaces and bare line feeds are refused by hyper.
+        let request = b"GET /nolanv HTTP/1.1\nX Legacy: 1\n\n";
+        let response = client
+            .send_raw_bytes(request)
+            .await
+            .expect("client.send_raw_bytes");
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.into_body(), request);
+        assert_eq!(client.stats().requests.get(&Method::GET), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn send_raw_bytes_chunked() {
+        let socket_path = make_socket_path_test("raw", "send_raw_bytes_chunked");
+        let raw_response: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 404 Not Found\r\ntransfer-encoding: chunked\r\nx-Bread: Baguette\r\n\r\n4;ext\r\nbagu\r\n4\r\nette\r\n0\r\nx-checksum: 42\r\n\r\n";
+        let _server = RawServer::try_new(&socket_path, raw_response)
+            .await
+            .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let response = client
+            .send_raw_bytes(b"GET /nolanv HTTP/1.1\r\n\r\n")
+            .await
+            .expect("client.send_raw_bytes");
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let headers = response
+            .extensions()
+            .get::<OriginalHeaders>()
+            .expect("OriginalHeaders");
+        assert_eq!(
+            headers.get("x-bread").map(|v| v.as_bytes()),
+            Some(&b"Baguette"[..])
+        );
+        assert_eq!(response.into_body(), b"baguette");
+
+        let bytes = client
+            .send_raw_bytes_unparsed(b"GET /nolanv HTTP/1.1\r\n\r\n")
+            .await
+            .expect("client.send_raw_bytes_unparsed");
+        assert_eq!(bytes, raw_response);
+    }
+
+    #[tokio::test]
+    async fn send_raw_bytes_overflow() {
+        let socket_path = make_socket_path_test("raw", "send_raw_bytes_overflow");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            if request.starts_with(b"GET /chunked") {
+                return b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\nffffffffffffffff\r\nnolanv".to_vec();
+            }
+            if request.starts_with(b"GET /head") {
+                // The head never ends.
+                return [&b"HTTP/1.1 200 OK\r\nx-nolanv: "[..], &[b'a'; 128 * 1024]].concat();
+            }
+            format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\nnolanv", usize::MAX)
+                .into_bytes()
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        for (request, message) in [
+            (&b"GET /chunked HTTP/1.1\r\n\r\n"[..], "invalid chunk size"),
+            (b"GET /length HTTP/1.1\r\n\r\n", "invalid content-length"),
+            (b"GET /head HTTP/1.1\r\n\r\n", "response head too large"),
+        ] {
+            let result = client.send_raw_bytes(request).await;
+            assert!(
+                matches!(result, Err(Error::RawResponseParsing(m)) if m == message),
+                "{:?}",
+                result
+            );
+        }
+    }
+}
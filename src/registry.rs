@@ -0,0 +1,210 @@
+use crate::{ClientUnix, ClientUnixBuilder, Error};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
+use tokio::sync::OnceCell;
+
+type Configure = dyn Fn(ClientUnixBuilder) -> ClientUnixBuilder + Send + Sync;
+
+/// Registry of the clients returned by [ClientUnix::global].
+static GLOBAL: LazyLock<ClientRegistry> = LazyLock::new(ClientRegistry::new);
+
+/// Shared registry of [ClientUnix], lazily created and cached per socket path.
+///
+/// Applications talking to many servers can keep a single [ClientRegistry] (cheap to clone) and ask for the client of a socket when needed, the first call connects it and the next ones return a handle to the same pooled client.
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{ClientRegistry, Method};
+///
+/// pub async fn get_from_many_servers(registry: &ClientRegistry) {
+///     for socket_path in ["/tmp/unix.socket", "/tmp/unix2.socket"] {
+///         let client = registry.get(socket_path).await.expect("registry.get");
+///         client
+///             .send_request("/nolanv", Method::GET, &[], None)
+///             .await
+///             .expect("client.send_request");
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ClientRegistry {
+    clients: Arc<Mutex<HashMap<PathBuf, Arc<OnceCell<ClientUnix>>>>>,
+    configure: Option<Arc<Configure>>,
+}
+
+impl std::fmt::Debug for ClientRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ClientRegistry")
+            .field("clients", &self.clients)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClientRegistry {
+    pub fn new() -> Self {
+        ClientRegistry::default()
+    }
+
+    /// Create a registry applying `configure` to the [ClientUnixBuilder] of every client it creates.
+    pub fn with_builder(
+        configure: impl Fn(ClientUnixBuilder) -> ClientUnixBuilder + Send + Sync + 'static,
+    ) -> Self {
+        ClientRegistry {
+            clients: Arc::default(),
+            configure: Some(Arc::new(configure)),
+        }
+    }
+
+    /// Get the client of a socket, connecting it on first use.
+    ///
+    /// Concurrent calls for the same socket wait for a single connection. When connecting fails, the error is returned and the next call tries again.
+    pub async fn get(&self, socket_path: impl AsRef<Path>) -> Result<ClientUnix, Error> {
+        let socket_path = socket_path.as_ref();
+        let cell = match self.clients.lock() {
+            Ok(mut clients) => clients
+                .entry(socket_path.to_path_buf())
+                .or_default()
+                .clone(),
+            Err(_) => Arc::default(),
+        };
+
+        cell.get_or_try_init(|| {
+            let builder = ClientUnix::builder(socket_path);
+            match &self.configure {
+                Some(configure) => configure(builder),
+                None => builder,
+            }
+            .try_build()
+        })
+        .await
+        .cloned()
+    }
+
+    /// Reconnect the client of a socket, see [ClientUnix::try_reconnect].
+    ///
+    /// All the handles previously returned for this socket share the new connection.
+    pub async fn reconnect(&self, socket_path: impl AsRef<Path>) -> Result<ClientUnix, Error> {
+        self.get(socket_path).await?.try_reconnect().await
+    }
+
+    /// Remove the client of a socket from the registry, the next [ClientRegistry::get] creates a new one.
+    pub fn remove(&self, socket_path: impl AsRef<Path>) -> Option<ClientUnix> {
+        self.clients
+            .lock()
+            .ok()?
+            .remove(socket_path.as_ref())?
+            .get()
+            .cloned()
+    }
+}
+
+impl ClientUnix {
+    /// Get the process-wide client of a socket, connecting it with the default configuration on first use.
+    ///
+    /// Small tools and tests can call it wherever a client is needed instead of passing one through every function, every call for the same socket returns a handle to the same pooled client. Its connections are driven by the [tokio] runtime of the first call, use a [ClientRegistry] when clients must not outlive a runtime.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_hello() {
+    ///     let (_, hello) = ClientUnix::global("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::global")
+    ///         .send_request("/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request");
+    ///     println!("{:?}", hello);
+    /// }
+    /// ```
+    pub async fn global(socket_path: impl AsRef<Path>) -> Result<ClientUnix, Error> {
+        GLOBAL.get(socket_path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Method, StatusCode,
+        test_helpers::{raw_server::RawServer, util::make_socket_path_test},
+    };
+
+    #[tokio::test]
+    async fn registry_reuse_client() {
+        let socket_path = make_socket_path_test("registry", "registry_reuse_client");
+        let server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let registry = ClientRegistry::with_builder(|builder| builder.max_connections(1));
+
+        for _ in 0..3 {
+            let (status_code, _) = registry
+                .get(&socket_path)
+                .await
+                .expect("registry.get")
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request");
+            assert_eq!(status_code, StatusCode::OK);
+        }
+        assert_eq!(server.connections(), 1);
+
+        assert!(registry.remove(&socket_path).is_some());
+        registry.get(&socket_path).await.expect("registry.get");
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(server.connections(), 2);
+    }
+
+    #[tokio::test]
+    async fn global_client() {
+        let socket_path = make_socket_path_test("registry", "global_client");
+        let server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+
+        let client = ClientUnix::global(&socket_path)
+            .await
+            .expect("ClientUnix::global");
+        client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        let client = ClientUnix::global(&socket_path)
+            .await
+            .expect("ClientUnix::global");
+        client
+            .send_request("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(client.stats().connections, 1);
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn registry_retry_after_failure() {
+        let socket_path = make_socket_path_test("registry", "registry_retry_after_failure");
+        let _ = std::fs::remove_file(&socket_path);
+        let registry = ClientRegistry::new();
+
+        assert!(matches!(
+            registry.get(&socket_path).await.err(),
+            Some(Error::SocketConnectionInitiation(_))
+        ));
+
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv",
+        )
+        .await
+        .expect("RawServer::try_new");
+        assert!(registry.get(&socket_path).await.is_ok());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Error, builder::ClientConfig};
+use hyper::{Uri, header::HeaderValue};
+
+/// Parts of every request computed once when the client is built, so sending a request only appends its endpoint.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestDefaults {
+    /// `http://<authority>`, the endpoint is appended to it.
+    base_uri: String,
+    host: Option<HeaderValue>,
+    user_agent: Option<HeaderValue>,
+}
+
+impl RequestDefaults {
+    /// Validate the authority and the `User-Agent` of the client, [Error::RequestBuild] is returned when one is invalid.
+    pub(crate) fn try_new(config: &ClientConfig) -> Result<Self, Error> {
+        let base_uri = format!("http://{}", config.authority);
+        format!("{}/", base_uri)
+            .parse::<Uri>()
+            .map_err(|e| Error::RequestBuild(e.into()))?;
+        let host =
+            HeaderValue::from_str(&config.authority).map_err(|e| Error::RequestBuild(e.into()))?;
+        let user_agent = config
+            .user_agent
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()
+            .map_err(|e| Error::RequestBuild(e.into()))?;
+        Ok(RequestDefaults {
+            base_uri,
+            host: Some(host),
+            user_agent,
+        })
+    }
+
+    /// Absolute URI of `path_and_query`.
+    pub(crate) fn uri(&self, path_and_query: &str) -> String {
+        let mut uri = String::with_capacity(self.base_uri.len() + path_and_query.len());
+        uri.push_str(&self.base_uri);
+        uri.push_str(path_and_query);
+        uri
+    }
+
+    pub(crate) fn host(&self) -> Option<&HeaderValue> {
+        self.host.as_ref()
+    }
+
+    pub(crate) fn user_agent(&self) -> Option<&HeaderValue> {
+        self.user_agent.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_defaults() {
+        let config = ClientConfig {
+            authority: "nolanv.daemon".into(),
+            ..ClientConfig::default()
+        };
+        let defaults = RequestDefaults::try_new(&config).expect("RequestDefaults::try_new");
+        assert_eq!(defaults.uri("/hello?x=1"), "http://nolanv.daemon/hello?x=1");
+        assert_eq!(
+            defaults.host().map(|v| v.as_bytes()),
+            Some(&b"nolanv.daemon"[..])
+        );
+        assert!(defaults.user_agent().is_some());
+
+        for config in [
+            ClientConfig {
+                authority: "nolan v".into(),
+                ..ClientConfig::default()
+            },
+            ClientConfig {
+                user_agent: Some("nolanv\n".into()),
+                ..ClientConfig::default()
+            },
+        ] {
+            assert!(matches!(
+                RequestDefaults::try_new(&config),
+                Err(Error::RequestBuild(_))
+            ));
+        }
+    }
+}
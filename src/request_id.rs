@@ -0,0 +1,133 @@
+use crate::Error;
+use hyper::HeaderMap;
+use std::sync::Arc;
+
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Identifier of a request, sent in the `X-Request-Id` header when [crate::ClientUnixBuilder::request_id] is enabled.
+///
+/// It is inserted in the extensions of the sent request and of the [crate::Response], added to the errors of the request with [Error::WithRequestId] and to the slow request warnings, to correlate the client and server logs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Add the id to the `error` of its request.
+    pub(crate) fn wrap(&self, error: Error) -> Error {
+        match error {
+            Error::WithRequestId(..) => error,
+            error => Error::WithRequestId(self.clone(), Box::new(error)),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+type GeneratorFn = dyn Fn() -> String + Send + Sync;
+
+/// Generator of the [RequestId] of the requests without `X-Request-Id` header.
+#[derive(Clone)]
+pub(crate) struct RequestIdGenerator(Arc<GeneratorFn>);
+
+impl std::fmt::Debug for RequestIdGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("RequestIdGenerator").finish()
+    }
+}
+
+impl RequestIdGenerator {
+    pub(crate) fn new(generator: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        RequestIdGenerator(Arc::new(generator))
+    }
+
+    pub(crate) fn uuid() -> Self {
+        RequestIdGenerator::new(|| uuid::Uuid::new_v4().to_string())
+    }
+
+    pub(crate) fn generate(&self) -> String {
+        (self.0)()
+    }
+
+    /// Id of the request, the one given in its headers or a new one.
+    pub(crate) fn request_id(&self, headers: &HeaderMap) -> RequestId {
+        match headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(value) => RequestId(value.to_string()),
+            None => RequestId(self.generate()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ClientUnix, Error, ErrorAndResponse, Method, RequestId,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+
+    #[tokio::test]
+    async fn request_id() {
+        let socket_path = make_socket_path_test("request_id", "request_id");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .request_id(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let response = client
+            .send_request_full("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_full");
+        let request_id = response
+            .extensions()
+            .get::<RequestId>()
+            .expect("RequestId")
+            .clone();
+        assert_eq!(request_id.0.len(), 36);
+        assert!(
+            String::from_utf8_lossy(response.body())
+                .contains(&format!("x-request-id: {}\r\n", request_id))
+        );
+
+        let response = client
+            .send_request_full("/nolanv", Method::GET, &[("X-Request-Id", "nolanv")], None)
+            .await
+            .expect("client.send_request_full");
+        assert_eq!(
+            response.extensions().get::<RequestId>(),
+            Some(&RequestId("nolanv".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn request_id_in_errors() {
+        let socket_path = make_socket_path_test("request_id", "request_id_in_errors");
+        // The connection is closed without any response.
+        let _server = RawServer::try_new_with(&socket_path, true, |_| Vec::new())
+            .await
+            .expect("RawServer::try_new_with");
+        let client = ClientUnix::builder(&socket_path)
+            .request_id_generator(|| "nolanv".into())
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let Err(ErrorAndResponse::InternalError(e)) =
+            client.send_request("/nolanv", Method::GET, &[], None).await
+        else {
+            panic!("expected an internal error");
+        };
+        assert_eq!(e.request_id(), Some(&RequestId("nolanv".into())));
+        assert!(matches!(e.inner(), Error::RequestSend(_)));
+        assert!(e.is_retryable());
+        assert!(e.to_string().ends_with(" (request id nolanv)"));
+    }
+}
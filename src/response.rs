@@ -0,0 +1,445 @@
+use crate::Body;
+use crate::{
+    ClientUnix, Error, RequestDescription, Timing, WireBytes, body::Trailers,
+    error::ErrorAndResponse,
+};
+#[cfg(feature = "json")]
+use crate::{error::ErrorAndResponseJson, json};
+use hyper::{
+    HeaderMap, Method, Request, StatusCode, Version,
+    header::{HOST, USER_AGENT},
+    http::{Extensions, response::Parts, uri::InvalidUri},
+};
+#[cfg(feature = "json")]
+use serde::{Serialize, de::DeserializeOwned};
+use std::time::{Duration, Instant};
+
+/// Response and its metadata, returned by the `*_full` methods.
+#[derive(Debug)]
+pub struct Response<T> {
+    status_code: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    extensions: Extensions,
+    trailers: Option<HeaderMap>,
+    elapsed: Duration,
+    body: T,
+}
+
+impl<T> Response<T> {
+    pub(crate) fn new(mut parts: Parts, body: T, elapsed: Duration) -> Self {
+        Response {
+            status_code: parts.status,
+            version: parts.version,
+            trailers: parts.extensions.remove::<Trailers>().map(|t| t.0),
+            headers: parts.headers,
+            extensions: parts.extensions,
+            elapsed,
+            body,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Extensions of the response, like the [crate::OriginalHeaders] recorded with [crate::ClientUnixBuilder::preserve_header_case].
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Trailers sent by the server after a chunked body.
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        self.trailers.as_ref()
+    }
+
+    /// Durations of the steps of the request, [None] when the response was served by the cache or shared by a coalesced request.
+    pub fn timing(&self) -> Option<Timing> {
+        self.extensions.get::<Timing>().copied()
+    }
+
+    /// Exact bytes received for the response, [None] unless [crate::ClientUnixBuilder::capture_wire] is enabled.
+    pub fn wire_bytes(&self) -> Option<&[u8]> {
+        self.extensions
+            .get::<WireBytes>()
+            .map(|wire_bytes| wire_bytes.0.as_ref())
+    }
+
+    /// Duration between sending the request and receiving the whole response, including retries.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn body(&self) -> &T {
+        &self.body
+    }
+
+    pub fn into_body(self) -> T {
+        self.body
+    }
+}
+
+impl ClientUnix {
+    /// Send a raw HTTP request and return the [Response] with all its metadata.
+    ///
+    /// Same as [ClientUnix::send_request] but the status code, version, headers, extensions, trailers and duration of the response are returned.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let response = client
+    ///         .send_request_full("/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_full");
+    ///     println!("{:?} in {:?}", response.version(), response.elapsed());
+    /// }
+    /// ```
+    pub async fn send_request_full(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<Response<Vec<u8>>, ErrorAndResponse> {
+        let start = Instant::now();
+        let (parts, body_response) = self
+            .send(endpoint, method, headers, body_request)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        Ok(Response::new(parts, body_response.into(), start.elapsed()))
+    }
+
+    /// Send a raw HTTP request and return the [Response], whatever its status code.
+    ///
+    /// Same as [ClientUnix::send_request_full] but unsuccessful responses are returned as a [Response] instead of [ErrorAndResponse::ResponseUnsuccessful], so their headers can be read. Only the errors preventing to receive a response are returned.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let response = client
+    ///         .send_request_unchecked("/nolanv", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_unchecked");
+    ///     println!("{} {:?}", response.status_code(), response.headers());
+    /// }
+    /// ```
+    pub async fn send_request_unchecked(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<Response<Vec<u8>>, Error> {
+        let start = Instant::now();
+        let (parts, body_response) = self.send(endpoint, method, headers, body_request).await?;
+        Ok(Response::new(parts, body_response.into(), start.elapsed()))
+    }
+
+    /// Send a raw HTTP request described by a [RequestDescription] and return the [Response].
+    ///
+    /// The extensions attached with [RequestDescription::extension] are carried in the extensions of the sent HTTP request and copied in the [Response] extensions, to correlate responses with application context.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method, RequestDescription};
+    ///
+    /// #[derive(Clone, Debug)]
+    /// struct JobId(u64);
+    ///
+    /// pub async fn run_job() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let response = client
+    ///         .send_description(RequestDescription::new("/jobs", Method::POST).extension(JobId(42)))
+    ///         .await
+    ///         .expect("client.send_description");
+    ///     println!("{:?}", response.extensions().get::<JobId>());
+    /// }
+    /// ```
+    pub async fn send_description(
+        &self,
+        mut request: RequestDescription,
+    ) -> Result<Response<Vec<u8>>, ErrorAndResponse> {
+        let start = Instant::now();
+        let body_request = request.body.take();
+        let headers = request.headers();
+        let (mut parts, body_response) = self
+            .send_with_extensions(
+                &request.endpoint,
+                request.method.clone(),
+                &headers,
+                body_request,
+                &request.extensions,
+            )
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        parts.extensions.extend(request.extensions);
+        Ok(Response::new(parts, body_response.into(), start.elapsed()))
+    }
+
+    /// Send an HTTP request built by the caller and return the [Response].
+    ///
+    /// The request is sent as-is, with its method, version, headers in their order and extensions, only the authority of its URI is replaced by the [crate::ClientUnixBuilder::authority] of the client and a `Host` header is added when it has none. Useful for requests built by other libraries. The response cache, coalescing, retries and cassette don't apply to it.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{Body, ClientUnix};
+    ///
+    /// pub async fn put_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let request = hyper::Request::put("/nolanv")
+    ///         .header("X-B", "1")
+    ///         .header("X-A", "2")
+    ///         .body(Body::from("Hello"))
+    ///         .expect("Request::builder");
+    ///     let response = client
+    ///         .send_http_request(request)
+    ///         .await
+    ///         .expect("client.send_http_request");
+    ///     println!("{}", response.status_code());
+    /// }
+    /// ```
+    pub async fn send_http_request(
+        &self,
+        mut request: Request<Body>,
+    ) -> Result<Response<Vec<u8>>, ErrorAndResponse> {
+        let start = Instant::now();
+        let request_defaults = &self.config().request_defaults;
+        let path_and_query = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path_and_query| path_and_query.as_str());
+        *request.uri_mut() =
+            request_defaults
+                .uri(path_and_query)
+                .parse()
+                .map_err(|e: InvalidUri| {
+                    ErrorAndResponse::InternalError(Error::RequestBuild(e.into()))
+                })?;
+        if let Some(host) = request_defaults.host()
+            && !request.headers().contains_key(HOST)
+        {
+            request.headers_mut().insert(HOST, host.clone());
+        }
+        if let Some(user_agent) = request_defaults.user_agent()
+            && !request.headers().contains_key(USER_AGENT)
+        {
+            request.headers_mut().insert(USER_AGENT, user_agent.clone());
+        }
+        let extensions = request.extensions().clone();
+        let (mut parts, body_response) = self
+            .exchange_request(request)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        parts.extensions.extend(extensions);
+        Ok(Response::new(parts, body_response.into(), start.elapsed()))
+    }
+
+    /// Send JSON HTTP request and return the [Response] with all its metadata **(feature = json)**
+    ///
+    /// Same as [ClientUnix::send_request_json] but the status code, version, headers, extensions, trailers and duration of the response are returned.
+    #[cfg(feature = "json")]
+    pub async fn send_request_json_full<
+        IN: Serialize,
+        OUT: DeserializeOwned,
+        ERR: DeserializeOwned,
+    >(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+    ) -> Result<Response<OUT>, ErrorAndResponseJson<ERR>> {
+        let start = Instant::now();
+        let (parts, body_response) = self
+            .send_json(endpoint, method, headers, body_request)
+            .await?;
+        let body_response =
+            json::from_bytes(body_response).map_err(ErrorAndResponseJson::InternalError)?;
+        Ok(Response::new(parts, body_response, start.elapsed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+
+    #[tokio::test]
+    async fn send_request_full() {
+        let socket_path = make_socket_path_test("response", "send_request_full");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\ntrailer: x-checksum\r\n\r\n8\r\nbaguette\r\n0\r\nx-checksum: 42\r\n\r\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let response = client
+            .send_request_full("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_full");
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.version(), Version::HTTP_11);
+        assert!(response.timing().expect("timing").total() <= response.elapsed());
+        assert_eq!(
+            response.trailers().and_then(|t| t.get("x-checksum")),
+            Some(&"42".parse().expect("HeaderValue"))
+        );
+        assert_eq!(response.into_body(), b"baguette");
+    }
+
+    #[tokio::test]
+    async fn send_request_unchecked() {
+        let socket_path = make_socket_path_test("response", "send_request_unchecked");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.0 404 Not Found\r\nx-reason: nolanv\r\ncontent-length: 7\r\n\r\nmissing",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let response = client
+            .send_request_unchecked("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_unchecked");
+
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(response.version(), Version::HTTP_10);
+        assert_eq!(
+            response.headers().get("x-reason").map(|v| v.as_bytes()),
+            Some(&b"nolanv"[..])
+        );
+        assert_eq!(response.into_body(), b"missing");
+    }
+
+    #[tokio::test]
+    async fn wire_bytes() {
+        let socket_path = make_socket_path_test("response", "wire_bytes");
+        let raw_response: &[u8] = b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\nx-bread: a\r\nX-BREAD: b\r\n\r\n8\r\nbaguette\r\n0\r\n\r\n";
+        let _server = RawServer::try_new(&socket_path, raw_response)
+            .await
+            .expect("RawServer::try_new");
+        let client = ClientUnix::builder(&socket_path)
+            .capture_wire(1024)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        for _ in 0..2 {
+            let response = client
+                .send_request_full("/nolanv", Method::GET, &[], None)
+                .await
+                .expect("client.send_request_full");
+            assert_eq!(response.wire_bytes(), Some(raw_response));
+            assert_eq!(response.into_body(), b"baguette");
+        }
+
+        let client = ClientUnix::builder(&socket_path)
+            .capture_wire(15)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let response = client
+            .send_request_full("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_full");
+        assert_eq!(response.wire_bytes(), Some(&b"HTTP/1.1 200 OK"[..]));
+    }
+
+    #[tokio::test]
+    async fn send_http_request() {
+        let socket_path = make_socket_path_test("response", "send_http_request");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .authority("nolanv.daemon")
+            .title_case_headers(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let request = Request::put("http://localhost/nolanv?name=v")
+            .header("X-B", "1")
+            .header("X-A", "2")
+            .extension(42u64)
+            .body(Body::from("Hello"))
+            .expect("Request::builder");
+        let response = client
+            .send_http_request(request)
+            .await
+            .expect("client.send_http_request");
+        assert_eq!(response.extensions().get::<u64>(), Some(&42));
+        let request = String::from_utf8(response.into_body()).expect("String::from_utf8");
+        assert!(request.starts_with("PUT http://nolanv.daemon/nolanv?name=v HTTP/1.1\r\n"));
+        assert!(request.contains("X-B: 1\r\nX-A: 2\r\nHost: nolanv.daemon\r\n"));
+        assert!(request.ends_with("\r\n\r\nHello"));
+    }
+
+    #[tokio::test]
+    async fn send_description_extensions() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct JobId(u64);
+
+        let socket_path = make_socket_path_test("response", "send_description_extensions");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let response = client
+            .send_description(
+                RequestDescription::new("/jobs", Method::POST)
+                    .body(Body::from("baguette"))
+                    .extension(JobId(42)),
+            )
+            .await
+            .expect("client.send_description");
+
+        assert_eq!(response.extensions().get::<JobId>(), Some(&JobId(42)));
+        assert!(response.body().ends_with(b"\r\n\r\nbaguette"));
+    }
+}
@@ -0,0 +1,108 @@
+use hyper::{StatusCode, header::HeaderValue};
+use std::time::{Duration, SystemTime};
+
+/// Policy used to retry requests rejected with `429 Too Many Requests` or `503 Service Unavailable`.
+///
+/// The delay between two attempts doubles after each attempt, starting at [RetryPolicy::base_delay]. When the response has a `Retry-After` header, its value is used instead. The delay is capped at [RetryPolicy::max_delay], so a server asking to wait longer is retried sooner than requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, in addition to the first attempt.
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+
+    /// Set the delay before the first retry when the server doesn't send `Retry-After`. Default is 100ms.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the longest delay the client accepts to wait before a retry. Default is 10s.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Delay before the retry following `attempt` (starting at 0), [None] when the request must not be retried.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        let delay = retry_after
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(attempt)));
+        Some(delay.min(self.max_delay))
+    }
+}
+
+pub(crate) fn is_retryable(status_code: StatusCode) -> bool {
+    status_code == StatusCode::TOO_MANY_REQUESTS || status_code == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Parse a `Retry-After` header, either a number of seconds or an HTTP-date compared to `now`.
+pub(crate) fn parse_retry_after(value: &HeaderValue, now: SystemTime) -> Option<Duration> {
+    let value = value.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_seconds_and_date() {
+        assert_eq!(
+            parse_retry_after(&HeaderValue::from_static("120"), SystemTime::now()),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after(
+                &HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+                SystemTime::now()
+            ),
+            Some(Duration::ZERO)
+        );
+
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        let delay = parse_retry_after(
+            &HeaderValue::from_str(&date).expect("HeaderValue"),
+            SystemTime::now(),
+        )
+        .expect("parse_retry_after");
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        assert_eq!(
+            parse_retry_after(&HeaderValue::from_static("soon"), SystemTime::now()),
+            None
+        );
+    }
+
+    #[test]
+    fn delay() {
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(10));
+        assert_eq!(policy.delay(0, None), Some(Duration::from_millis(10)));
+        assert_eq!(policy.delay(2, None), Some(Duration::from_millis(40)));
+        assert_eq!(policy.delay(3, None), None);
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(2))),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(60))),
+            Some(Duration::from_secs(10))
+        );
+    }
+}
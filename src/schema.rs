@@ -0,0 +1,171 @@
+use crate::{ClientUnix, Error, ErrorAndResponseJson, json};
+use hyper::{Method, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// JSON Schema compiled once, validating the responses of [ClientUnix::send_request_json_validated] **(feature = jsonschema)**
+///
+/// Useful with daemons drifting from their documented API, a response not matching the schema is reported with all its violations instead of a deserialization error on the first unexpected field.
+#[derive(Debug, Clone)]
+pub struct ResponseSchema(Arc<jsonschema::Validator>);
+
+/// A value of a response not matching its [ResponseSchema], in [Error::ResponseValidation] **(feature = jsonschema)**
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the invalid value in the response, like `/items/3/name`.
+    pub instance_path: String,
+    /// JSON pointer to the keyword of the schema which rejected it, like `/properties/items/items/required`.
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.instance_path.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{}: {}", self.instance_path, self.message),
+        }
+    }
+}
+
+impl ResponseSchema {
+    /// Compile the JSON Schema, its draft is detected from `$schema` (default is 2020-12). [Error::InvalidSchema] is returned when it is not a valid schema.
+    pub fn try_new(schema: &Value) -> Result<Self, Error> {
+        jsonschema::validator_for(schema)
+            .map(|validator| ResponseSchema(Arc::new(validator)))
+            .map_err(|e| Error::InvalidSchema(e.to_string()))
+    }
+
+    /// Validate a parsed response, [Error::ResponseValidation] lists every violation.
+    pub fn validate(&self, response: &Value) -> Result<(), Error> {
+        let violations: Vec<SchemaViolation> = self
+            .0
+            .iter_errors(response)
+            .map(|error| SchemaViolation {
+                instance_path: error.instance_path.to_string(),
+                schema_path: error.schema_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect();
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(Error::ResponseValidation(violations)),
+        }
+    }
+}
+
+impl ClientUnix {
+    /// Send JSON HTTP request and validate the response against `schema` before deserializing it **(feature = jsonschema)**
+    ///
+    /// Same as [ClientUnix::send_request_json] but [Error::ResponseValidation] is returned with every violation when the successful response doesn't match the schema. Unsuccessful responses are not validated.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method, ResponseSchema};
+    /// use serde_json::{Value, json};
+    ///
+    /// pub async fn get_validated_hello() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///     let schema = ResponseSchema::try_new(&json!({
+    ///         "type": "object",
+    ///         "required": ["hello"],
+    ///         "properties": {"hello": {"type": "string"}}
+    ///     }))
+    ///     .expect("ResponseSchema::try_new");
+    ///
+    ///     let (_, hello) = client
+    ///         .send_request_json_validated::<(), Value, Value>("/nolanv", Method::GET, &[], None, &schema)
+    ///         .await
+    ///         .expect("client.send_request_json_validated");
+    ///     println!("{}", hello["hello"]);
+    /// }
+    /// ```
+    pub async fn send_request_json_validated<
+        IN: Serialize,
+        OUT: DeserializeOwned,
+        ERR: DeserializeOwned,
+    >(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<&IN>,
+        schema: &ResponseSchema,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        let (parts, body_response) = self
+            .send_json(endpoint, method, headers, body_request)
+            .await?;
+
+        // Empty bodies are validated as `null`, like they are deserialized.
+        let response = match body_response.iter().all(u8::is_ascii_whitespace) {
+            true => Value::Null,
+            false => serde_json::from_slice(&body_response)
+                .map_err(|e| ErrorAndResponseJson::InternalError(Error::ResponseParsing(e)))?,
+        };
+        schema
+            .validate(&response)
+            .map_err(ErrorAndResponseJson::InternalError)?;
+        Ok((
+            parts.status,
+            json::from_bytes(body_response).map_err(ErrorAndResponseJson::InternalError)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::util::make_client_server;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn validate_response() {
+        let (_, client) = make_client_server("validate_response").await;
+        let schema = |hello_type: &str| {
+            ResponseSchema::try_new(&json!({
+                "type": "object",
+                "required": ["hello"],
+                "properties": {"hello": {"type": hello_type}}
+            }))
+            .expect("ResponseSchema::try_new")
+        };
+
+        let (status_code, response) = client
+            .send_request_json_validated::<(), Value, Value>(
+                "/json/nolanv",
+                Method::GET,
+                &[],
+                None,
+                &schema("string"),
+            )
+            .await
+            .expect("client.send_request_json_validated");
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, json!({"hello": "nolanv"}));
+
+        let result = client
+            .send_request_json_validated::<(), Value, Value>(
+                "/json/nolanv",
+                Method::GET,
+                &[],
+                None,
+                &schema("integer"),
+            )
+            .await;
+        match result {
+            Err(ErrorAndResponseJson::InternalError(Error::ResponseValidation(violations))) => {
+                assert_eq!(violations.len(), 1);
+                assert_eq!(violations[0].instance_path, "/hello");
+                assert_eq!(violations[0].schema_path, "/properties/hello/type");
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+
+        assert!(matches!(
+            ResponseSchema::try_new(&json!({"type": 42})),
+            Err(Error::InvalidSchema(_))
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+use hyper::{
+    Request, Response,
+    body::{Body as HttpBody, Incoming},
+    server::conn::http1,
+};
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+use std::{
+    io,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, DuplexStream},
+    net::{UnixListener, UnixStream},
+    task::JoinHandle,
+};
+use tower_service::Service;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// HTTP server listening on a UNIX domain socket **(feature = server)**
+///
+/// Serves any tower service, like an `axum::Router`, so both ends of a local HTTP IPC can come from this crate. The socket file is removed when the server is shut down or dropped.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::ServerUnix;
+/// use axum::{Router, routing::get};
+///
+/// pub async fn serve_hello() {
+///     let server = ServerUnix::builder("/tmp/unix.socket")
+///         .permissions(0o660)
+///         .try_serve(Router::new().route("/hello", get(|| async { "Hello" })))
+///         .await
+///         .expect("ServerUnixBuilder::try_serve");
+///
+///     // ...
+///     server.shutdown().await;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ServerUnix {
+    socket_path: PathBuf,
+    join_handle: JoinHandle<()>,
+}
+
+/// Builder used to configure a [ServerUnix] before binding its socket **(feature = server)**
+#[derive(Debug, Clone)]
+pub struct ServerUnixBuilder {
+    socket_path: PathBuf,
+    permissions: Option<u32>,
+}
+
+/// Error returned when a [ServerUnix] can't bind its socket **(feature = server)**
+#[derive(Debug)]
+pub enum ServerError {
+    /// Another server is listening on the socket.
+    SocketInUse(PathBuf),
+    /// The path exists and is not a socket, it is never removed.
+    NotASocket(PathBuf),
+    CreateSocketParentDir(io::Error),
+    RemoveStaleSocket(io::Error),
+    SocketBind(io::Error),
+    SetPermissions(io::Error),
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServerError::SocketInUse(path) => {
+                write!(f, "Another server is listening on {}", path.display())
+            }
+            ServerError::NotASocket(path) => {
+                write!(f, "{} exists and is not a unix socket", path.display())
+            }
+            ServerError::CreateSocketParentDir(e) => {
+                write!(f, "Failed to create the socket parent directory, {}", e)
+            }
+            ServerError::RemoveStaleSocket(e) => {
+                write!(f, "Failed to remove stale unix socket, {}", e)
+            }
+            ServerError::SocketBind(e) => write!(f, "Failed to bind unix socket, {}", e),
+            ServerError::SetPermissions(e) => {
+                write!(f, "Failed to set unix socket permissions, {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ServerError::SocketInUse(_) | ServerError::NotASocket(_) => None,
+            ServerError::CreateSocketParentDir(error)
+            | ServerError::RemoveStaleSocket(error)
+            | ServerError::SocketBind(error)
+            | ServerError::SetPermissions(error) => Some(error),
+        }
+    }
+}
+
+impl ServerUnix {
+    pub fn builder(socket_path: impl AsRef<Path>) -> ServerUnixBuilder {
+        ServerUnixBuilder {
+            socket_path: socket_path.as_ref().to_path_buf(),
+            permissions: None,
+        }
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Stop accepting connections and remove the socket file, connections already accepted are served until they are closed.
+    pub async fn shutdown(mut self) {
+        self.join_handle.abort();
+        let _ = (&mut self.join_handle).await;
+    }
+}
+
+impl Drop for ServerUnix {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+impl ServerUnixBuilder {
+    /// Set the permissions of the socket file, like `0o660` to only allow the owner and its group to connect. Default is the permissions given by the process umask.
+    ///
+    /// The socket is bound in a private directory next to the socket path and moved in place once its permissions are set, so clients never see it with the umask permissions.
+    pub fn permissions(mut self, mode: u32) -> Self {
+        self.permissions = Some(mode);
+        self
+    }
+
+    /// Bind the socket and serve `service` on every accepted connection.
+    ///
+    /// A socket file left by a stopped server is removed, [ServerError::SocketInUse] is returned if a server is still listening on it. Missing parent directories are created.
+    pub async fn try_serve<S, B>(self, service: S) -> Result<ServerUnix, ServerError>
+    where
+        S: Service<Request<Incoming>, Response = Response<B>> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+        S::Error: Into<BoxError>,
+        B: HttpBody + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<BoxError>,
+    {
+        let socket_path = self.socket_path;
+        remove_stale_socket(&socket_path).await?;
+        let listener = match self.permissions {
+            Some(mode) => bind_with_permissions(&socket_path, mode).await?,
+            None => UnixListener::bind(&socket_path).map_err(ServerError::SocketBind)?,
+        };
+
+        let accept_path = socket_path.clone();
+        let join_handle = tokio::task::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => serve_connection(stream, service.clone()),
+                    // Like running out of file descriptors, accepting again may succeed later.
+                    Err(e) => {
+                        log::error!("Failed to accept a connection on {:?}, {}", accept_path, e);
+                        tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    }
+                }
+            }
+        });
+
+        Ok(ServerUnix {
+            socket_path,
+            join_handle,
+        })
+    }
+}
+
+/// Serve `service` on a single connection, in its own [tokio::task].
+fn serve_connection<I, S, B>(io: I, service: S)
+where
+    I: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    S: Service<Request<Incoming>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    let service = TowerToHyperService::new(service);
+    tokio::task::spawn(async move {
+        let _ = http1::Builder::new()
+            .serve_connection(TokioIo::new(io), service)
+            .with_upgrades()
+            .await;
+    });
+}
+
+/// Pause after a failed accept, so a lasting error doesn't spin the accept loop.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Bind the socket in a private directory next to `socket_path`, then move it in place once its permissions are set, so it is never reachable with the permissions given by the umask.
+async fn bind_with_permissions(socket_path: &Path, mode: u32) -> Result<UnixListener, ServerError> {
+    static PRIVATE_DIRS: AtomicUsize = AtomicUsize::new(0);
+    let private_dir = socket_path.parent().unwrap_or(Path::new("")).join(format!(
+        ".bind-{}-{}",
+        std::process::id(),
+        PRIVATE_DIRS.fetch_add(1, Ordering::Relaxed)
+    ));
+    tokio::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .await
+        .map_err(ServerError::SocketBind)?;
+
+    let bind_path = private_dir.join("socket");
+    let result = async {
+        let listener = UnixListener::bind(&bind_path).map_err(ServerError::SocketBind)?;
+        tokio::fs::set_permissions(&bind_path, std::fs::Permissions::from_mode(mode))
+            .await
+            .map_err(ServerError::SetPermissions)?;
+        tokio::fs::rename(&bind_path, socket_path)
+            .await
+            .map_err(ServerError::SocketBind)?;
+        Ok(listener)
+    }
+    .await;
+    let _ = tokio::fs::remove_dir_all(&private_dir).await;
+    result
+}
+
+/// Size of the in-memory buffer of each direction of an in-process connection.
+const IN_PROCESS_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Open connections to a service running in the same process, used by [crate::ClientUnix::builder_in_process].
+#[derive(Clone)]
+pub(crate) struct InProcessConnector(Arc<dyn Fn() -> DuplexStream + Send + Sync>);
+
+impl std::fmt::Debug for InProcessConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("InProcessConnector").finish()
+    }
+}
+
+impl InProcessConnector {
+    pub(crate) fn new<S, B>(service: S) -> Self
+    where
+        S: Service<Request<Incoming>, Response = Response<B>> + Clone + Send + Sync + 'static,
+        S::Future: Send + 'static,
+        S::Error: Into<BoxError>,
+        B: HttpBody + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<BoxError>,
+    {
+        InProcessConnector(Arc::new(move || {
+            let (client, server) = tokio::io::duplex(IN_PROCESS_BUFFER_SIZE);
+            serve_connection(server, service.clone());
+            client
+        }))
+    }
+
+    /// Start serving a new connection, returns the client end of its pipe.
+    pub(crate) fn connect(&self) -> DuplexStream {
+        (self.0)()
+    }
+}
+
+async fn remove_stale_socket(socket_path: &Path) -> Result<(), ServerError> {
+    let metadata = match tokio::fs::symlink_metadata(socket_path).await {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            if let Some(parent) = socket_path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(ServerError::CreateSocketParentDir)?;
+            }
+            return Ok(());
+        }
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(ServerError::NotASocket(socket_path.to_path_buf()));
+    }
+    if UnixStream::connect(socket_path).await.is_ok() {
+        return Err(ServerError::SocketInUse(socket_path.to_path_buf()));
+    }
+    tokio::fs::remove_file(socket_path)
+        .await
+        .map_err(ServerError::RemoveStaleSocket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientUnix, Method, StatusCode, test_helpers::util::make_socket_path_test};
+    use axum::{Router, routing::get};
+
+    #[tokio::test]
+    async fn serve_and_replace_stale_socket() {
+        let socket_path = make_socket_path_test("server", "serve_and_replace_stale_socket");
+        let _ = std::fs::remove_file(&socket_path);
+        let router = Router::new().route("/hello", get(|| async { "Hello nolanv" }));
+
+        // Socket file left by a crashed server.
+        drop(std::os::unix::net::UnixListener::bind(&socket_path).expect("UnixListener::bind"));
+        let server = ServerUnix::builder(&socket_path)
+            .permissions(0o600)
+            .try_serve(router.clone())
+            .await
+            .expect("ServerUnixBuilder::try_serve");
+        let mode = std::fs::metadata(&socket_path)
+            .expect("fs::metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The private directory the socket was bound in is removed.
+        let parent = Path::new(&socket_path).parent().expect("parent");
+        assert!(
+            std::fs::read_dir(parent)
+                .expect("fs::read_dir")
+                .all(|entry| !entry
+                    .expect("entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(".bind-"))
+        );
+
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+        let (status_code, response) = client
+            .send_request("/hello", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, b"Hello nolanv");
+
+        let result = ServerUnix::builder(&socket_path).try_serve(router).await;
+        assert!(matches!(result, Err(ServerError::SocketInUse(_))));
+
+        server.shutdown().await;
+        assert!(!Path::new(&socket_path).exists());
+    }
+
+    #[tokio::test]
+    async fn in_process_router() {
+        let router = Router::new().route("/hello", get(|| async { "Hello nolanv" }));
+        let client = ClientUnix::builder_in_process(router)
+            .max_connections(2)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (first, second) = tokio::join!(
+            client.send_request("/hello", Method::GET, &[], None),
+            client.send_request("/hello", Method::GET, &[], None)
+        );
+        for result in [first, second] {
+            let (status_code, response) = result.expect("client.send_request");
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(response, b"Hello nolanv");
+        }
+
+        let result = client.send_request("/nope", Method::GET, &[], None).await;
+        assert!(matches!(
+            result,
+            Err(crate::ErrorAndResponse::ResponseUnsuccessful(
+                StatusCode::NOT_FOUND,
+                _
+            ))
+        ));
+        assert_eq!(client.stats().connections, 2);
+    }
+}
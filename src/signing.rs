@@ -0,0 +1,79 @@
+use crate::Body;
+use crate::Error;
+use http_body_util::BodyExt;
+use hyper::{Request, body::Bytes};
+use std::sync::Arc;
+
+type SignerFn = dyn Fn(&mut Request<Bytes>) + Send + Sync;
+
+/// Hook set with [crate::ClientUnixBuilder::request_signer], called on every request before it is sent.
+#[derive(Clone)]
+pub(crate) struct RequestSigner(Arc<SignerFn>);
+
+impl std::fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("RequestSigner").finish()
+    }
+}
+
+impl RequestSigner {
+    pub(crate) fn new(signer: impl Fn(&mut Request<Bytes>) + Send + Sync + 'static) -> Self {
+        RequestSigner(Arc::new(signer))
+    }
+
+    /// Buffer the body so the hook can read it, then let it edit the request.
+    pub(crate) async fn sign(&self, request: Request<Body>) -> Result<Request<Body>, Error> {
+        let (parts, body) = request.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(Error::RequestCollect)?
+            .to_bytes();
+
+        let mut request = Request::from_parts(parts, body);
+        (self.0)(&mut request);
+        Ok(request.map(Body::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+    use hyper::Method;
+
+    #[tokio::test]
+    async fn request_signer() {
+        let socket_path = make_socket_path_test("signing", "request_signer");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::builder(&socket_path)
+            .request_signer(|request| {
+                let signature = format!(
+                    "{} {} {}",
+                    request.method(),
+                    request.uri().path(),
+                    request.body().len()
+                );
+                request
+                    .headers_mut()
+                    .insert("x-signature", signature.parse().expect("HeaderValue"));
+            })
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+
+        let (_, response) = client
+            .send_request("/nolanv", Method::POST, &[], Some(Body::from("baguette")))
+            .await
+            .expect("client.send_request");
+        let response = String::from_utf8(response).expect("String::from_utf8");
+
+        assert!(response.contains("x-signature: POST /nolanv 8\r\n"));
+        assert!(response.ends_with("\r\n\r\nbaguette"));
+    }
+}
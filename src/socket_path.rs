@@ -0,0 +1,146 @@
+use crate::{Error, builder::ClientConfig};
+use std::{
+    io,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+/// Size of `sockaddr_un.sun_path`, the path must be shorter to leave room for its nul terminator.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SUN_PATH_LEN: usize = 108;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SUN_PATH_LEN: usize = 104;
+
+/// Check the socket path before connecting, so a misconfigured path is reported with a precise [Error] instead of the io error of `connect`.
+///
+/// A missing socket is still reported as [Error::SocketConnectionInitiation], the server may not be started yet. Returns the path to connect to, canonicalized when [ClientConfig::canonicalize_socket_path] is enabled.
+pub(crate) async fn validate(socket_path: &Path, config: &ClientConfig) -> Result<PathBuf, Error> {
+    let socket_path = match config.canonicalize_socket_path {
+        true => tokio::fs::canonicalize(socket_path)
+            .await
+            .map_err(|e| connect_error(socket_path, e))?,
+        false => socket_path.to_path_buf(),
+    };
+    if socket_path.as_os_str().len() >= SUN_PATH_LEN {
+        return Err(Error::PathTooLong(socket_path));
+    }
+    if config.refuse_world_writable_parents {
+        for parent in socket_path.ancestors().skip(1) {
+            if parent.as_os_str().is_empty() {
+                continue;
+            }
+            let mode = tokio::fs::metadata(parent)
+                .await
+                .map_err(|e| connect_error(&socket_path, e))?
+                .permissions()
+                .mode();
+            // World-writable without the sticky bit.
+            if mode & 0o1002 == 0o0002 {
+                return Err(Error::WorldWritableParent(parent.to_path_buf()));
+            }
+        }
+    }
+    let metadata = tokio::fs::metadata(&socket_path)
+        .await
+        .map_err(|e| connect_error(&socket_path, e))?;
+    if !metadata.file_type().is_socket() {
+        return Err(Error::NotASocket(socket_path));
+    }
+    Ok(socket_path)
+}
+
+/// Wrap an io error of `connect`, using [Error::PermissionDenied] when the socket or one of its parent directories is not accessible.
+pub(crate) fn connect_error(socket_path: &Path, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied(socket_path.to_path_buf()),
+        _ => Error::SocketConnectionInitiation(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, ErrorKind,
+        test_helpers::{server::Server, util::make_socket_path_test},
+    };
+
+    #[tokio::test]
+    async fn invalid_socket_paths() {
+        let socket_path = format!("/tmp/{}.socket", "a".repeat(SUN_PATH_LEN));
+        let result = ClientUnix::try_new(&socket_path).await;
+        assert!(
+            matches!(result, Err(Error::PathTooLong(path)) if path.as_os_str() == socket_path.as_str())
+        );
+
+        let file_path = make_socket_path_test("socket_path", "invalid_socket_paths");
+        std::fs::create_dir_all("/tmp/http_client_unix_domain_socket").expect("fs::create_dir_all");
+        std::fs::write(&file_path, b"nolanv").expect("fs::write");
+        let error = ClientUnix::try_new(&file_path)
+            .await
+            .expect_err("ClientUnix::try_new");
+        assert!(matches!(error, Error::NotASocket(_)));
+        assert_eq!(error.kind(), ErrorKind::Connect);
+        assert!(!error.is_retryable());
+        let _ = std::fs::remove_file(&file_path);
+
+        let missing_path = make_socket_path_test("socket_path", "missing");
+        let _ = std::fs::remove_file(&missing_path);
+        assert!(matches!(
+            ClientUnix::try_new(&missing_path).await,
+            Err(Error::SocketConnectionInitiation(_))
+        ));
+        assert!(matches!(
+            connect_error(
+                Path::new(&missing_path),
+                io::Error::from(io::ErrorKind::PermissionDenied)
+            ),
+            Error::PermissionDenied(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn world_writable_parents() {
+        let shared_dir = "/tmp/http_client_unix_domain_socket/world_writable";
+        let socket_path = format!("{}/nolanv.socket", shared_dir);
+        let _server = Server::try_new(&socket_path)
+            .await
+            .expect("Server::try_new");
+        std::fs::set_permissions(shared_dir, std::fs::Permissions::from_mode(0o777))
+            .expect("fs::set_permissions");
+        let link_path = make_socket_path_test("socket_path", "world_writable_parents");
+        let _ = std::fs::remove_file(&link_path);
+        std::os::unix::fs::symlink(&socket_path, &link_path).expect("fs::symlink");
+
+        let result = ClientUnix::builder(&socket_path)
+            .refuse_world_writable_parents(true)
+            .try_build()
+            .await;
+        assert!(
+            matches!(result, Err(Error::WorldWritableParent(path)) if path == Path::new(shared_dir))
+        );
+
+        // The symlink hides the shared directory unless it is resolved.
+        ClientUnix::builder(&link_path)
+            .refuse_world_writable_parents(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+        let result = ClientUnix::builder(&link_path)
+            .canonicalize_socket_path(true)
+            .refuse_world_writable_parents(true)
+            .try_build()
+            .await;
+        assert!(matches!(result, Err(Error::WorldWritableParent(_))));
+
+        // Only the owner of a sticky directory can replace its entries.
+        std::fs::set_permissions(shared_dir, std::fs::Permissions::from_mode(0o1777))
+            .expect("fs::set_permissions");
+        ClientUnix::builder(&link_path)
+            .canonicalize_socket_path(true)
+            .refuse_world_writable_parents(true)
+            .try_build()
+            .await
+            .expect("ClientUnixBuilder::try_build");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::histogram::{LatencyHistogram, LatencyRecorder};
+use hyper::{Method, StatusCode};
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+/// Cumulative counters of a client and all its clones, returned by [crate::ClientUnix::stats].
+///
+/// Only the requests sent to the server are counted, each retry is counted as a request, responses served from the cache or a cassette are not.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientStats {
+    /// Requests sent, per method.
+    pub requests: HashMap<Method, u64>,
+    /// Responses received, per status class.
+    pub responses: ResponseCounts,
+    /// Requests that failed without a response.
+    pub errors: u64,
+    /// Connections opened to the server, including the first one of each socket.
+    pub connections: u64,
+    /// Connections opened to replace a connection closed by the server.
+    pub reconnects: u64,
+    /// Bytes written on the sockets.
+    pub bytes_sent: u64,
+    /// Bytes read from the sockets.
+    pub bytes_received: u64,
+    /// Requests currently using or waiting for a connection.
+    pub in_flight: usize,
+    /// Distribution of the request latencies, with their percentiles.
+    pub latency: LatencyHistogram,
+    /// Connections of the pools of every socket, at the time of the snapshot.
+    pub pool: PoolStats,
+}
+
+/// Gauges of the connection pools, to tune [crate::ClientUnixBuilder::max_connections] and the idle connection options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Connections open, idle or in use.
+    pub total: usize,
+    /// Connections waiting in the pool for the next request.
+    pub idle: usize,
+    /// Connections borrowed by a request.
+    pub in_use: usize,
+    /// Requests waiting for a connection to be freed.
+    pub waiting: usize,
+}
+
+/// Number of responses per status class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseCounts {
+    /// `1xx`
+    pub informational: u64,
+    /// `2xx`
+    pub success: u64,
+    /// `3xx`
+    pub redirection: u64,
+    /// `4xx`
+    pub client_error: u64,
+    /// `5xx`
+    pub server_error: u64,
+}
+
+impl ResponseCounts {
+    pub fn total(&self) -> u64 {
+        self.informational + self.success + self.redirection + self.client_error + self.server_error
+    }
+}
+
+/// Counters shared by every connection of a client.
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    requests: Mutex<HashMap<Method, u64>>,
+    responses: [AtomicU64; 5],
+    errors: AtomicU64,
+    connections: AtomicU64,
+    reconnects: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    latency: LatencyRecorder,
+}
+
+impl StatsRecorder {
+    pub(crate) fn request(&self, method: &Method) {
+        if let Ok(mut requests) = self.requests.lock() {
+            *requests.entry(method.clone()).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn response(&self, status: Option<StatusCode>) {
+        let counter = match status.map(|status| status.as_u16() / 100) {
+            Some(class @ 1..=5) => &self.responses[usize::from(class) - 1],
+            _ => &self.errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn connection(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn sent(&self, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn latency(&self, latency: Duration) {
+        self.latency.record(latency);
+    }
+
+    pub(crate) fn snapshot(&self, in_flight: usize, pool: PoolStats) -> ClientStats {
+        let response = |class: usize| self.responses[class].load(Ordering::Relaxed);
+        ClientStats {
+            requests: self
+                .requests
+                .lock()
+                .map(|requests| requests.clone())
+                .unwrap_or_default(),
+            responses: ResponseCounts {
+                informational: response(0),
+                success: response(1),
+                redirection: response(2),
+                client_error: response(3),
+                server_error: response(4),
+            },
+            errors: self.errors.load(Ordering::Relaxed),
+            connections: self.connections.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            in_flight,
+            latency: self.latency.snapshot(),
+            pool,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::util::make_client_server;
+
+    #[tokio::test]
+    async fn stats() {
+        let (_, client) = make_client_server("stats").await;
+
+        for endpoint in ["/nolanv", "/nolanv2", "/nolanv/nope"] {
+            let _ = client.send_request(endpoint, Method::GET, &[], None).await;
+        }
+
+        let stats = client.stats();
+        assert_eq!(stats.requests, HashMap::from([(Method::GET, 3)]));
+        assert_eq!(
+            stats.responses,
+            ResponseCounts {
+                success: 2,
+                client_error: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(stats.errors, 0);
+        assert_eq!(stats.connections, 1);
+        assert!(stats.bytes_sent > 0 && stats.bytes_received > 0);
+        assert_eq!(stats.in_flight, 0);
+        assert_eq!(stats.latency.count(), 3);
+        assert!(stats.latency.p99().is_some());
+        assert_eq!(
+            stats.pool,
+            PoolStats {
+                total: 1,
+                idle: 1,
+                in_use: 0,
+                waiting: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{ErrorAndResponse, Response};
+use hyper::StatusCode;
+
+/// Class of a status code, given by its first digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusClass {
+    /// `1xx`
+    Informational,
+    /// `2xx`
+    Success,
+    /// `3xx`
+    Redirection,
+    /// `4xx`, the request was rejected and must be changed before being sent again.
+    ClientError,
+    /// `5xx`, the server failed to handle the request.
+    ServerError,
+}
+
+impl StatusClass {
+    /// Class of `status_code`, non-standard codes over `599` are server errors.
+    pub fn of(status_code: StatusCode) -> Self {
+        match status_code.as_u16() {
+            100..=199 => StatusClass::Informational,
+            200..=299 => StatusClass::Success,
+            300..=399 => StatusClass::Redirection,
+            400..=499 => StatusClass::ClientError,
+            _ => StatusClass::ServerError,
+        }
+    }
+}
+
+impl From<StatusCode> for StatusClass {
+    fn from(status_code: StatusCode) -> Self {
+        StatusClass::of(status_code)
+    }
+}
+
+impl ErrorAndResponse {
+    /// Status code of the unsuccessful response, [None] for internal errors.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            ErrorAndResponse::InternalError(_) => None,
+            ErrorAndResponse::ResponseUnsuccessful(status_code, _)
+            | ErrorAndResponse::RetriesExhausted(status_code, _, _) => Some(*status_code),
+        }
+    }
+
+    /// Class of the status code of the unsuccessful response, [None] for internal errors.
+    pub fn status_class(&self) -> Option<StatusClass> {
+        self.status_code().map(StatusClass::of)
+    }
+}
+
+impl<T> Response<T> {
+    pub fn status_class(&self) -> StatusClass {
+        StatusClass::of(self.status_code())
+    }
+}
+
+impl Response<Vec<u8>> {
+    /// Turn a response which isn't `2xx` into [ErrorAndResponse::ResponseUnsuccessful], for the methods returning the responses whatever their status like [crate::ClientUnix::send_raw_bytes].
+    pub fn ok_or_status(self) -> Result<Self, ErrorAndResponse> {
+        match self.status_code().is_success() {
+            true => Ok(self),
+            false => Err(ErrorAndResponse::ResponseUnsuccessful(
+                self.status_code(),
+                self.into_body(),
+            )),
+        }
+    }
+}
+
+/// Combinators handling the unsuccessful responses of a request by [StatusClass].
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::{ClientUnix, Method, StatusResultExt};
+///
+/// pub async fn get_optional_config() {
+///     let client = ClientUnix::try_new("/tmp/unix.socket")
+///         .await
+///         .expect("ClientUnix::try_new");
+///
+///     // 4xx responses fall back to an empty config, 5xx and internal errors are still errors.
+///     let config = client
+///         .send_request("/config", Method::GET, &[], None)
+///         .await
+///         .map(|(_, body)| body)
+///         .map_client_error(|_, _| Vec::new())
+///         .expect("client.send_request");
+///     println!("{} bytes", config.len());
+/// }
+/// ```
+pub trait StatusResultExt<T> {
+    /// Replace the `4xx` unsuccessful responses by the value built from their status code and body.
+    fn map_client_error(self, f: impl FnOnce(StatusCode, Vec<u8>) -> T) -> Self;
+
+    /// Replace the `5xx` unsuccessful responses by the value built from their status code and body.
+    fn map_server_error(self, f: impl FnOnce(StatusCode, Vec<u8>) -> T) -> Self;
+}
+
+impl<T> StatusResultExt<T> for Result<T, ErrorAndResponse> {
+    fn map_client_error(self, f: impl FnOnce(StatusCode, Vec<u8>) -> T) -> Self {
+        map_class(self, StatusClass::ClientError, f)
+    }
+
+    fn map_server_error(self, f: impl FnOnce(StatusCode, Vec<u8>) -> T) -> Self {
+        map_class(self, StatusClass::ServerError, f)
+    }
+}
+
+fn map_class<T>(
+    result: Result<T, ErrorAndResponse>,
+    class: StatusClass,
+    f: impl FnOnce(StatusCode, Vec<u8>) -> T,
+) -> Result<T, ErrorAndResponse> {
+    match result {
+        Err(ErrorAndResponse::ResponseUnsuccessful(status_code, body))
+        | Err(ErrorAndResponse::RetriesExhausted(status_code, _, body))
+            if StatusClass::of(status_code) == class =>
+        {
+            Ok(f(status_code, body))
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, Method,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+
+    #[test]
+    fn status_classes() {
+        assert_eq!(
+            StatusClass::of(StatusCode::SWITCHING_PROTOCOLS),
+            StatusClass::Informational
+        );
+        assert_eq!(
+            StatusClass::of(StatusCode::NO_CONTENT),
+            StatusClass::Success
+        );
+        assert_eq!(
+            StatusClass::from(StatusCode::NOT_MODIFIED),
+            StatusClass::Redirection
+        );
+        assert_eq!(
+            StatusClass::of(StatusCode::NOT_FOUND),
+            StatusClass::ClientError
+        );
+        assert_eq!(
+            StatusClass::of(StatusCode::from_u16(599).expect("StatusCode")),
+            StatusClass::ServerError
+        );
+
+        let not_found: Result<Vec<u8>, _> = Err(ErrorAndResponse::ResponseUnsuccessful(
+            StatusCode::NOT_FOUND,
+            b"nope".to_vec(),
+        ));
+        assert_eq!(
+            not_found.map_client_error(|_, body| body).ok(),
+            Some(b"nope".to_vec())
+        );
+        let unavailable: Result<Vec<u8>, _> = Err(ErrorAndResponse::ResponseUnsuccessful(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Vec::new(),
+        ));
+        let unavailable = unavailable.map_client_error(|_, body| body);
+        assert_eq!(
+            unavailable
+                .as_ref()
+                .err()
+                .and_then(ErrorAndResponse::status_class),
+            Some(StatusClass::ServerError)
+        );
+        assert_eq!(
+            unavailable
+                .map_server_error(|status_code, _| status_code.as_str().into())
+                .ok(),
+            Some(b"503".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn ok_or_status() {
+        let socket_path = make_socket_path_test("status", "ok_or_status");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 404 Not Found\r\ncontent-length: 4\r\n\r\nnope",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let response = client
+            .send_raw_bytes(b"GET /nolanv HTTP/1.1\r\n\r\n")
+            .await
+            .expect("client.send_raw_bytes");
+        assert_eq!(response.status_class(), StatusClass::ClientError);
+        assert!(matches!(
+            response.ok_or_status(),
+            Err(ErrorAndResponse::ResponseUnsuccessful(StatusCode::NOT_FOUND, body)) if body == b"nope"
+        ));
+        assert!(
+            client
+                .send_request("/nolanv", Method::GET, &[], None)
+                .await
+                .map_client_error(|status_code, _| (status_code, Vec::new()))
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::Body;
+use crate::{ClientUnix, Error, body::ResponseBody, error::ErrorAndResponse};
+use futures_util::{Stream, stream};
+use hyper::{Method, StatusCode, header::CONTENT_TYPE, http::Extensions};
+
+const HEADER_LENGTH: usize = 8;
+
+/// Chunk of a Docker log or attach stream, tagged with the stream it was written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogChunk {
+    Stdin(Vec<u8>),
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// Incremental decoder of the Docker stdcopy framing, fed with chunks of the body.
+///
+/// Each frame is an 8 bytes header, the stream type followed by 3 zero bytes and the big-endian length of the payload.
+#[derive(Debug, Default)]
+struct Demultiplexer {
+    buffer: Vec<u8>,
+}
+
+impl Demultiplexer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Next complete frame, [None] when more bytes are needed.
+    fn next_chunk(&mut self) -> Result<Option<LogChunk>, Error> {
+        let Some(header) = self.buffer.get(..HEADER_LENGTH) else {
+            return Ok(None);
+        };
+        let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let stream_type = header[0];
+        if self.buffer.len() < HEADER_LENGTH + length {
+            return Ok(None);
+        }
+
+        let payload = self.buffer[HEADER_LENGTH..HEADER_LENGTH + length].to_vec();
+        self.buffer.drain(..HEADER_LENGTH + length);
+        match stream_type {
+            0 => Ok(Some(LogChunk::Stdin(payload))),
+            1 => Ok(Some(LogChunk::Stdout(payload))),
+            2 => Ok(Some(LogChunk::Stderr(payload))),
+            _ => Err(Error::StdcopyParsing("unknown stream type")),
+        }
+    }
+
+    fn finish(&self) -> Result<(), Error> {
+        match self.buffer.is_empty() {
+            true => Ok(()),
+            false => Err(Error::StdcopyParsing(
+                "unexpected end of multiplexed stream",
+            )),
+        }
+    }
+}
+
+impl ClientUnix {
+    /// Send a raw HTTP request and demultiplex the Docker stdcopy framed response.
+    ///
+    /// Used by Docker `/containers/{id}/logs` and `/containers/{id}/attach` endpoints, stdout and stderr chunks are yielded as soon as they are received. Containers started with a TTY are not multiplexed (`application/vnd.docker.raw-stream`), their output is yielded as [LogChunk::Stdout]. The connection is released once the stream is fully read, dropping it before closes the connection. Unlike [ClientUnix::send_request], the response is not cached, coalesced nor retried.
+    /// # Example
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use http_client_unix_domain_socket::{ClientUnix, LogChunk, Method};
+    ///
+    /// pub async fn get_logs() {
+    ///     let client = ClientUnix::try_new("/var/run/docker.sock")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, logs) = client
+    ///         .send_request_stdcopy(
+    ///             "/containers/nolanv/logs?stdout=true&stderr=true",
+    ///             Method::GET,
+    ///             &[],
+    ///             None,
+    ///         )
+    ///         .await
+    ///         .expect("client.send_request_stdcopy");
+    ///     let mut logs = std::pin::pin!(logs);
+    ///     while let Some(chunk) = logs.next().await {
+    ///         match chunk.expect("chunk") {
+    ///             LogChunk::Stderr(line) => eprint!("{}", String::from_utf8_lossy(&line)),
+    ///             LogChunk::Stdout(line) | LogChunk::Stdin(line) => {
+    ///                 print!("{}", String::from_utf8_lossy(&line))
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_request_stdcopy(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<
+        (
+            StatusCode,
+            impl Stream<Item = Result<LogChunk, Error>> + Send + 'static,
+        ),
+        ErrorAndResponse,
+    > {
+        let (parts, body_response) = self
+            .start(endpoint, method, headers, body_request, &Extensions::new())
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            let body_response = body_response
+                .collect()
+                .await
+                .map_err(ErrorAndResponse::InternalError)?;
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        let raw = parts
+            .headers
+            .get(CONTENT_TYPE)
+            .is_some_and(|content_type| content_type == "application/vnd.docker.raw-stream");
+        Ok((parts.status, chunks(body_response, raw)))
+    }
+}
+
+fn chunks(
+    body_response: ResponseBody,
+    raw: bool,
+) -> impl Stream<Item = Result<LogChunk, Error>> + Send + 'static {
+    stream::unfold(
+        Some((body_response, Demultiplexer::default())),
+        move |state| async move {
+            let (mut body_response, mut demultiplexer) = state?;
+            loop {
+                match demultiplexer.next_chunk() {
+                    Ok(Some(chunk)) => {
+                        return Some((Ok(chunk), Some((body_response, demultiplexer))));
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Some((Err(e), None)),
+                }
+                match body_response.data().await {
+                    Some(Ok(data)) if raw => {
+                        let chunk = LogChunk::Stdout(data.into());
+                        return Some((Ok(chunk), Some((body_response, demultiplexer))));
+                    }
+                    Some(Ok(data)) => demultiplexer.push(&data),
+                    Some(Err(e)) => return Some((Err(e), None)),
+                    None => return demultiplexer.finish().err().map(|e| (Err(e), None)),
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn demultiplex_split_frames() {
+        let mut demultiplexer = Demultiplexer::default();
+        demultiplexer.push(b"\x01\0\0\0\0\0\0\x03out\x02\0\0");
+        assert_eq!(
+            demultiplexer.next_chunk().expect("next_chunk"),
+            Some(LogChunk::Stdout(b"out".to_vec()))
+        );
+        assert_eq!(demultiplexer.next_chunk().expect("next_chunk"), None);
+        assert!(demultiplexer.finish().is_err());
+
+        demultiplexer.push(b"\0\0\0\0\x03err");
+        assert_eq!(
+            demultiplexer.next_chunk().expect("next_chunk"),
+            Some(LogChunk::Stderr(b"err".to_vec()))
+        );
+        assert!(demultiplexer.finish().is_ok());
+
+        demultiplexer.push(b"\x07\0\0\0\0\0\0\0");
+        assert!(demultiplexer.next_chunk().is_err());
+    }
+
+    #[tokio::test]
+    async fn send_request_stdcopy() {
+        let socket_path = make_socket_path_test("stdcopy", "send_request_stdcopy");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-type: application/vnd.docker.multiplexed-stream\r\ncontent-length: 25\r\n\r\n\x01\0\0\0\0\0\0\x04out\n\x02\0\0\0\0\0\0\x05err!\n",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        for _ in 0..2 {
+            let (status_code, chunks) = client
+                .send_request_stdcopy("/containers/nolanv/logs", Method::GET, &[], None)
+                .await
+                .expect("client.send_request_stdcopy");
+            let chunks: Vec<LogChunk> = chunks.map(|chunk| chunk.expect("chunk")).collect().await;
+
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(
+                chunks,
+                vec![
+                    LogChunk::Stdout(b"out\n".to_vec()),
+                    LogChunk::Stderr(b"err!\n".to_vec())
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    builder::ClientConfig,
+    stats::StatsRecorder,
+    throttle::{Bandwidth, Throttle},
+    wire::WireRecorder,
+};
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::UnixStream,
+    time::{Sleep, sleep},
+};
+
+const HEAD_END: &[u8] = b"\r\n\r\n";
+
+/// Heads larger than this are not recorded, their original header case is lost.
+pub(crate) const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Raw response head recorded while it is read from the socket.
+#[derive(Debug, Default)]
+pub(crate) struct HeadRecorder {
+    buffer: Vec<u8>,
+    complete: bool,
+    /// The head is larger than [MAX_HEAD_SIZE], nothing is recorded until the next reset.
+    oversized: bool,
+}
+
+impl HeadRecorder {
+    /// Forget the previous head, must be called before sending a new request.
+    pub(crate) fn reset(&mut self) {
+        self.buffer.clear();
+        self.complete = false;
+        self.oversized = false;
+    }
+
+    /// Take the recorded head if it was fully read.
+    pub(crate) fn take(&mut self) -> Option<Vec<u8>> {
+        match self.complete {
+            true => Some(std::mem::take(&mut self.buffer)),
+            false => None,
+        }
+    }
+
+    fn record(&mut self, mut bytes: &[u8]) {
+        while !self.complete && !self.oversized && !bytes.is_empty() {
+            let recorded = self.buffer.len();
+            let start = recorded.saturating_sub(HEAD_END.len() - 1);
+            // One byte over the limit tells an oversized head apart.
+            let room = (MAX_HEAD_SIZE + 1 - recorded).min(bytes.len());
+            self.buffer.extend_from_slice(&bytes[..room]);
+
+            let Some(position) = self.buffer[start..]
+                .windows(HEAD_END.len())
+                .position(|window| window == HEAD_END)
+            else {
+                if self.buffer.len() > MAX_HEAD_SIZE {
+                    self.oversized = true;
+                    self.buffer = Vec::new();
+                }
+                return;
+            };
+            let end = start + position + HEAD_END.len();
+            self.buffer.truncate(end);
+            bytes = &bytes[end - recorded..];
+
+            // Informational (1xx) heads are followed by the final response head.
+            match is_informational(&self.buffer) {
+                true => self.buffer.clear(),
+                false => self.complete = true,
+            }
+        }
+    }
+}
+
+/// Whether the head is an informational (1xx) response, other than `101 Switching Protocols` after which the connection no longer speaks HTTP.
+fn is_informational(head: &[u8]) -> bool {
+    head.get(9) == Some(&b'1') && head.get(9..12) != Some(b"101")
+}
+
+/// Inactivity timer, restarted every time the stream makes progress.
+#[derive(Debug)]
+struct IdleTimer {
+    timeout: Option<Duration>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl IdleTimer {
+    fn new(timeout: Option<Duration>) -> Self {
+        IdleTimer {
+            timeout,
+            sleep: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.sleep = None;
+    }
+
+    /// Poll the timer while the stream is pending, returns an error once it elapsed.
+    fn poll_elapsed(&mut self, cx: &mut Context<'_>, direction: &str) -> Poll<io::Result<()>> {
+        let Some(timeout) = self.timeout else {
+            return Poll::Pending;
+        };
+        let sleep = self.sleep.get_or_insert_with(|| Box::pin(sleep(timeout)));
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                self.sleep = None;
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("no {} activity during {:?}", direction, timeout),
+                )))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Byte stream to the server.
+#[derive(Debug)]
+pub(crate) enum Socket {
+    Unix(UnixStream),
+    /// Pipe to a service running in the same process **(feature = server)**
+    #[cfg(feature = "server")]
+    InProcess(tokio::io::DuplexStream),
+}
+
+impl AsyncRead for Socket {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Socket::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Socket {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Socket::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Socket::Unix(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Socket::Unix(stream) => stream.is_write_vectored(),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Socket::Unix(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Socket::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+            #[cfg(feature = "server")]
+            Socket::InProcess(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// [Socket] wrapper used by every connection of a [crate::ClientUnix].
+#[derive(Debug)]
+pub(crate) struct ClientStream {
+    inner: Socket,
+    head_recorder: Option<Arc<Mutex<HeadRecorder>>>,
+    wire_recorder: Option<Arc<Mutex<WireRecorder>>>,
+    in_flight: Arc<AtomicBool>,
+    read_timer: IdleTimer,
+    write_timer: IdleTimer,
+    read_throttle: Throttle,
+    write_throttle: Throttle,
+    last_write: Arc<Mutex<Option<Instant>>>,
+    stats: Arc<StatsRecorder>,
+}
+
+impl ClientStream {
+    pub(crate) fn new(
+        inner: Socket,
+        head_recorder: Option<Arc<Mutex<HeadRecorder>>>,
+        wire_recorder: Option<Arc<Mutex<WireRecorder>>>,
+        in_flight: Arc<AtomicBool>,
+        config: &ClientConfig,
+        bandwidths: Vec<Arc<Bandwidth>>,
+        last_write: Arc<Mutex<Option<Instant>>>,
+    ) -> Self {
+        ClientStream {
+            inner,
+            head_recorder,
+            wire_recorder,
+            in_flight,
+            read_timer: IdleTimer::new(config.read_timeout),
+            write_timer: IdleTimer::new(config.write_timeout),
+            read_throttle: Throttle::new(bandwidths.clone()),
+            write_throttle: Throttle::new(bandwidths),
+            last_write,
+            stats: config.stats.clone(),
+        }
+    }
+}
+
+impl AsyncRead for ClientStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.read_throttle.poll_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let filled_before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+        // Idle connections are always waiting for a read, only time it out during a request.
+        if poll.is_pending() {
+            return match self.in_flight.load(Ordering::Acquire) {
+                true => self.read_timer.poll_elapsed(cx, "read"),
+                false => {
+                    self.read_timer.reset();
+                    Poll::Pending
+                }
+            };
+        }
+        self.read_timer.reset();
+        let read = buf.filled().len() - filled_before;
+        self.read_throttle.charge(read);
+        self.stats.received(read);
+
+        if let (Poll::Ready(Ok(())), Some(head_recorder)) = (&poll, &self.head_recorder)
+            && let Ok(mut head_recorder) = head_recorder.lock()
+        {
+            head_recorder.record(&buf.filled()[filled_before..]);
+        }
+        if let (Poll::Ready(Ok(())), Some(wire_recorder)) = (&poll, &self.wire_recorder)
+            && let Ok(wire_recorder) = wire_recorder.lock()
+        {
+            wire_recorder.record(&buf.filled()[filled_before..]);
+        }
+        poll
+    }
+}
+
+impl ClientStream {
+    fn poll_write_timer(
+        &mut self,
+        cx: &mut Context<'_>,
+        poll: Poll<io::Result<usize>>,
+    ) -> Poll<io::Result<usize>> {
+        if poll.is_pending() {
+            return self.write_timer.poll_elapsed(cx, "write").map_ok(|_| 0);
+        }
+        self.write_timer.reset();
+        if let Poll::Ready(Ok(written)) = poll {
+            self.write_throttle.charge(written);
+            self.stats.sent(written);
+            if written > 0
+                && let Ok(mut last_write) = self.last_write.lock()
+            {
+                *last_write = Some(Instant::now());
+            }
+        }
+        poll
+    }
+}
+
+impl AsyncWrite for ClientStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.write_throttle.poll_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        self.poll_write_timer(cx, poll)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        if self.write_throttle.poll_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let poll = Pin::new(&mut self.inner).poll_write_vectored(cx, bufs);
+        self.poll_write_timer(cx, poll)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_recorder_split_reads() {
+        let mut head_recorder = HeadRecorder::default();
+        head_recorder.record(b"HTTP/1.1 200 OK\r\nX-Nolan: v\r");
+        assert_eq!(head_recorder.take(), None);
+
+        head_recorder.record(b"\n\r\nbody");
+        assert_eq!(
+            head_recorder.take(),
+            Some(b"HTTP/1.1 200 OK\r\nX-Nolan: v\r\n\r\n".to_vec())
+        );
+    }
+
+    #[test]
+    fn head_recorder_skip_informational() {
+        let mut head_recorder = HeadRecorder::default();
+        head_recorder.record(b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n");
+        assert_eq!(
+            head_recorder.take(),
+            Some(b"HTTP/1.1 204 No Content\r\n\r\n".to_vec())
+        );
+    }
+
+    #[test]
+    fn head_recorder_stop_after_upgrade() {
+        let mut head_recorder = HeadRecorder::default();
+        head_recorder.record(b"HTTP/1.1 101 Switching Protocols\r\n\r\ntunnel bytes");
+        head_recorder.record(b"more tunnel bytes");
+        assert_eq!(
+            head_recorder.take(),
+            Some(b"HTTP/1.1 101 Switching Protocols\r\n\r\n".to_vec())
+        );
+    }
+
+    #[test]
+    fn head_recorder_oversized() {
+        let mut head_recorder = HeadRecorder::default();
+        head_recorder.record(b"HTTP/1.1 200 OK\r\n");
+        for _ in 0..MAX_HEAD_SIZE / 1024 {
+            head_recorder.record(&[b'x'; 1024]);
+        }
+        head_recorder.record(b"\r\n\r\n");
+        assert_eq!(head_recorder.take(), None);
+        assert!(head_recorder.buffer.capacity() == 0);
+
+        head_recorder.reset();
+        head_recorder.record(b"HTTP/1.1 204 No Content\r\n\r\n");
+        assert!(head_recorder.take().is_some());
+    }
+}
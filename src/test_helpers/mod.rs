@@ -1,2 +1,3 @@
+pub mod raw_server;
 pub mod server;
 pub mod util;
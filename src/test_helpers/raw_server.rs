@@ -0,0 +1,105 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use tokio::{
+    fs::{create_dir_all, remove_file, try_exists},
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::UnixListener,
+    task::JoinHandle,
+};
+
+use crate::test_helpers::server::ErrorServer;
+
+/// Server answering every request with raw HTTP response bytes.
+#[derive(Debug)]
+pub struct RawServer {
+    server_handle: JoinHandle<ErrorServer>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl RawServer {
+    /// Answer every request with the same response.
+    pub async fn try_new(socket_path: &str, response: &'static [u8]) -> Result<Self, ErrorServer> {
+        RawServer::try_new_with(socket_path, false, move |_| response.to_vec()).await
+    }
+
+    /// Answer every request with a 200 response whose body is the raw request received.
+    pub async fn try_new_echo(socket_path: &str) -> Result<Self, ErrorServer> {
+        RawServer::try_new_with(socket_path, false, |request| {
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n",
+                request.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(request);
+            response
+        })
+        .await
+    }
+
+    /// Answer every request with the response built by `handler`, closing the connection after it when `close_after_response` is set.
+    pub async fn try_new_with(
+        socket_path: &str,
+        close_after_response: bool,
+        handler: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Result<Self, ErrorServer> {
+        let handler = Arc::new(handler);
+        let socket_path = PathBuf::from(socket_path);
+        if try_exists(socket_path.clone())
+            .await
+            .map_err(ErrorServer::CheckOldSocketExist)?
+        {
+            remove_file(socket_path.clone())
+                .await
+                .map_err(ErrorServer::RemoveOldSocket)?;
+        } else {
+            create_dir_all(socket_path.parent().ok_or(ErrorServer::SocketNoParentDir)?)
+                .await
+                .map_err(ErrorServer::CreateSocketParentDir)?;
+        }
+
+        let socket = UnixListener::bind(socket_path).map_err(ErrorServer::SocketBind)?;
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted_connections = connections.clone();
+        let server_handle = tokio::task::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = socket.accept().await else {
+                    return ErrorServer::ServerHandleError;
+                };
+                accepted_connections.fetch_add(1, Ordering::SeqCst);
+                let handler = handler.clone();
+                tokio::task::spawn(async move {
+                    let mut buffer = [0; 4096];
+                    while let Ok(read) = stream.read(&mut buffer).await {
+                        if read == 0
+                            || stream.write_all(&handler(&buffer[..read])).await.is_err()
+                            || close_after_response
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(RawServer {
+            server_handle,
+            connections,
+        })
+    }
+
+    /// Number of connections accepted since the server started.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    pub async fn abort(self) -> Option<ErrorServer> {
+        self.server_handle.abort();
+        self.server_handle.await.ok()
+    }
+}
@@ -0,0 +1,162 @@
+use crate::Body;
+use crate::{ClientUnix, error::ErrorAndResponse};
+use encoding_rs::Encoding;
+use hyper::{HeaderMap, Method, StatusCode, body::Bytes, header::CONTENT_TYPE};
+
+/// Value of a parameter of the `Content-Type` header, unquoted.
+pub(crate) fn content_type_parameter(headers: &HeaderMap, name: &str) -> Option<String> {
+    let content_type = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (parameter_name, value) = parameter.split_once('=')?;
+        match parameter_name.trim().eq_ignore_ascii_case(name) {
+            true => Some(value.trim().trim_matches('"').to_string()),
+            false => None,
+        }
+    })
+}
+
+/// Encoding named by the `charset` parameter of the `Content-Type` header.
+pub(crate) fn charset(headers: &HeaderMap) -> Option<&'static Encoding> {
+    Encoding::for_label(content_type_parameter(headers, "charset")?.as_bytes())
+}
+
+/// Transcode a body to UTF-8 with its charset, bodies without charset or already in UTF-8 are returned unchanged.
+#[cfg(feature = "json")]
+pub(crate) fn to_utf8(headers: &HeaderMap, body: Bytes) -> Bytes {
+    match charset(headers) {
+        Some(encoding) if encoding != encoding_rs::UTF_8 => {
+            Bytes::from(encoding.decode(&body).0.into_owned())
+        }
+        _ => body,
+    }
+}
+
+/// Decode a body with its charset, falling back to lossy UTF-8.
+pub(crate) fn decode_text(headers: &HeaderMap, body: Bytes) -> String {
+    match charset(headers) {
+        Some(encoding) if encoding != encoding_rs::UTF_8 => encoding.decode(&body).0.into_owned(),
+        _ => String::from_utf8(body.into())
+            .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+impl ClientUnix {
+    /// Send a raw HTTP request and decode the response body as text.
+    ///
+    /// Same as [ClientUnix::send_request] but the body is decoded according to the `charset` of the `Content-Type` response header, invalid or missing charsets fall back to lossy UTF-8.
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    ///
+    /// pub async fn get_motd() {
+    ///     let client = ClientUnix::try_new("/tmp/unix.socket")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, motd) = client
+    ///         .send_request_text("/motd", Method::GET, &[], None)
+    ///         .await
+    ///         .expect("client.send_request_text");
+    ///     println!("{}", motd);
+    /// }
+    /// ```
+    pub async fn send_request_text(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<(StatusCode, String), ErrorAndResponse> {
+        let (parts, body_response) = self
+            .send(endpoint, method, headers, body_request)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if !parts.status.is_success() {
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        Ok((parts.status, decode_text(&parts.headers, body_response)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use hyper::header::HeaderValue;
+
+    fn headers(content_type: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers
+    }
+
+    #[test]
+    fn decode_charsets() {
+        let latin1 = Bytes::from_static(b"caf\xe9");
+        assert_eq!(
+            decode_text(&headers("text/plain; charset=ISO-8859-1"), latin1.clone()),
+            "café"
+        );
+        assert_eq!(
+            decode_text(&headers("text/plain;charset=\"latin1\""), latin1.clone()),
+            "café"
+        );
+        assert_eq!(
+            decode_text(&headers("text/plain; charset=nope"), latin1.clone()),
+            "caf\u{FFFD}"
+        );
+        assert_eq!(
+            decode_text(&HeaderMap::new(), Bytes::from_static("café".as_bytes())),
+            "café"
+        );
+    }
+
+    #[tokio::test]
+    async fn send_request_text() {
+        let socket_path = make_socket_path_test("text", "send_request_text");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-type: text/plain; charset=windows-1252\r\ncontent-length: 5\r\n\r\n\x80 caf",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, response) = client
+            .send_request_text("/nolanv", Method::GET, &[], None)
+            .await
+            .expect("client.send_request_text");
+
+        assert_eq!(status_code, StatusCode::OK);
+        assert_eq!(response, "€ caf");
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn send_request_json_latin1() {
+        let socket_path = make_socket_path_test("text", "send_request_json_latin1");
+        let _server = RawServer::try_new(
+            &socket_path,
+            b"HTTP/1.1 200 OK\r\ncontent-type: application/json; charset=ISO-8859-1\r\ncontent-length: 16\r\n\r\n{\"name\": \"caf\xe9\"}",
+        )
+        .await
+        .expect("RawServer::try_new");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (_, response) = client
+            .send_request_json::<(), serde_json::Value, serde_json::Value>(
+                "/nolanv",
+                Method::GET,
+                &[],
+                None,
+            )
+            .await
+            .expect("client.send_request_json");
+        assert_eq!(response, serde_json::json!({"name": "café"}));
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep, sleep_until};
+
+/// Token bucket of bytes, the transferred bytes are charged after the fact so a read or write is never split.
+#[derive(Debug)]
+pub(crate) struct Bandwidth {
+    bytes_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bandwidth {
+    pub(crate) fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second as f64;
+        // Allow bursts of 100ms worth of bytes.
+        let burst = (bytes_per_second / 10.0).max(1.0);
+        Bandwidth {
+            bytes_per_second,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Instant at which the bytes already transferred are paid back, [None] when data can flow.
+    fn blocked_until(&self) -> Option<Instant> {
+        let mut bucket = self.bucket.lock().ok()?;
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.bytes_per_second).min(self.burst);
+        bucket.refilled_at = now;
+
+        (bucket.tokens < 0.0)
+            .then(|| now + Duration::from_secs_f64(-bucket.tokens / self.bytes_per_second))
+    }
+
+    fn charge(&self, bytes: usize) {
+        if let Ok(mut bucket) = self.bucket.lock() {
+            bucket.tokens -= bytes as f64;
+        }
+    }
+}
+
+/// Bandwidth limits applied to one direction of a stream.
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    bandwidths: Vec<Arc<Bandwidth>>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Throttle {
+    pub(crate) fn new(bandwidths: Vec<Arc<Bandwidth>>) -> Self {
+        Throttle {
+            bandwidths,
+            sleep: None,
+        }
+    }
+
+    /// Wait until every limit allows data to flow again.
+    pub(crate) fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            if let Some(sleep) = &mut self.sleep {
+                match sleep.as_mut().poll(cx) {
+                    Poll::Ready(()) => self.sleep = None,
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            let blocked_until = self
+                .bandwidths
+                .iter()
+                .filter_map(|bandwidth| bandwidth.blocked_until())
+                .max();
+            match blocked_until {
+                Some(deadline) => self.sleep = Some(Box::pin(sleep_until(deadline))),
+                None => return Poll::Ready(()),
+            }
+        }
+    }
+
+    pub(crate) fn charge(&self, bytes: usize) {
+        for bandwidth in &self.bandwidths {
+            bandwidth.charge(bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn pay_back_debt() {
+        let bandwidth = Bandwidth::new(1000);
+        assert_eq!(bandwidth.blocked_until(), None);
+
+        bandwidth.charge(600);
+        let blocked_until = bandwidth.blocked_until().expect("blocked_until");
+        assert_eq!(blocked_until - Instant::now(), Duration::from_millis(500));
+
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(bandwidth.blocked_until(), None);
+    }
+}
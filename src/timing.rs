@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+/// Durations of the steps of a request, returned by [crate::Response::timing].
+///
+/// Only the last attempt is measured when the request is retried, see [crate::Response::elapsed] for the whole duration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// Waiting for the rate limiter, the in flight limit and a ready connection, including reconnecting.
+    pub queue: Duration,
+    /// Writing the request on the socket.
+    pub write: Duration,
+    /// From the start of the write until the response head is received.
+    pub time_to_first_byte: Duration,
+    /// Reading the response body.
+    pub body: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.queue + self.time_to_first_byte + self.body
+    }
+}
@@ -0,0 +1,80 @@
+use hyper::{
+    HeaderMap,
+    header::{HeaderName, HeaderValue},
+};
+use opentelemetry::{
+    Context, global,
+    propagation::{Injector, TextMapPropagator},
+};
+
+/// Insert the injected fields in the request headers, without replacing the ones given by the caller.
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) && !self.0.contains_key(&name)
+        {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Inject the current OpenTelemetry context (`traceparent`, `tracestate`, ...) with the global propagator.
+pub(crate) fn inject(headers: &mut HeaderMap) {
+    global::get_text_map_propagator(|propagator| {
+        inject_with(propagator, &Context::current(), headers)
+    });
+}
+
+fn inject_with(propagator: &dyn TextMapPropagator, context: &Context, headers: &mut HeaderMap) {
+    propagator.inject_context(context, &mut HeaderInjector(headers));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::propagation::{Extractor, text_map_propagator::FieldIter};
+
+    #[derive(Debug)]
+    struct TestPropagator;
+
+    impl TextMapPropagator for TestPropagator {
+        fn inject_context(&self, _: &Context, injector: &mut dyn Injector) {
+            injector.set(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".into(),
+            );
+            injector.set("tracestate", "nolanv=v".into());
+        }
+
+        fn extract_with_context(&self, context: &Context, _: &dyn Extractor) -> Context {
+            context.clone()
+        }
+
+        fn fields(&self) -> FieldIter<'_> {
+            FieldIter::new(&[])
+        }
+    }
+
+    #[test]
+    fn inject_keep_caller_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("tracestate", HeaderValue::from_static("caller=1"));
+
+        inject_with(&TestPropagator, &Context::new(), &mut headers);
+
+        assert_eq!(
+            headers.get("traceparent"),
+            Some(&HeaderValue::from_static(
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+            ))
+        );
+        assert_eq!(
+            headers.get("tracestate"),
+            Some(&HeaderValue::from_static("caller=1"))
+        );
+    }
+}
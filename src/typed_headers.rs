@@ -0,0 +1,98 @@
+use crate::Response;
+use headers::{Header, HeaderMapExt};
+use hyper::HeaderMap;
+
+/// Headers of a request built from the typed headers of the `headers` crate **(feature = typed-headers)**
+///
+/// [TypedHeaders::pairs] gives them as the name and value pairs taken by every request method of [crate::ClientUnix].
+/// # Example
+/// ```rust
+/// use headers::{Authorization, ETag, IfMatch};
+/// use http_client_unix_domain_socket::{ClientUnix, Method, TypedHeaders};
+///
+/// pub async fn delete_if_unchanged(etag: ETag) {
+///     let client = ClientUnix::try_new("/tmp/unix.socket")
+///         .await
+///         .expect("ClientUnix::try_new");
+///     let headers = TypedHeaders::new()
+///         .with(Authorization::bearer("t0k3n").expect("Authorization::bearer"))
+///         .with(IfMatch::from(etag));
+///
+///     client
+///         .send_request("/nolanv", Method::DELETE, &headers.pairs(), None)
+///         .await
+///         .expect("client.send_request");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TypedHeaders(HeaderMap);
+
+impl TypedHeaders {
+    pub fn new() -> Self {
+        TypedHeaders::default()
+    }
+
+    /// Add `header`, replacing the previous value of the same header.
+    pub fn with<H: Header>(mut self, header: H) -> Self {
+        self.0.typed_insert(header);
+        self
+    }
+
+    /// Name and value of each header, values which aren't valid UTF-8 are skipped.
+    pub fn pairs(&self) -> Vec<(&str, &str)> {
+        self.0
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+            .collect()
+    }
+
+    pub fn header_map(&self) -> &HeaderMap {
+        &self.0
+    }
+}
+
+impl<T> Response<T> {
+    /// Typed value of a response header, [None] when it is missing or invalid **(feature = typed-headers)**
+    pub fn typed_header<H: Header>(&self) -> Option<H> {
+        self.headers().typed_get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientUnix, Method,
+        test_helpers::{raw_server::RawServer, util::*},
+    };
+    use headers::{ContentLength, ContentType, ETag, IfNoneMatch};
+
+    #[tokio::test]
+    async fn typed_headers() {
+        let socket_path = make_socket_path_test("typed_headers", "typed_headers");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let etag: ETag = "\"v42\"".parse().expect("ETag");
+        let headers = TypedHeaders::new()
+            .with(ContentType::json())
+            .with(IfNoneMatch::from(etag));
+        let response = client
+            .send_request_full("/nolanv", Method::GET, &headers.pairs(), None)
+            .await
+            .expect("client.send_request_full");
+
+        let request = String::from_utf8_lossy(response.body()).to_string();
+        assert!(request.contains("content-type: application/json\r\n"));
+        assert!(request.contains("if-none-match: \"v42\"\r\n"));
+        assert_eq!(
+            response.typed_header::<ContentLength>(),
+            Some(ContentLength(request.len() as u64))
+        );
+        assert_eq!(response.typed_header::<ETag>(), None);
+    }
+}
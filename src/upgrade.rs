@@ -0,0 +1,262 @@
+use crate::Body;
+use crate::{ClientUnix, Error, OriginalHeaders, body::ResponseBody, error::ErrorAndResponse};
+use hyper::{
+    Method, Response, StatusCode, Uri,
+    header::{HOST, HeaderValue},
+    http::{
+        Extensions,
+        response::Parts,
+        uri::{self, Authority},
+    },
+    upgrade::Upgraded,
+};
+use hyper_util::rt::TokioIo;
+
+impl ClientUnix {
+    /// Send an HTTP upgrade request and return the upgraded connection.
+    ///
+    /// The `Upgrade` and `Connection` headers must be given by the caller, any protocol can be negotiated. Once the server answered `101 Switching Protocols`, the connection is taken out of the pool and returned as a raw IO implementing tokio's [AsyncRead](tokio::io::AsyncRead) and [AsyncWrite](tokio::io::AsyncWrite), along with the response headers. Any other status is returned as [ErrorAndResponse::ResponseUnsuccessful].
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::{ClientUnix, Method};
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// pub async fn attach() {
+    ///     let client = ClientUnix::try_new("/var/run/docker.sock")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, _, mut io) = client
+    ///         .send_request_upgrade(
+    ///             "/containers/nolanv/attach?stream=1&stdin=1",
+    ///             Method::POST,
+    ///             &[("Upgrade", "tcp"), ("Connection", "Upgrade")],
+    ///             None,
+    ///         )
+    ///         .await
+    ///         .expect("client.send_request_upgrade");
+    ///     io.write_all(b"ls\n").await.expect("io.write_all");
+    /// }
+    /// ```
+    pub async fn send_request_upgrade(
+        &self,
+        endpoint: &str,
+        method: Method,
+        headers: &[(&str, &str)],
+        body_request: Option<Body>,
+    ) -> Result<(StatusCode, OriginalHeaders, TokioIo<Upgraded>), ErrorAndResponse> {
+        let (parts, body_response) = self
+            .start(endpoint, method, headers, body_request, &Extensions::new())
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+
+        if parts.status != StatusCode::SWITCHING_PROTOCOLS {
+            let body_response = body_response
+                .collect()
+                .await
+                .map_err(ErrorAndResponse::InternalError)?;
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        upgrade(parts, body_response).await
+    }
+
+    /// Open a tunnel to `authority` through the HTTP proxy listening on the socket.
+    ///
+    /// A `CONNECT` request is sent with `authority` (e.g. `"example.com:443"`) as target and `Host` header, along with `headers` such as `Proxy-Authorization`. Once the proxy answered with a successful status, the connection is taken out of the pool and returned as a raw IO implementing tokio's [AsyncRead](tokio::io::AsyncRead) and [AsyncWrite](tokio::io::AsyncWrite), along with the response headers. The IO can be used as is, wrapped in TLS, or wrapped in another [TokioIo] and handed to [hyper::client::conn::http1::handshake] to continue with HTTP. Any other status is returned as [ErrorAndResponse::ResponseUnsuccessful], and an invalid `authority` as [Error::RequestBuild].
+    /// # Example
+    /// ```rust
+    /// use http_client_unix_domain_socket::ClientUnix;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// pub async fn fetch_through_proxy() {
+    ///     let client = ClientUnix::try_new("/run/proxy.sock")
+    ///         .await
+    ///         .expect("ClientUnix::try_new");
+    ///
+    ///     let (_, _, mut io) = client
+    ///         .send_request_connect("example.com:80", &[])
+    ///         .await
+    ///         .expect("client.send_request_connect");
+    ///     io.write_all(b"GET / HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n")
+    ///         .await
+    ///         .expect("io.write_all");
+    ///     let mut response = Vec::new();
+    ///     io.read_to_end(&mut response).await.expect("io.read_to_end");
+    /// }
+    /// ```
+    pub async fn send_request_connect(
+        &self,
+        authority: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<(StatusCode, OriginalHeaders, TokioIo<Upgraded>), ErrorAndResponse> {
+        let request_build =
+            |e: hyper::http::Error| ErrorAndResponse::InternalError(Error::RequestBuild(e));
+        let mut uri = uri::Parts::default();
+        uri.authority = Some(
+            authority
+                .parse::<Authority>()
+                .map_err(|e| request_build(e.into()))?,
+        );
+        let uri = Uri::from_parts(uri).map_err(|e| request_build(e.into()))?;
+        let mut request = self
+            .build_request("/", Method::CONNECT, headers, None, &Extensions::new())
+            .map_err(ErrorAndResponse::InternalError)?;
+        *request.uri_mut() = uri;
+        if !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("host"))
+        {
+            let host = HeaderValue::from_str(authority).map_err(|e| request_build(e.into()))?;
+            request.headers_mut().insert(HOST, host);
+        }
+
+        let (parts, body_response) = self
+            .start_request(request)
+            .await
+            .map_err(ErrorAndResponse::InternalError)?;
+        if !parts.status.is_success() {
+            let body_response = body_response
+                .collect()
+                .await
+                .map_err(ErrorAndResponse::InternalError)?;
+            return Err(self.unsuccessful(&parts, body_response));
+        }
+        upgrade(parts, body_response).await
+    }
+}
+
+/// Take the connection of an upgraded response out of the pool.
+async fn upgrade(
+    mut parts: Parts,
+    body_response: ResponseBody,
+) -> Result<(StatusCode, OriginalHeaders, TokioIo<Upgraded>), ErrorAndResponse> {
+    let headers = parts
+        .extensions
+        .remove::<OriginalHeaders>()
+        .unwrap_or_else(|| OriginalHeaders::from_header_map(&parts.headers));
+    let status_code = parts.status;
+
+    // The connection task must keep running until it hands the IO over.
+    let upgraded = hyper::upgrade::on(Response::from_parts(parts, ()))
+        .await
+        .map_err(|e| ErrorAndResponse::InternalError(Error::Upgrade(e)))?;
+    drop(body_response);
+
+    Ok((status_code, headers, TokioIo::new(upgraded)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn send_request_upgrade() {
+        let socket_path = make_socket_path_test("upgrade", "send_request_upgrade");
+        let server = RawServer::try_new_with(&socket_path, false, |request| {
+            if request.starts_with(b"GET http://unix.socket/nolanv") {
+                b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: nolan\r\nConnection: Upgrade\r\n\r\n"
+                    .to_vec()
+            } else if request.starts_with(b"GET ") {
+                b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n".to_vec()
+            } else {
+                request.to_ascii_uppercase()
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, headers, mut io) = client
+            .send_request_upgrade(
+                "/nolanv",
+                Method::GET,
+                &[("Upgrade", "nolan"), ("Connection", "Upgrade")],
+                None,
+            )
+            .await
+            .expect("client.send_request_upgrade");
+        assert_eq!(status_code, StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(
+            headers.get("upgrade").map(|v| v.as_bytes()),
+            Some(&b"nolan"[..])
+        );
+
+        io.write_all(b"baguette").await.expect("io.write_all");
+        let mut buffer = [0; 8];
+        io.read_exact(&mut buffer).await.expect("io.read_exact");
+        assert_eq!(&buffer, b"BAGUETTE");
+
+        // The upgraded connection is not given back to the pool.
+        client
+            .send_request("/other", Method::GET, &[], None)
+            .await
+            .expect("client.send_request");
+        assert_eq!(server.connections(), 2);
+    }
+
+    #[tokio::test]
+    async fn send_request_connect() {
+        let socket_path = make_socket_path_test("upgrade", "send_request_connect");
+        let _server = RawServer::try_new_with(&socket_path, false, |request| {
+            if request.starts_with(b"CONNECT nolanv.example:80 HTTP/1.1\r\n") {
+                match request
+                    .windows(25)
+                    .any(|window| window.eq_ignore_ascii_case(b"host: nolanv.example:80\r\n"))
+                {
+                    true => b"HTTP/1.1 200 Connection Established\r\n\r\n".to_vec(),
+                    false => b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\n\r\n".to_vec(),
+                }
+            } else if request.starts_with(b"CONNECT ") {
+                b"HTTP/1.1 407 Proxy Authentication Required\r\ncontent-length: 0\r\n\r\n".to_vec()
+            } else {
+                // Requests sent through the tunnel.
+                b"HTTP/1.1 200 OK\r\ncontent-length: 6\r\n\r\nnolanv".to_vec()
+            }
+        })
+        .await
+        .expect("RawServer::try_new_with");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (status_code, _, io) = client
+            .send_request_connect("nolanv.example:80", &[])
+            .await
+            .expect("client.send_request_connect");
+        assert_eq!(status_code, StatusCode::OK);
+
+        // Continue with HTTP through the tunnel.
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(io))
+            .await
+            .expect("hyper::client::conn::http1::handshake");
+        tokio::task::spawn(connection);
+        let response = sender
+            .send_request(
+                hyper::Request::get("/")
+                    .header(HOST, "nolanv.example")
+                    .body(Body::default())
+                    .expect("Request::builder"),
+            )
+            .await
+            .expect("sender.send_request");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert!(matches!(
+            client.send_request_connect("other.example:80", &[]).await,
+            Err(ErrorAndResponse::ResponseUnsuccessful(
+                StatusCode::PROXY_AUTHENTICATION_REQUIRED,
+                _
+            ))
+        ));
+        assert!(matches!(
+            client
+                .send_request_connect("http://nolanv.example/", &[])
+                .await,
+            Err(ErrorAndResponse::InternalError(Error::RequestBuild(_)))
+        ));
+    }
+}
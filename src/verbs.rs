@@ -0,0 +1,172 @@
+use crate::Body;
+#[cfg(feature = "json")]
+use crate::error::ErrorAndResponseJson;
+use crate::{ClientUnix, error::ErrorAndResponse};
+use hyper::{Method, StatusCode};
+#[cfg(feature = "json")]
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Shorthands of [ClientUnix::send_request] and [ClientUnix::send_request_json] without headers.
+///
+/// # Example
+/// ```rust
+/// use http_client_unix_domain_socket::ClientUnix;
+///
+/// pub async fn get_hello() {
+///     let client = ClientUnix::try_new("/tmp/unix.socket")
+///         .await
+///         .expect("ClientUnix::try_new");
+///
+///     let (_, hello) = client.get("/nolanv").await.expect("client.get");
+///     println!("{}", String::from_utf8_lossy(&hello));
+/// }
+/// ```
+impl ClientUnix {
+    /// Send a GET request, see [ClientUnix::send_request].
+    pub async fn get(&self, endpoint: &str) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::GET, &[], None).await
+    }
+
+    /// Send a POST request, see [ClientUnix::send_request].
+    pub async fn post(
+        &self,
+        endpoint: &str,
+        body_request: Body,
+    ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::POST, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a PUT request, see [ClientUnix::send_request].
+    pub async fn put(
+        &self,
+        endpoint: &str,
+        body_request: Body,
+    ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::PUT, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a PATCH request, see [ClientUnix::send_request].
+    pub async fn patch(
+        &self,
+        endpoint: &str,
+        body_request: Body,
+    ) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::PATCH, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a DELETE request, see [ClientUnix::send_request].
+    pub async fn delete(&self, endpoint: &str) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::DELETE, &[], None).await
+    }
+
+    /// Send a HEAD request, see [ClientUnix::send_request]. The response body is always empty.
+    pub async fn head(&self, endpoint: &str) -> Result<(StatusCode, Vec<u8>), ErrorAndResponse> {
+        self.send_request(endpoint, Method::HEAD, &[], None).await
+    }
+
+    /// Send a JSON GET request, see [ClientUnix::send_request_json] **(feature = json)**
+    #[cfg(feature = "json")]
+    pub async fn get_json<OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        self.send_request_json::<(), OUT, ERR>(endpoint, Method::GET, &[], None)
+            .await
+    }
+
+    /// Send a JSON POST request, see [ClientUnix::send_request_json] **(feature = json)**
+    #[cfg(feature = "json")]
+    pub async fn post_json<IN: Serialize, OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body_request: &IN,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        self.send_request_json(endpoint, Method::POST, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a JSON PUT request, see [ClientUnix::send_request_json] **(feature = json)**
+    #[cfg(feature = "json")]
+    pub async fn put_json<IN: Serialize, OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body_request: &IN,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        self.send_request_json(endpoint, Method::PUT, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a JSON PATCH request, see [ClientUnix::send_request_json] **(feature = json)**
+    #[cfg(feature = "json")]
+    pub async fn patch_json<IN: Serialize, OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body_request: &IN,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        self.send_request_json(endpoint, Method::PATCH, &[], Some(body_request))
+            .await
+    }
+
+    /// Send a JSON DELETE request, see [ClientUnix::send_request_json] **(feature = json)**
+    #[cfg(feature = "json")]
+    pub async fn delete_json<OUT: DeserializeOwned, ERR: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<(StatusCode, OUT), ErrorAndResponseJson<ERR>> {
+        self.send_request_json::<(), OUT, ERR>(endpoint, Method::DELETE, &[], None)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{raw_server::RawServer, util::*};
+
+    #[tokio::test]
+    async fn raw_verbs() {
+        let socket_path = make_socket_path_test("verbs", "raw_verbs");
+        let _server = RawServer::try_new_echo(&socket_path)
+            .await
+            .expect("RawServer::try_new_echo");
+        let client = ClientUnix::try_new(&socket_path)
+            .await
+            .expect("ClientUnix::try_new");
+
+        let (_, response) = client.get("/nolanv").await.expect("client.get");
+        assert!(response.starts_with(b"GET http://unix.socket/nolanv HTTP/1.1\r\n"));
+
+        let (_, response) = client
+            .patch("/nolanv", Body::from("baguette"))
+            .await
+            .expect("client.patch");
+        assert!(response.starts_with(b"PATCH "));
+        assert!(response.ends_with(b"\r\n\r\nbaguette"));
+
+        let (_, response) = client.delete("/nolanv").await.expect("client.delete");
+        assert!(response.starts_with(b"DELETE "));
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn json_verbs() {
+        use serde_json::{Value, json};
+
+        let (_, client) = make_client_server("json_verbs").await;
+
+        let (_, response) = client
+            .get_json::<Value, Value>("/json/nolanv")
+            .await
+            .expect("client.get_json");
+        assert_eq!(response, json!({"hello": "nolanv"}));
+
+        let (_, response) = client
+            .post_json::<_, Value, Value>("/json", &json!({"name": "baguette"}))
+            .await
+            .expect("client.post_json");
+        assert_eq!(response, json!({"hello": "baguette"}));
+    }
+}
@@ -0,0 +1,55 @@
+use bytes::Bytes;
+use std::sync::{Arc, Mutex};
+
+/// Exact bytes received for a response, status line, headers and body as sent by the server, captured with [crate::ClientUnixBuilder::capture_wire].
+///
+/// Stored in the extensions of the response, see [crate::Response::wire_bytes]. The body is still in its transfer encoding, like chunked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WireBytes(pub Bytes);
+
+/// Bytes received for a single request, kept in the response extensions until its body is read.
+#[derive(Debug, Clone)]
+pub(crate) struct WireCapture(Arc<Mutex<Vec<u8>>>);
+
+impl WireCapture {
+    pub(crate) fn take(&self) -> WireBytes {
+        let bytes = self
+            .0
+            .lock()
+            .map(|mut bytes| std::mem::take(&mut *bytes))
+            .unwrap_or_default();
+        WireBytes(bytes.into())
+    }
+}
+
+/// Recorder of the bytes read on a connection, appending them to the capture of its current request.
+#[derive(Debug)]
+pub(crate) struct WireRecorder {
+    max_size: usize,
+    current: Option<WireCapture>,
+}
+
+impl WireRecorder {
+    pub(crate) fn new(max_size: usize) -> Self {
+        WireRecorder {
+            max_size,
+            current: None,
+        }
+    }
+
+    /// Start capturing the bytes of a new request, must be called before sending it.
+    pub(crate) fn start(&mut self) -> WireCapture {
+        let capture = WireCapture(Arc::default());
+        self.current = Some(capture.clone());
+        capture
+    }
+
+    pub(crate) fn record(&self, bytes: &[u8]) {
+        if let Some(WireCapture(capture)) = &self.current
+            && let Ok(mut capture) = capture.lock()
+        {
+            let len = bytes.len().min(self.max_size.saturating_sub(capture.len()));
+            capture.extend_from_slice(&bytes[..len]);
+        }
+    }
+}